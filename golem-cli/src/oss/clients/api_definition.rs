@@ -80,18 +80,18 @@ async fn create_or_update_api_definition<
     match action {
         Action::Import => {
             let value = decode_api_definition(definition_str.as_str(), format)?;
-            Ok(client.import_open_api_json(&value).await?)
+            Ok(client.import_open_api_json(None, &value).await?)
         }
         Action::Create => {
             let value: HttpApiDefinitionRequest =
                 decode_api_definition(definition_str.as_str(), format)?;
-            Ok(client.create_definition_json(&value).await?)
+            Ok(client.create_definition_json(None, None, None, &value).await?)
         }
         Action::Update => {
             let value: HttpApiDefinitionRequest =
                 decode_api_definition(definition_str.as_str(), format)?;
             Ok(client
-                .update_definition_json(&value.id, &value.version, &value)
+                .update_definition_json(&value.id, &value.version, None, None, None, &value)
                 .await?)
         }
     }
@@ -112,7 +112,7 @@ impl<C: golem_client::api::ApiDefinitionClient + Sync + Send> ApiDefinitionClien
 
         Ok(self
             .client
-            .list_definitions(id.map(|id| id.0.as_str()))
+            .list_definitions(id.map(|id| id.0.as_str()), None, None, None)
             .await?)
     }
 
@@ -126,7 +126,7 @@ impl<C: golem_client::api::ApiDefinitionClient + Sync + Send> ApiDefinitionClien
 
         Ok(self
             .client
-            .get_definition(id.0.as_str(), version.0.as_str())
+            .get_definition(id.0.as_str(), version.0.as_str(), None)
             .await?)
     }
 
@@ -166,7 +166,7 @@ impl<C: golem_client::api::ApiDefinitionClient + Sync + Send> ApiDefinitionClien
         info!("Deleting api definition for {}/{}", id.0, version.0);
         Ok(self
             .client
-            .delete_definition(id.0.as_str(), version.0.as_str())
+            .delete_definition(id.0.as_str(), version.0.as_str(), None)
             .await?)
     }
 }
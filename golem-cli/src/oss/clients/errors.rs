@@ -81,7 +81,9 @@ impl ResponseContentErrorMapper for ApiDefinitionError {
             ApiDefinitionError::Error401(error) => error.error,
             ApiDefinitionError::Error403(error) => error.error,
             ApiDefinitionError::Error404(error) => error.error,
-            ApiDefinitionError::Error409(error) => error.to_string(),
+            ApiDefinitionError::Error409(error) => error.error,
+            ApiDefinitionError::Error412(error) => error.error,
+            ApiDefinitionError::Error429(error) => error.error,
             ApiDefinitionError::Error500(error) => error.error,
         }
     }
@@ -94,7 +96,9 @@ impl ResponseContentErrorMapper for ApiSecurityError {
             ApiSecurityError::Error401(error) => error.error,
             ApiSecurityError::Error403(error) => error.error,
             ApiSecurityError::Error404(error) => error.error,
-            ApiSecurityError::Error409(error) => error.to_string(),
+            ApiSecurityError::Error409(error) => error.error,
+            ApiSecurityError::Error412(error) => error.error,
+            ApiSecurityError::Error429(error) => error.error,
             ApiSecurityError::Error500(error) => error.error,
         }
     }
@@ -107,7 +111,9 @@ impl ResponseContentErrorMapper for ApiDeploymentError {
             ApiDeploymentError::Error401(error) => error.error,
             ApiDeploymentError::Error403(error) => error.error,
             ApiDeploymentError::Error404(error) => error.error,
-            ApiDeploymentError::Error409(error) => error.to_string(),
+            ApiDeploymentError::Error409(error) => error.error,
+            ApiDeploymentError::Error412(error) => error.error,
+            ApiDeploymentError::Error429(error) => error.error,
             ApiDeploymentError::Error500(error) => error.error,
         }
     }
@@ -258,43 +264,52 @@ mod tests {
     use crate::oss::clients::errors::ResponseContentErrorMapper;
     use golem_client::{
         api::ApiDefinitionError,
-        model::{ErrorBody, MessagesErrorsBody, ValidationErrorsBody, WorkerServiceErrorsBody},
+        model::{
+            ApiEndpointErrorBody, MessagesErrorsBody, ValidationErrorsBody, WorkerServiceErrorsBody,
+        },
     };
 
     #[test]
     fn api_definition_error_409() {
-        let error = ApiDefinitionError::Error409("409".to_string());
+        let error = ApiDefinitionError::Error409(ApiEndpointErrorBody {
+            error: "409".to_string(),
+            code: "api_definition.already_exists".to_string(),
+        });
         assert_eq!(error.map(), "409".to_string())
     }
 
     #[test]
     fn api_definition_error_401() {
-        let error = ApiDefinitionError::Error401(ErrorBody {
+        let error = ApiDefinitionError::Error401(ApiEndpointErrorBody {
             error: "401".to_string(),
+            code: "unauthorized".to_string(),
         });
         assert_eq!(error.map(), "401".to_string())
     }
 
     #[test]
     fn api_definition_error_403() {
-        let error = ApiDefinitionError::Error403(ErrorBody {
+        let error = ApiDefinitionError::Error403(ApiEndpointErrorBody {
             error: "403".to_string(),
+            code: "forbidden".to_string(),
         });
         assert_eq!(error.map(), "403".to_string())
     }
 
     #[test]
     fn api_definition_error_404() {
-        let error = ApiDefinitionError::Error404(ErrorBody {
+        let error = ApiDefinitionError::Error404(ApiEndpointErrorBody {
             error: "404".to_string(),
+            code: "api_definition.not_found".to_string(),
         });
         assert_eq!(error.map(), "404".to_string())
     }
 
     #[test]
     fn api_definition_error_500() {
-        let error = ApiDefinitionError::Error500(ErrorBody {
+        let error = ApiDefinitionError::Error500(ApiEndpointErrorBody {
             error: "500".to_string(),
+            code: "api_definition.internal".to_string(),
         });
         assert_eq!(error.map(), "500".to_string())
     }
@@ -304,6 +319,7 @@ mod tests {
         let error =
             ApiDefinitionError::Error400(WorkerServiceErrorsBody::Messages(MessagesErrorsBody {
                 errors: vec!["400_1".to_string(), "400_2".to_string()],
+                code: "api_definition.invalid".to_string(),
             }));
         assert_eq!(error.map(), "400_1, 400_2".to_string())
     }
@@ -316,6 +332,7 @@ mod tests {
                     "Get/path/02f09a3f-1624-3b1d-8409-44eff7708208/Duplicate route".to_string(),
                     "Post/path2/02f09a3f-1624-3b1d-8409-44eff7708209/Other route".to_string(),
                 ],
+                code: "api_definition.validation_failed".to_string(),
             },
         ));
         assert_eq!(error.map(), "Get/path/02f09a3f-1624-3b1d-8409-44eff7708208/Duplicate route\nPost/path2/02f09a3f-1624-3b1d-8409-44eff7708209/Other route".to_string())
@@ -261,6 +261,7 @@ fn golem_def_with_response(
         id: id.to_string(),
         version: "0.1.0".to_string(),
         draft: true,
+        labels: None,
         security: security_id.map(|id| vec![id.to_string()]),
         routes: vec![RouteRequestData {
             method: MethodPattern::Get,
@@ -275,6 +276,9 @@ fn golem_def_with_response(
                 worker_name: Some("\"foo\"".to_string()),
                 idempotency_key: None,
                 response: Some(response),
+                timeout_ms: None,
+                component_version: None,
+                param_mappings: None,
                 allow_origin: None,
                 allow_methods: None,
                 allow_headers: None,
@@ -439,6 +443,7 @@ pub fn to_api_definition_with_type_info(
         id: request.id,
         version: request.version,
         draft: request.draft,
+        labels: request.labels.unwrap_or_default(),
         routes: request
             .routes
             .iter()
@@ -464,6 +469,9 @@ pub fn to_api_definition_with_type_info(
                         binding_type: Some(GatewayBindingType::Default),
                         cors_preflight: None,
                         response_mapping_output: Some(expected_out.clone()),
+                        timeout_ms: None,
+                        component_version: None,
+                        param_mappings: None,
                     },
                 }
             })
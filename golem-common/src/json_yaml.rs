@@ -6,8 +6,11 @@ use poem_openapi::payload::{ParsePayload, Payload};
 use poem_openapi::registry::{MetaMediaType, MetaRequest, MetaSchemaRef, Registry};
 use poem_openapi::types::{ParseFromJSON, ParseFromYAML, Type};
 use poem_openapi::{ApiExtractor, ApiExtractorType, ExtractParamOptions};
+use serde::de::DeserializeOwned;
+use std::fmt::Display;
 
-// A poem type that supports json or yaml with explicit content type
+// A poem type that supports json or yaml with explicit content type. Requests with any other
+// content type are rejected with 415 Unsupported Media Type, on every endpoint using it.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct JsonOrYaml<T>(pub T);
 
@@ -33,7 +36,7 @@ impl<T: Type> Payload for JsonOrYaml<T> {
 }
 
 // Explicit ApiExtractor than derived to help with multiple content types
-impl<'a, T: ParseFromJSON + ParseFromYAML> ApiExtractor<'a> for JsonOrYaml<T> {
+impl<'a, T: ParseFromJSON + ParseFromYAML + DeserializeOwned> ApiExtractor<'a> for JsonOrYaml<T> {
     const TYPES: &'static [ApiExtractorType] = &[ApiExtractorType::RequestObject];
     type ParamType = ();
     type ParamRawType = ();
@@ -68,7 +71,7 @@ impl<'a, T: ParseFromJSON + ParseFromYAML> ApiExtractor<'a> for JsonOrYaml<T> {
     }
 }
 
-impl<T: ParseFromYAML + ParseFromJSON> ParsePayload for JsonOrYaml<T> {
+impl<T: ParseFromYAML + ParseFromJSON + DeserializeOwned> ParsePayload for JsonOrYaml<T> {
     const IS_REQUIRED: bool = true;
 
     async fn from_request(request: &Request, body: &mut RequestBody) -> poem::Result<Self> {
@@ -78,6 +81,16 @@ impl<T: ParseFromYAML + ParseFromJSON> ParsePayload for JsonOrYaml<T> {
             .and_then(|value| value.to_str().ok())
             .unwrap_or_default();
 
+        // Checked before reading the body, as other payloads would only fail with a parse error
+        if !content_type.contains("json") && !content_type.contains("yaml") {
+            return Err(poem::Error::from_string(
+                format!(
+                    "Unsupported content type {content_type:?}, expected application/json or application/yaml"
+                ),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ));
+        }
+
         let body = body.take().map_err(|e| {
             poem::Error::from_string(
                 format!("Missing request body {}", e),
@@ -99,29 +112,51 @@ impl<T: ParseFromYAML + ParseFromJSON> ParsePayload for JsonOrYaml<T> {
                     StatusCode::BAD_REQUEST,
                 )
             })?;
-            let value =
-                T::parse_from_yaml(Some(json_data)).map_err(|err| ParseRequestPayloadError {
-                    reason: err.into_message(),
-                })?;
-            Ok(Self(value))
-        } else if content_type.contains("yaml") {
+            Ok(Self(parse_value(json_data, &bytes, true)?))
+        } else {
             let yaml_data = serde_yaml::from_slice(&bytes).map_err(|e| {
                 poem::Error::from_string(
                     format!("Failed to read YAML data {}", e),
                     StatusCode::BAD_REQUEST,
                 )
             })?;
+            Ok(Self(parse_value(yaml_data, &bytes, false)?))
+        }
+    }
+}
 
-            let value =
-                T::parse_from_yaml(Some(yaml_data)).map_err(|err| ParseRequestPayloadError {
-                    reason: err.into_message(),
-                })?;
-            Ok(Self(value))
+// Poem's parse errors don't tell which field was wrong, so a failing payload is deserialized
+// again from the body with serde to report the path of the field, such as
+// `routes[1].binding.response`. The parsed value itself is consumed by poem, without a copy.
+fn parse_value<T: ParseFromYAML + DeserializeOwned>(
+    data: serde_json::Value,
+    body: &[u8],
+    json: bool,
+) -> Result<T, ParseRequestPayloadError> {
+    T::parse_from_yaml(Some(data)).map_err(|err| {
+        let field_error = if json {
+            invalid_field::<T, _>(serde_path_to_error::deserialize(
+                &mut serde_json::Deserializer::from_slice(body),
+            ))
         } else {
-            Err(poem::Error::from_string(
-                "Unsupported content type".to_string(),
-                StatusCode::BAD_REQUEST,
+            invalid_field::<T, _>(serde_path_to_error::deserialize(
+                serde_yaml::Deserializer::from_slice(body),
             ))
+        };
+
+        ParseRequestPayloadError {
+            reason: field_error.unwrap_or_else(|| err.into_message()),
+        }
+    })
+}
+
+fn invalid_field<T, E: Display>(
+    result: Result<T, serde_path_to_error::Error<E>>,
+) -> Option<String> {
+    match result {
+        Err(err) if err.path().iter().next().is_some() => {
+            Some(format!("Invalid field {}: {}", err.path(), err.inner()))
         }
+        _ => None,
     }
 }
@@ -27,6 +27,10 @@ pub enum ShardManagerError {
     NoSourceIpForPod,
     #[error("Failed to resolve address for pod")]
     FailedAddressResolveForPod,
+    #[error("No registered pod at {0}")]
+    UnknownPod(String),
+    #[error("Invalid port {0}")]
+    InvalidPort(i32),
     #[error("Timeout")]
     Timeout,
     #[error("gRPC: error status: {0}")]
@@ -48,6 +52,8 @@ impl IsRetriableError for ShardManagerError {
         match self {
             ShardManagerError::NoSourceIpForPod => false,
             ShardManagerError::FailedAddressResolveForPod => false,
+            ShardManagerError::UnknownPod(_) => false,
+            ShardManagerError::InvalidPort(_) => false,
             ShardManagerError::Timeout => true,
             ShardManagerError::GrpcError(status) => status.is_retriable(),
             ShardManagerError::NoResult => true,
@@ -81,6 +87,14 @@ impl From<ShardManagerError> for golem::shardmanager::v1::ShardManagerError {
                 shard_manager_error::Error::Unknown,
                 "FailedAddressResolveForPod".to_string(),
             ),
+            ShardManagerError::UnknownPod(address) => error(
+                shard_manager_error::Error::InvalidRequest,
+                format!("UnknownPod: {address}"),
+            ),
+            ShardManagerError::InvalidPort(port) => error(
+                shard_manager_error::Error::InvalidRequest,
+                format!("InvalidPort: {port}"),
+            ),
             ShardManagerError::Timeout => {
                 error(shard_manager_error::Error::Timeout, "Timeout".to_string())
             }
@@ -111,6 +111,20 @@ impl ShardManagerServiceImpl {
         Ok(())
     }
 
+    // Unlike a failed health check, this is requested by the pod's owner, for example before
+    // stopping it, so that its shards are assigned to the remaining pods while it still runs
+    async fn unregister_internal(
+        &self,
+        request: golem::shardmanager::v1::UnregisterRequest,
+    ) -> Result<(), ShardManagerError> {
+        let routing_table = self.shard_management.current_snapshot().await;
+        for pod in pods_to_unregister(&routing_table, &request)? {
+            info!("Shard Manager received request to unregister pod: {}", pod);
+            self.shard_management.unregister_pod(pod).await;
+        }
+        Ok(())
+    }
+
     fn start_health_check(&self) {
         let delay = self.shard_manager_config.health_check.delay;
         let shard_management = self.shard_management.clone();
@@ -151,6 +165,27 @@ impl ShardManagerServiceImpl {
     }
 }
 
+// The registered pods matching the host and port of an unregister request, of which there can be
+// several when the same address re-registered under a different source IP or pod name
+fn pods_to_unregister(
+    routing_table: &RoutingTable,
+    request: &golem::shardmanager::v1::UnregisterRequest,
+) -> Result<Vec<Pod>, ShardManagerError> {
+    let port = u16::try_from(request.port)
+        .map_err(|_| ShardManagerError::InvalidPort(request.port))?;
+    let pods: Vec<Pod> = routing_table
+        .get_pods()
+        .into_iter()
+        .filter(|pod| pod.is_at(&request.host, port))
+        .collect();
+
+    if pods.is_empty() {
+        let address = format!("{}:{}", request.host, request.port);
+        return Err(ShardManagerError::UnknownPod(address));
+    }
+    Ok(pods)
+}
+
 #[tonic::async_trait]
 impl ShardManagerService for ShardManagerServiceImpl {
     async fn get_routing_table(
@@ -212,6 +247,40 @@ impl ShardManagerService for ShardManagerServiceImpl {
             result: Some(result),
         }))
     }
+
+    async fn unregister(
+        &self,
+        request: tonic::Request<golem::shardmanager::v1::UnregisterRequest>,
+    ) -> Result<Response<golem::shardmanager::v1::UnregisterResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "unregister",
+            host = &request.host,
+            port = &request.port.to_string(),
+        );
+
+        let response = self
+            .unregister_internal(request)
+            .instrument(record.span.clone())
+            .await;
+
+        let result = match response {
+            Ok(_) => record.succeed(golem::shardmanager::v1::unregister_response::Result::Success(
+                golem::shardmanager::v1::UnregisterSuccess {},
+            )),
+            Err(error) => {
+                let error: golem::shardmanager::v1::ShardManagerError = error.into();
+                record.fail(
+                    golem::shardmanager::v1::unregister_response::Result::Failure(error.clone()),
+                    &ShardManagerTraceErrorKind(&error),
+                )
+            }
+        };
+
+        Ok(Response::new(golem::shardmanager::v1::UnregisterResponse {
+            result: Some(result),
+        }))
+    }
 }
 
 pub async fn run(
@@ -327,3 +396,51 @@ pub async fn run(
         grpc_port,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_api_grpc::proto::golem::shardmanager::v1::UnregisterRequest;
+
+    use crate::error::ShardManagerError;
+    use crate::model::{Pod, RoutingTable};
+    use crate::pods_to_unregister;
+
+    fn routing_table() -> RoutingTable {
+        let mut routing_table = RoutingTable::new(8);
+        routing_table.add_pod(&Pod::new("pod0".to_string(), 9000));
+        routing_table.add_pod(&Pod::new("pod1".to_string(), 9001));
+        routing_table
+    }
+
+    fn request(host: &str, port: i32) -> UnregisterRequest {
+        UnregisterRequest {
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn known_pods_are_unregistered() {
+        let pods = pods_to_unregister(&routing_table(), &request("pod1", 9001));
+        assert_eq!(pods.unwrap(), vec![Pod::new("pod1".to_string(), 9001)]);
+    }
+
+    #[test]
+    fn unknown_pods_are_rejected() {
+        let unknown_host = pods_to_unregister(&routing_table(), &request("pod2", 9001));
+        let unknown_port = pods_to_unregister(&routing_table(), &request("pod1", 9000));
+        assert!(matches!(unknown_host, Err(ShardManagerError::UnknownPod(_))));
+        assert!(matches!(unknown_port, Err(ShardManagerError::UnknownPod(_))));
+    }
+
+    #[test]
+    fn ports_out_of_range_are_rejected() {
+        // Truncated to 16 bits, 74536 would be the port of pod0
+        let too_large = pods_to_unregister(&routing_table(), &request("pod0", 9000 + 65536));
+        let negative = pods_to_unregister(&routing_table(), &request("pod0", -1));
+        assert!(matches!(too_large, Err(ShardManagerError::InvalidPort(74536))));
+        assert!(matches!(negative, Err(ShardManagerError::InvalidPort(-1))));
+    }
+}
@@ -66,6 +66,11 @@ impl Pod {
             .expect("Failed to build URI")
     }
 
+    /// True if the pod registered itself with the given host and port
+    pub fn is_at(&self, host: &str, port: u16) -> bool {
+        self.host == host && self.port == port
+    }
+
     pub fn address(&self) -> Result<vec::IntoIter<SocketAddr>, std::io::Error> {
         format!("{}:{}", self.ip, self.port).to_socket_addrs()
     }
@@ -13,16 +13,24 @@
 // limitations under the License.
 
 use std::collections::HashMap;
-use std::io::{BufRead, BufReader};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::Child;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
 use std::time::Duration;
 
+use anyhow::anyhow;
+use tokio::sync::broadcast;
 use tokio::time::Instant;
+use tonic::transport::{Channel, Endpoint};
 use tracing::{debug, info, trace};
 use tracing::{error, warn, Level};
 
 use golem_api_grpc::proto::grpc::health::v1::health_check_response::ServingStatus;
+use golem_api_grpc::proto::grpc::health::v1::health_client::HealthClient;
 use golem_api_grpc::proto::grpc::health::v1::HealthCheckRequest;
 
 pub mod component_compilation_service;
@@ -40,9 +48,16 @@ pub mod worker_service;
 
 const NETWORK: &str = "golem_test_network";
 
+// Number of the first stderr lines kept by `ChildProcessLogger` to diagnose failed startups
+const STDERR_HEAD_LINES: usize = 20;
+
 pub struct ChildProcessLogger {
     _out_handle: JoinHandle<()>,
     _err_handle: JoinHandle<()>,
+    out_file: Option<Arc<Mutex<BufWriter<File>>>>,
+    err_file: Option<Arc<Mutex<BufWriter<File>>>>,
+    log_channel: Option<broadcast::Sender<String>>,
+    stderr_head: Arc<Mutex<Vec<String>>>,
 }
 
 impl ChildProcessLogger {
@@ -51,6 +66,88 @@ impl ChildProcessLogger {
         out_level: Level,
         err_level: Level,
         child: &mut Child,
+    ) -> Self {
+        Self::start(prefix, out_level, err_level, None, None, None, child)
+    }
+
+    /// Same as `log_child_process`, but also tees the raw stdout and stderr lines
+    /// of the child process into the given files.
+    pub fn log_child_process_to_file(
+        prefix: &str,
+        out_level: Level,
+        err_level: Level,
+        out_path: &Path,
+        err_path: &Path,
+        child: &mut Child,
+    ) -> std::io::Result<Self> {
+        let out_file = Arc::new(Mutex::new(BufWriter::new(File::create(out_path)?)));
+        let err_file = Arc::new(Mutex::new(BufWriter::new(File::create(err_path)?)));
+
+        Ok(Self::start(
+            prefix,
+            out_level,
+            err_level,
+            Some(out_file),
+            Some(err_file),
+            None,
+            child,
+        ))
+    }
+
+    /// Same as `log_child_process`, but also publishes every stdout and stderr line of the
+    /// child process to a broadcast channel, see `subscribe`. When `log_paths` is given, the
+    /// lines are written to those files too.
+    pub fn log_child_process_to_channel(
+        prefix: &str,
+        out_level: Level,
+        err_level: Level,
+        log_paths: Option<(&Path, &Path)>,
+        child: &mut Child,
+    ) -> std::io::Result<Self> {
+        let (out_file, err_file) = match log_paths {
+            Some((out_path, err_path)) => (
+                Some(Arc::new(Mutex::new(BufWriter::new(File::create(out_path)?)))),
+                Some(Arc::new(Mutex::new(BufWriter::new(File::create(err_path)?)))),
+            ),
+            None => (None, None),
+        };
+        let (log_channel, _) = broadcast::channel(1024);
+
+        Ok(Self::start(
+            prefix,
+            out_level,
+            err_level,
+            out_file,
+            err_file,
+            Some(log_channel),
+            child,
+        ))
+    }
+
+    /// Subscribes to the lines logged after this call, if the logger was created with
+    /// `log_child_process_to_channel`
+    pub fn subscribe(&self) -> Option<broadcast::Receiver<String>> {
+        self.log_channel.as_ref().map(|sender| sender.subscribe())
+    }
+
+    /// The first lines the child process wrote to its stderr
+    pub fn stderr_head(&self) -> Vec<String> {
+        self.stderr_head.lock().unwrap().clone()
+    }
+
+    /// True once both stdout and stderr of the child process were read to their end
+    pub fn is_finished(&self) -> bool {
+        self._out_handle.is_finished() && self._err_handle.is_finished()
+    }
+
+    fn start(
+        prefix: &str,
+        out_level: Level,
+        err_level: Level,
+        out_file: Option<Arc<Mutex<BufWriter<File>>>>,
+        err_file: Option<Arc<Mutex<BufWriter<File>>>>,
+        log_channel: Option<broadcast::Sender<String>>,
+        child: &mut Child,
     ) -> Self {
         let stdout = child
             .stdout
@@ -62,79 +159,334 @@ impl ChildProcessLogger {
             .take()
             .unwrap_or_else(|| panic!("Can't get {prefix} stderr"));
 
-        let prefix_clone = prefix.to_string();
-        let stdout_handle = std::thread::spawn(move || {
-            let reader = BufReader::new(stdout);
+        let stderr_head = Arc::new(Mutex::new(Vec::new()));
+
+        let stdout_handle = Self::forward(
+            prefix,
+            out_level,
+            stdout,
+            out_file.clone(),
+            log_channel.clone(),
+            None,
+        );
+        let stderr_handle = Self::forward(
+            prefix,
+            err_level,
+            stderr,
+            err_file.clone(),
+            log_channel.clone(),
+            Some(stderr_head.clone()),
+        );
+
+        Self {
+            _out_handle: stdout_handle,
+            _err_handle: stderr_handle,
+            out_file,
+            err_file,
+            log_channel,
+            stderr_head,
+        }
+    }
+
+    fn forward<R: Read + Send + 'static>(
+        prefix: &str,
+        level: Level,
+        source: R,
+        file: Option<Arc<Mutex<BufWriter<File>>>>,
+        log_channel: Option<broadcast::Sender<String>>,
+        head: Option<Arc<Mutex<Vec<String>>>>,
+    ) -> JoinHandle<()> {
+        let prefix = prefix.to_string();
+        std::thread::spawn(move || {
+            let reader = BufReader::new(source);
             for line in reader.lines() {
-                match out_level {
-                    Level::TRACE => trace!("{} {}", prefix_clone, line.unwrap()),
-                    Level::DEBUG => debug!("{} {}", prefix_clone, line.unwrap()),
-                    Level::INFO => info!("{} {}", prefix_clone, line.unwrap()),
-                    Level::WARN => warn!("{} {}", prefix_clone, line.unwrap()),
-                    Level::ERROR => error!("{} {}", prefix_clone, line.unwrap()),
+                let line = line.unwrap();
+                if let Some(file) = &file {
+                    let _ = writeln!(file.lock().unwrap(), "{line}");
                 }
+                if let Some(head) = &head {
+                    let mut head = head.lock().unwrap();
+                    if head.len() < STDERR_HEAD_LINES {
+                        head.push(line.clone());
+                    }
+                }
+                match line_level(&line, level) {
+                    Level::TRACE => trace!("{} {}", prefix, line),
+                    Level::DEBUG => debug!("{} {}", prefix, line),
+                    Level::INFO => info!("{} {}", prefix, line),
+                    Level::WARN => warn!("{} {}", prefix, line),
+                    Level::ERROR => error!("{} {}", prefix, line),
+                }
+                if let Some(log_channel) = &log_channel {
+                    // Fails only if there are no subscribers
+                    let _ = log_channel.send(line);
+                }
+            }
+            if let Some(file) = &file {
+                let _ = file.lock().unwrap().flush();
             }
+        })
+    }
+}
+
+/// Returns the level a child process output line should be logged at.
+///
+/// Lines starting with an `ERROR` or `WARN` severity token (optionally preceded by a
+/// timestamp, as in the default tracing format) are promoted to that level if it is more
+/// severe than `default`. Any other line is logged at `default`.
+fn line_level(line: &str, default: Level) -> Level {
+    let line = strip_ansi_escapes(line);
+    let severity = line
+        .split_whitespace()
+        .take(2)
+        .find_map(|token| match token.trim_matches(|c| c == '[' || c == ']' || c == ':') {
+            "ERROR" => Some(Level::ERROR),
+            "WARN" | "WARNING" => Some(Level::WARN),
+            _ => None,
         });
 
-        let prefix_clone = prefix.to_string();
-        let stderr_handle = std::thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                match err_level {
-                    Level::TRACE => trace!("{} {}", prefix_clone, line.unwrap()),
-                    Level::DEBUG => debug!("{} {}", prefix_clone, line.unwrap()),
-                    Level::INFO => info!("{} {}", prefix_clone, line.unwrap()),
-                    Level::WARN => warn!("{} {}", prefix_clone, line.unwrap()),
-                    Level::ERROR => error!("{} {}", prefix_clone, line.unwrap()),
+    match severity {
+        // Less verbose levels compare as smaller
+        Some(severity) if severity < default => severity,
+        _ => default,
+    }
+}
+
+pub(crate) fn strip_ansi_escapes(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
                 }
             }
-        });
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
 
-        Self {
-            _out_handle: stdout_handle,
-            _err_handle: stderr_handle,
+impl Drop for ChildProcessLogger {
+    fn drop(&mut self) {
+        for file in [&self.out_file, &self.err_file].into_iter().flatten() {
+            let _ = file.lock().unwrap().flush();
         }
     }
 }
 
+/// Waits until a line matching `predicate` arrives on a channel returned by
+/// `ChildProcessLogger::subscribe`, returning that line
+pub async fn wait_for_log_line(
+    receiver: &mut broadcast::Receiver<String>,
+    predicate: impl Fn(&str) -> bool,
+    timeout: Duration,
+) -> crate::Result<String> {
+    let wait = async {
+        loop {
+            match receiver.recv().await {
+                Ok(line) if predicate(&line) => break Ok(line),
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Log subscriber lagged behind, skipped {skipped} lines");
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    break Err(anyhow!("Log channel closed before a matching line arrived"));
+                }
+            }
+        }
+    };
+
+    tokio::time::timeout(timeout, wait)
+        .await
+        .map_err(|_| anyhow!("No matching log line arrived in {}s", timeout.as_secs()))?
+}
+
 pub async fn wait_for_startup_grpc(host: &str, grpc_port: u16, name: &str, timeout: Duration) {
+    if let Err(err) = try_wait_for_startup_grpc(host, grpc_port, name, timeout).await {
+        panic!("{err}");
+    }
+}
+
+/// Same as `wait_for_startup_grpc`, but returns an error instead of panicking on timeout
+pub async fn try_wait_for_startup_grpc(
+    host: &str,
+    grpc_port: u16,
+    name: &str,
+    timeout: Duration,
+) -> crate::Result<()> {
     info!(
         "Waiting for {name} start on host {host}:{grpc_port}, timeout: {}s",
         timeout.as_secs()
     );
     let start = Instant::now();
     loop {
-        let success =
-            match golem_api_grpc::proto::grpc::health::v1::health_client::HealthClient::connect(
-                format!("http://{host}:{grpc_port}"),
-            )
-            .await
-            {
-                Ok(mut client) => match client
-                    .check(HealthCheckRequest {
-                        service: "".to_string(),
-                    })
-                    .await
-                {
-                    Ok(response) => response.into_inner().status == ServingStatus::Serving as i32,
-                    Err(err) => {
-                        debug!("Health request for {name} returned with an error: {err:?}");
-                        false
-                    }
-                },
-                Err(_) => false,
-            };
+        let success = is_serving_grpc(host, grpc_port, name).await;
         if success {
-            break;
+            break Ok(());
         } else {
             if start.elapsed() > timeout {
-                panic!("Failed to verify that {name} is running");
+                break Err(anyhow!("Failed to verify that {name} is running"));
             }
             tokio::time::sleep(Duration::from_secs(2)).await;
         }
     }
 }
 
+/// Performs a single gRPC health check, returning whether the service reports `Serving`
+pub async fn is_serving_grpc(host: &str, grpc_port: u16, name: &str) -> bool {
+    match Endpoint::new(format!("http://{host}:{grpc_port}")) {
+        Ok(endpoint) => match endpoint.connect().await {
+            Ok(channel) => is_serving_grpc_channel(channel, name).await,
+            Err(_) => false,
+        },
+        Err(_) => false,
+    }
+}
+
+/// Same as `is_serving_grpc`, but over an already established channel (e.g. a TLS one)
+pub async fn is_serving_grpc_channel(channel: Channel, name: &str) -> bool {
+    match HealthClient::new(channel)
+        .check(HealthCheckRequest {
+            service: "".to_string(),
+        })
+        .await
+    {
+        Ok(response) => response.into_inner().status == ServingStatus::Serving as i32,
+        Err(err) => {
+            debug!("Health request for {name} returned with an error: {err:?}");
+            false
+        }
+    }
+}
+
+/// Lock file claiming a port for the current process, so that test harnesses running at the
+/// same time don't start services on the same ports. The file is removed when dropped.
+pub struct PortLock {
+    path: PathBuf,
+}
+
+impl PortLock {
+    /// Claims `port` with a lock file in `directory`, waiting at most `timeout` for another
+    /// process to release it. Locks left behind by processes which are no longer running are
+    /// taken over.
+    pub async fn acquire(directory: &Path, port: u16, timeout: Duration) -> crate::Result<Self> {
+        let path = directory.join(format!("golem-test-port-{port}.lock"));
+        let takeover_path = directory.join(format!("golem-test-port-{port}.lock.takeover"));
+        let pid_file = PidFile::create(directory, port)?;
+        let start = Instant::now();
+        loop {
+            match std::fs::hard_link(&pid_file.path, &path) {
+                Ok(()) => return Ok(Self { path }),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                    let owner = lock_owner(&path);
+                    if let Some(pid) = owner.filter(|pid| !is_process_running(*pid)) {
+                        if Self::remove_stale(&path, &takeover_path, &pid_file, pid)? {
+                            continue;
+                        }
+                    }
+
+                    if start.elapsed() >= timeout {
+                        let owner = owner.map_or("unknown".to_string(), |pid| pid.to_string());
+                        return Err(anyhow!(
+                            "Port {port} is already used by another test harness \
+                            (process {owner}), remove {path:?} if that is no longer running"
+                        ));
+                    }
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    // A stale lock is only removed while holding its takeover lock, and only if it still belongs
+    // to the stale process, so that of the processes taking it over at once none can remove the
+    // lock another one created in the meantime. Returns false if another process is taking over.
+    // A takeover lock left behind by a process which died while holding it is not removed.
+    fn remove_stale(
+        path: &Path,
+        takeover_path: &Path,
+        pid_file: &PidFile,
+        stale_pid: u32,
+    ) -> crate::Result<bool> {
+        match std::fs::hard_link(&pid_file.path, takeover_path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => return Ok(false),
+            Err(err) => return Err(err.into()),
+        }
+
+        let removed = if lock_owner(path) == Some(stale_pid) {
+            warn!("Taking over stale lock {path:?} from process {stale_pid}");
+            match std::fs::remove_file(path) {
+                Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            }
+        } else {
+            Ok(())
+        };
+        let _ = std::fs::remove_file(takeover_path);
+        removed?;
+        Ok(true)
+    }
+}
+
+// The lock files are hard links to this file, so they are never seen without the PID in them.
+// It is unique to the lock being acquired, and removed once that is done.
+struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    fn create(directory: &Path, port: u16) -> crate::Result<Self> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let pid = std::process::id();
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let path = directory.join(format!("golem-test-port-{port}.{pid}-{id}.pid"));
+
+        let mut file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+        let pid_file = Self { path };
+        write!(file, "{pid}")?;
+        Ok(pid_file)
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn lock_owner(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|pid| pid.trim().parse::<u32>().ok())
+}
+
+impl Drop for PortLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// Sending no signal only checks whether the process exists. A process of another user can't be
+// signalled, but it is running too.
+#[cfg(unix)]
+fn is_process_running(pid: u32) -> bool {
+    match nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid as i32), None) {
+        Ok(()) => true,
+        Err(err) => err == nix::errno::Errno::EPERM,
+    }
+}
+
+// Elsewhere every lock is assumed to be held
+#[cfg(not(unix))]
+fn is_process_running(_pid: u32) -> bool {
+    true
+}
+
 struct EnvVarBuilder {
     env_vars: HashMap<String, String>,
 }
@@ -204,3 +556,151 @@ impl EnvVarBuilder {
 
 #[derive(Debug, Clone, Copy)]
 pub struct GolemEnvVars();
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::components::{line_level, strip_ansi_escapes, PortLock};
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+    use tracing::Level;
+
+    fn lock_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir()
+            .join(format!("golem-test-port-locks-{}", std::process::id()))
+            .join(name);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    async fn port_lock_is_released_on_drop() {
+        let directory = lock_directory("acquire");
+        let path = directory.join("golem-test-port-1000.lock");
+
+        let lock = PortLock::acquire(&directory, 1000, Duration::ZERO)
+            .await
+            .unwrap();
+        let owner = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(owner, std::process::id().to_string());
+        drop(lock);
+        assert!(!path.exists());
+
+        let lock = PortLock::acquire(&directory, 1000, Duration::ZERO).await;
+        assert!(lock.is_ok());
+    }
+
+    #[test]
+    async fn port_lock_times_out_while_held() {
+        let directory = lock_directory("timeout");
+        let _lock = PortLock::acquire(&directory, 1000, Duration::ZERO)
+            .await
+            .unwrap();
+
+        let start = Instant::now();
+        let timeout = Duration::from_millis(300);
+        let result = PortLock::acquire(&directory, 1000, timeout).await;
+        assert!(result.is_err());
+        assert!(start.elapsed() >= timeout);
+
+        let other_port = PortLock::acquire(&directory, 1001, Duration::ZERO).await;
+        assert!(other_port.is_ok());
+    }
+
+    // The PID of a process which exited and was waited for is not running
+    #[cfg(unix)]
+    fn stale_pid() -> u32 {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        child.id()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    async fn stale_port_lock_is_taken_over() {
+        let directory = lock_directory("stale");
+        let path = directory.join("golem-test-port-1000.lock");
+        std::fs::write(&path, stale_pid().to_string()).unwrap();
+
+        let _lock = PortLock::acquire(&directory, 1000, Duration::ZERO)
+            .await
+            .unwrap();
+        let owner = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(owner, std::process::id().to_string());
+        assert!(!directory.join("golem-test-port-1000.lock.takeover").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stale_port_lock_is_taken_over_once() {
+        let directory = lock_directory("stale-concurrent");
+        std::fs::write(
+            directory.join("golem-test-port-1000.lock"),
+            stale_pid().to_string(),
+        )
+        .unwrap();
+
+        // A zero timeout never sleeps, so the futures can be run on plain threads
+        let barrier = std::sync::Barrier::new(8);
+        let acquired = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        futures::executor::block_on(PortLock::acquire(
+                            &directory,
+                            1000,
+                            Duration::ZERO,
+                        ))
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .filter_map(Result::ok)
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(acquired.len(), 1);
+    }
+
+    #[test]
+    fn severe_lines_are_promoted() {
+        let lines = [
+            ("2025-01-01T10:00:00.123456Z ERROR golem: failed", Level::ERROR),
+            ("2025-01-01T10:00:00.123456Z  WARN golem: slow", Level::WARN),
+            ("ERROR: failed", Level::ERROR),
+            ("[WARNING] slow", Level::WARN),
+            ("2025-01-01T10:00:00.123456Z  INFO golem: started", Level::INFO),
+            ("2025-01-01T10:00:00.123456Z  INFO golem: no ERROR here", Level::INFO),
+            ("", Level::INFO),
+        ];
+        for (line, level) in lines {
+            assert_eq!(line_level(line, Level::INFO), level, "{line}");
+        }
+    }
+
+    #[test]
+    fn lines_are_not_demoted() {
+        assert_eq!(line_level("WARN slow", Level::ERROR), Level::ERROR);
+        assert_eq!(line_level("INFO started", Level::WARN), Level::WARN);
+    }
+
+    #[test]
+    fn colored_lines_are_promoted() {
+        let line = "\x1b[2m2025-01-01T10:00:00.123456Z\x1b[0m \x1b[31mERROR\x1b[0m golem: failed";
+        assert_eq!(line_level(line, Level::DEBUG), Level::ERROR);
+    }
+
+    #[test]
+    fn ansi_escapes_are_stripped() {
+        let colored = "\x1b[2m2025-01-01T10:00:00Z\x1b[0m \x1b[1;33m WARN\x1b[0m slow";
+        let stripped = strip_ansi_escapes(colored);
+        assert_eq!(stripped, "2025-01-01T10:00:00Z  WARN slow");
+        assert_eq!(strip_ansi_escapes("plain line"), "plain line");
+        // An escape sequence cut off at the end of the line is dropped
+        assert_eq!(strip_ansi_escapes("cut off\x1b[3"), "cut off");
+    }
+}
@@ -207,7 +207,9 @@ impl K8sWorkerExecutor {
             routing: managed_routing,
         } = Routing::create(name, Self::GRPC_PORT, namespace, routing_type).await;
 
-        wait_for_startup(&local_host, local_port, timeout).await;
+        wait_for_startup(&local_host, local_port, timeout)
+            .await
+            .expect("Failed to verify that golem-worker-executor is running");
 
         info!("Golem Worker Executor pod started");
 
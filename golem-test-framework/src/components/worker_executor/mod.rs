@@ -12,22 +12,38 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
+use anyhow::anyhow;
 use async_trait::async_trait;
+use tokio::time::Instant;
 use tonic::codec::CompressionEncoding;
 use tonic::transport::{Channel, Endpoint};
-use tracing::Level;
+use tracing::{debug, Level};
 
+use golem_api_grpc::proto::golem::component::v1::{
+    get_components_response, GetComponentsRequest, GetComponentsResponse,
+};
+use golem_api_grpc::proto::golem::shardmanager::v1::{
+    get_routing_table_response, unregister_response, GetRoutingTableRequest,
+    GetRoutingTableResponse, UnregisterRequest, UnregisterResponse,
+};
+use golem_api_grpc::proto::golem::shardmanager::{Pod, ShardId};
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
+use golem_api_grpc::proto::golem::workerexecutor::v1::{
+    get_running_workers_metadata_response, GetRunningWorkersMetadataRequest,
+    GetRunningWorkersMetadataResponse,
+};
+use golem_common::model::ComponentId;
 
 use crate::components::component_service::ComponentService;
 use crate::components::redis::Redis;
 use crate::components::shard_manager::ShardManager;
 use crate::components::worker_service::WorkerService;
-use crate::components::{wait_for_startup_grpc, EnvVarBuilder, GolemEnvVars};
+use crate::components::{try_wait_for_startup_grpc, EnvVarBuilder, GolemEnvVars};
 
 pub mod docker;
 pub mod k8s;
@@ -54,8 +70,214 @@ pub trait WorkerExecutor {
         self.private_grpc_port()
     }
 
+    /// Files the output of the executor is captured in, if it is written to any
+    fn log_files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
     async fn kill(&self);
     async fn restart(&self);
+
+    /// Polls the shard manager until it assigns at least one shard to this executor. An executor
+    /// can already be serving gRPC requests before that, but it can't run any workers yet.
+    async fn wait_for_shard_assignment(
+        &self,
+        shard_manager: &(dyn ShardManager + Send + Sync),
+        timeout: Duration,
+    ) -> crate::Result<()> {
+        let host = self.private_host();
+        let grpc_port = self.private_grpc_port();
+
+        let start = Instant::now();
+        loop {
+            if owns_shards(shard_manager, &host, grpc_port).await {
+                break Ok(());
+            } else if start.elapsed() > timeout {
+                break Err(anyhow!(
+                    "golem-worker-executor {host}:{grpc_port} was not assigned any shards in {}s",
+                    timeout.as_secs()
+                ));
+            } else {
+                debug!("Waiting for shards to be assigned to golem-worker-executor {grpc_port}");
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        }
+    }
+
+    /// Drains the executor before it is stopped. It is unregistered from the shard manager,
+    /// which assigns its shards to the other executors, so new invocations are routed there.
+    /// The workers already running on it are not interrupted: this polls them until none of
+    /// them is running anymore, and fails if some still are after `timeout`. The running
+    /// workers are looked up for every component known by `component_service`.
+    async fn drain(
+        &self,
+        shard_manager: &(dyn ShardManager + Send + Sync),
+        component_service: &(dyn ComponentService + Send + Sync),
+        timeout: Duration,
+    ) -> crate::Result<()> {
+        let host = self.private_host();
+        let grpc_port = self.private_grpc_port();
+        unregister(shard_manager, &host, grpc_port).await?;
+
+        let mut client = self.client().await?;
+        let start = Instant::now();
+        loop {
+            let running = running_workers(&mut client, component_service).await?;
+            if running == 0 {
+                break Ok(());
+            } else if start.elapsed() > timeout {
+                break Err(anyhow!(
+                    "{running} workers were still running on golem-worker-executor \
+                     {host}:{grpc_port} after {}s",
+                    timeout.as_secs()
+                ));
+            } else {
+                debug!(
+                    "Waiting for {running} workers to finish on golem-worker-executor {grpc_port}"
+                );
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        }
+    }
+}
+
+async fn owns_shards(
+    shard_manager: &(dyn ShardManager + Send + Sync),
+    host: &str,
+    grpc_port: u16,
+) -> bool {
+    match assignments(shard_manager, host, grpc_port).await {
+        Ok(assignments) => !assignments.is_empty(),
+        Err(err) => {
+            debug!("Failed to get the routing table: {err}");
+            false
+        }
+    }
+}
+
+// The shards assigned to the executor, with the pod the shard manager knows it by
+async fn assignments(
+    shard_manager: &(dyn ShardManager + Send + Sync),
+    host: &str,
+    grpc_port: u16,
+) -> crate::Result<Vec<(ShardId, Pod)>> {
+    let response = shard_manager
+        .client()
+        .await
+        .get_routing_table(GetRoutingTableRequest {})
+        .await?
+        .into_inner();
+
+    let routing_table = match response {
+        GetRoutingTableResponse {
+            result: Some(get_routing_table_response::Result::Success(routing_table)),
+        } => routing_table,
+        response => return Err(anyhow!("Failed to get the routing table: {response:?}")),
+    };
+
+    let entries: Vec<_> = routing_table
+        .shard_assignments
+        .into_iter()
+        .filter_map(|entry| Some((entry.shard_id?, entry.pod?)))
+        .filter(|(_, pod)| pod.port == grpc_port as u32)
+        .collect();
+
+    // The host the shard manager knows an executor by can differ from its private host, so the
+    // host is only compared when executors on multiple hosts share the port (e.g. docker)
+    let single_host = entries
+        .first()
+        .is_some_and(|(_, first)| entries.iter().all(|(_, pod)| pod.host == first.host));
+
+    Ok(entries
+        .into_iter()
+        .filter(|(_, pod)| single_host || pod.host == host)
+        .collect())
+}
+
+// An executor without shards is left registered, as there is nothing to unassign from it
+async fn unregister(
+    shard_manager: &(dyn ShardManager + Send + Sync),
+    host: &str,
+    grpc_port: u16,
+) -> crate::Result<()> {
+    let pods: HashSet<(String, u32)> = assignments(shard_manager, host, grpc_port)
+        .await?
+        .into_iter()
+        .map(|(_, pod)| (pod.host, pod.port))
+        .collect();
+
+    for (pod_host, pod_port) in pods {
+        debug!("Unregistering golem-worker-executor {pod_host}:{pod_port} from the shard manager");
+        let response = shard_manager
+            .client()
+            .await
+            .unregister(UnregisterRequest {
+                host: pod_host,
+                port: pod_port as i32,
+            })
+            .await?
+            .into_inner();
+
+        match response {
+            UnregisterResponse {
+                result: Some(unregister_response::Result::Success(_)),
+            } => {}
+            response => {
+                return Err(anyhow!(
+                    "Failed to unregister golem-worker-executor {host}:{grpc_port}: {response:?}"
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn running_workers(
+    client: &mut WorkerExecutorClient<Channel>,
+    component_service: &(dyn ComponentService + Send + Sync),
+) -> crate::Result<usize> {
+    let response = component_service
+        .client()
+        .await
+        .get_components(GetComponentsRequest {
+            project_id: None,
+            component_name: None,
+        })
+        .await?
+        .into_inner();
+
+    let components = match response {
+        GetComponentsResponse {
+            result: Some(get_components_response::Result::Success(result)),
+        } => result.components,
+        response => return Err(anyhow!("Failed to get the components: {response:?}")),
+    };
+
+    // Every version of a component is listed, while the workers are looked up by component id
+    let component_ids: HashSet<ComponentId> = components
+        .into_iter()
+        .filter_map(|component| component.versioned_component_id?.component_id)
+        .filter_map(|component_id| component_id.try_into().ok())
+        .collect();
+
+    let mut running = 0;
+    for component_id in component_ids {
+        let response = client
+            .get_running_workers_metadata(GetRunningWorkersMetadataRequest {
+                component_id: Some(component_id.into()),
+                filter: None,
+            })
+            .await?
+            .into_inner();
+
+        match response {
+            GetRunningWorkersMetadataResponse {
+                result: Some(get_running_workers_metadata_response::Result::Success(result)),
+            } => running += result.workers.len(),
+            response => return Err(anyhow!("Failed to get the running workers: {response:?}")),
+        }
+    }
+    Ok(running)
 }
 
 async fn new_client(host: &str, grpc_port: u16) -> crate::Result<WorkerExecutorClient<Channel>> {
@@ -75,8 +297,8 @@ fn new_client_lazy(host: &str, grpc_port: u16) -> crate::Result<WorkerExecutorCl
     .accept_compressed(CompressionEncoding::Gzip))
 }
 
-async fn wait_for_startup(host: &str, grpc_port: u16, timeout: Duration) {
-    wait_for_startup_grpc(host, grpc_port, "golem-worker-executor", timeout).await
+async fn wait_for_startup(host: &str, grpc_port: u16, timeout: Duration) -> crate::Result<()> {
+    try_wait_for_startup_grpc(host, grpc_port, "golem-worker-executor", timeout).await
 }
 
 #[async_trait]
@@ -93,6 +315,50 @@ pub trait WorkerExecutorEnvVars {
     ) -> HashMap<String, String>;
 }
 
+/// Wraps an env var provider, replacing or extending the provided variables with a fixed set
+pub struct WorkerExecutorEnvVarsWithOverrides {
+    inner: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+    overrides: HashMap<String, String>,
+}
+
+impl WorkerExecutorEnvVarsWithOverrides {
+    pub fn new(
+        inner: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+        overrides: HashMap<String, String>,
+    ) -> Self {
+        Self { inner, overrides }
+    }
+}
+
+#[async_trait]
+impl WorkerExecutorEnvVars for WorkerExecutorEnvVarsWithOverrides {
+    async fn env_vars(
+        &self,
+        http_port: u16,
+        grpc_port: u16,
+        component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+        shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
+        worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
+        redis: Arc<dyn Redis + Send + Sync + 'static>,
+        verbosity: Level,
+    ) -> HashMap<String, String> {
+        let mut env_vars = self
+            .inner
+            .env_vars(
+                http_port,
+                grpc_port,
+                component_service,
+                shard_manager,
+                worker_service,
+                redis,
+                verbosity,
+            )
+            .await;
+        env_vars.extend(self.overrides.clone());
+        env_vars
+    }
+}
+
 #[async_trait]
 impl WorkerExecutorEnvVars for GolemEnvVars {
     async fn env_vars(
@@ -17,16 +17,19 @@ use crate::components::worker_executor::{
     new_client, wait_for_startup, WorkerExecutor, WorkerExecutorEnvVars,
 };
 use crate::components::ChildProcessLogger;
+use anyhow::anyhow;
 use async_trait::async_trait;
 
 use crate::components::component_service::ComponentService;
 use crate::components::shard_manager::ShardManager;
 use crate::components::worker_service::WorkerService;
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
+use golem_common::model::RetryConfig;
+use golem_common::retries::get_delay;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tonic::transport::Channel;
 use tracing::info;
 use tracing::Level;
@@ -46,9 +49,53 @@ pub struct SpawnedWorkerExecutor {
     verbosity: Level,
     out_level: Level,
     err_level: Level,
+    extra_args: Vec<String>,
+    log_directory: Option<PathBuf>,
+    restart_backoff: Option<RestartBackoff>,
+    restart_history: Mutex<RestartHistory>,
     client: Option<WorkerExecutorClient<Channel>>,
 }
 
+/// Delays the restarts of an executor which follow the previous restart within `window`,
+/// with the exponential backoff of `retries`. When the attempts of `retries` are used up,
+/// every further restart waits for its `max_delay`.
+#[derive(Debug, Clone)]
+pub struct RestartBackoff {
+    pub retries: RetryConfig,
+    pub window: Duration,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct RestartHistory {
+    last_restart: Option<Instant>,
+    consecutive_restarts: u64,
+}
+
+impl RestartHistory {
+    /// Records a restart requested at `now` and returns how long it has to be delayed.
+    /// The restart is considered to happen once the delay elapsed.
+    pub(crate) fn record_restart(
+        &mut self,
+        restart_backoff: &RestartBackoff,
+        now: Instant,
+    ) -> Duration {
+        self.consecutive_restarts = match self.last_restart {
+            Some(last_restart) if now.duration_since(last_restart) < restart_backoff.window => {
+                self.consecutive_restarts + 1
+            }
+            _ => 0,
+        };
+
+        let delay = match self.consecutive_restarts {
+            0 => Duration::ZERO,
+            restarts => get_delay(&restart_backoff.retries, restarts)
+                .unwrap_or(restart_backoff.retries.max_delay),
+        };
+        self.last_restart = Some(now + delay);
+        delay
+    }
+}
+
 impl SpawnedWorkerExecutor {
     pub async fn new(
         env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
@@ -64,11 +111,55 @@ impl SpawnedWorkerExecutor {
         out_level: Level,
         err_level: Level,
         shared_client: bool,
+        extra_args: Vec<String>,
+        log_directory: Option<&Path>,
     ) -> Self {
+        Self::try_new(
+            env_vars,
+            executable,
+            working_directory,
+            http_port,
+            grpc_port,
+            redis,
+            component_service,
+            shard_manager,
+            worker_service,
+            verbosity,
+            out_level,
+            err_level,
+            shared_client,
+            extra_args,
+            log_directory,
+        )
+        .await
+        .expect("Failed to start golem-worker-executor")
+    }
+
+    /// Same as `new`, but returns an error when the executor cannot be started.
+    /// A process which was spawned but did not come up is killed before returning.
+    pub async fn try_new(
+        env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+        executable: &Path,
+        working_directory: &Path,
+        http_port: u16,
+        grpc_port: u16,
+        redis: Arc<dyn Redis + Send + Sync + 'static>,
+        component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+        shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
+        worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
+        verbosity: Level,
+        out_level: Level,
+        err_level: Level,
+        shared_client: bool,
+        extra_args: Vec<String>,
+        log_directory: Option<&Path>,
+    ) -> crate::Result<Self> {
         info!("Starting golem-worker-executor process");
 
         if !executable.exists() {
-            panic!("Expected to have precompiled golem-worker-executor at {executable:?}");
+            return Err(anyhow!(
+                "Expected to have precompiled golem-worker-executor at {executable:?}"
+            ));
         }
 
         let (child, logger) = Self::start(
@@ -84,10 +175,12 @@ impl SpawnedWorkerExecutor {
             verbosity,
             out_level,
             err_level,
+            &extra_args,
+            log_directory,
         )
-        .await;
+        .await?;
 
-        Self {
+        let mut worker_executor = Self {
             http_port,
             grpc_port,
             child: Arc::new(Mutex::new(Some(child))),
@@ -102,16 +195,25 @@ impl SpawnedWorkerExecutor {
             verbosity,
             out_level,
             err_level,
-            client: if shared_client {
-                Some(
-                    new_client("localhost", grpc_port)
-                        .await
-                        .expect("Failed to create client"),
-                )
-            } else {
-                None
-            },
+            extra_args,
+            log_directory: log_directory.map(Path::to_path_buf),
+            restart_backoff: None,
+            restart_history: Mutex::new(RestartHistory::default()),
+            client: None,
+        };
+
+        // Dropping the executor on failure kills the already started process
+        if shared_client {
+            worker_executor.client = Some(new_client("localhost", grpc_port).await?);
         }
+
+        Ok(worker_executor)
+    }
+
+    /// Delays the restarts following each other, which are not delayed by default
+    pub fn with_restart_backoff(mut self, restart_backoff: RestartBackoff) -> Self {
+        self.restart_backoff = Some(restart_backoff);
+        self
     }
 
     async fn start(
@@ -127,9 +229,12 @@ impl SpawnedWorkerExecutor {
         verbosity: Level,
         out_level: Level,
         err_level: Level,
-    ) -> (Child, ChildProcessLogger) {
+        extra_args: &[String],
+        log_directory: Option<&Path>,
+    ) -> crate::Result<(Child, ChildProcessLogger)> {
         let mut child = Command::new(executable)
             .current_dir(working_directory)
+            .args(extra_args)
             .envs(
                 env_vars
                     .env_vars(
@@ -147,18 +252,61 @@ impl SpawnedWorkerExecutor {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
-            .expect("Failed to start worker");
+            .map_err(|err| anyhow!("Failed to start golem-worker-executor: {err}"))?;
 
-        let logger = ChildProcessLogger::log_child_process(
-            &format!("[worker-{grpc_port}]"),
-            out_level,
-            err_level,
-            &mut child,
-        );
+        let prefix = format!("[worker-{grpc_port}]");
+        let logger = match log_directory {
+            Some(log_directory) => {
+                let (out_path, err_path) = Self::log_paths(log_directory, grpc_port);
+                match ChildProcessLogger::log_child_process_to_file(
+                    &prefix,
+                    out_level,
+                    err_level,
+                    &out_path,
+                    &err_path,
+                    &mut child,
+                ) {
+                    Ok(logger) => logger,
+                    Err(err) => {
+                        let _ = child.kill();
+                        return Err(anyhow!(
+                            "Failed to create golem-worker-executor log files: {err}"
+                        ));
+                    }
+                }
+            }
+            None => {
+                ChildProcessLogger::log_child_process(&prefix, out_level, err_level, &mut child)
+            }
+        };
+
+        let started = wait_for_startup("localhost", grpc_port, Duration::from_secs(90)).await;
+        if let Err(err) = started {
+            // Otherwise the process would keep its ports until the test run exits
+            let _ = child.kill();
+            return Err(err);
+        }
+
+        Ok((child, logger))
+    }
 
-        wait_for_startup("localhost", grpc_port, Duration::from_secs(90)).await;
+    // The files are recreated on restart, so they only contain the output since the last start
+    fn log_paths(log_directory: &Path, grpc_port: u16) -> (PathBuf, PathBuf) {
+        (
+            log_directory.join(format!("golem-worker-executor-{grpc_port}.stdout.log")),
+            log_directory.join(format!("golem-worker-executor-{grpc_port}.stderr.log")),
+        )
+    }
 
-        (child, logger)
+    // Also records the restart
+    fn restart_delay(&self) -> Option<Duration> {
+        let restart_backoff = self.restart_backoff.as_ref()?;
+        let delay = self
+            .restart_history
+            .lock()
+            .unwrap()
+            .record_restart(restart_backoff, Instant::now());
+        (!delay.is_zero()).then_some(delay)
     }
 
     fn blocking_kill(&self) {
@@ -191,11 +339,29 @@ impl WorkerExecutor for SpawnedWorkerExecutor {
         self.grpc_port
     }
 
+    fn log_files(&self) -> Vec<PathBuf> {
+        match &self.log_directory {
+            Some(log_directory) => {
+                let (out_path, err_path) = Self::log_paths(log_directory, self.grpc_port);
+                vec![out_path, err_path]
+            }
+            None => Vec::new(),
+        }
+    }
+
     async fn kill(&self) {
         self.blocking_kill();
     }
 
     async fn restart(&self) {
+        if let Some(delay) = self.restart_delay() {
+            info!(
+                "Delaying the restart of golem-worker-executor {} by {delay:?}",
+                self.grpc_port
+            );
+            tokio::time::sleep(delay).await;
+        }
+
         info!("Restarting golem-worker-executor {}", self.grpc_port);
 
         let (child, logger) = Self::start(
@@ -211,8 +377,11 @@ impl WorkerExecutor for SpawnedWorkerExecutor {
             self.verbosity,
             self.out_level,
             self.err_level,
+            &self.extra_args,
+            self.log_directory.as_deref(),
         )
-        .await;
+        .await
+        .expect("Failed to restart golem-worker-executor");
 
         let mut child_field = self.child.lock().unwrap();
         let mut logger_field = self.logger.lock().unwrap();
@@ -230,3 +399,73 @@ impl Drop for SpawnedWorkerExecutor {
         self.blocking_kill();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::components::worker_executor::spawned::{RestartBackoff, RestartHistory};
+    use golem_common::model::RetryConfig;
+    use std::time::{Duration, Instant};
+
+    fn restart_backoff() -> RestartBackoff {
+        RestartBackoff {
+            retries: RetryConfig {
+                max_attempts: 5,
+                min_delay: Duration::from_millis(100),
+                max_delay: Duration::from_secs(2),
+                multiplier: 2.0,
+                max_jitter_factor: None,
+            },
+            window: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn restarts_within_the_window_are_delayed_progressively() {
+        let restart_backoff = restart_backoff();
+        let mut history = RestartHistory::default();
+
+        let mut now = Instant::now();
+        let mut delays = Vec::new();
+        for _ in 0..7 {
+            let delay = history.record_restart(&restart_backoff, now);
+            delays.push(delay);
+            now += delay + Duration::from_millis(10);
+        }
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(100),
+                Duration::from_millis(200),
+                Duration::from_millis(400),
+                Duration::from_millis(800),
+                Duration::from_secs(2),
+                Duration::from_secs(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn restarts_after_the_window_are_not_delayed() {
+        let restart_backoff = restart_backoff();
+        let mut history = RestartHistory::default();
+
+        let now = Instant::now();
+        let first = history.record_restart(&restart_backoff, now);
+        let now = now + Duration::from_millis(10);
+        let second = history.record_restart(&restart_backoff, now);
+        assert_eq!(first, Duration::ZERO);
+        assert_eq!(second, Duration::from_millis(100));
+
+        // The window is measured from the end of the delayed restart
+        let now = now + second + restart_backoff.window;
+        let third = history.record_restart(&restart_backoff, now);
+        let now = now + Duration::from_millis(10);
+        let fourth = history.record_restart(&restart_backoff, now);
+        assert_eq!(third, Duration::ZERO);
+        assert_eq!(fourth, Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::components::worker_executor::spawned::{RestartBackoff, RestartHistory};
+use crate::components::worker_executor_cluster::WorkerExecutorCluster;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::{interval_at, Instant};
+use tracing::{info, warn};
+
+/// Periodically kills a random started executor and restarts a random stopped one.
+///
+/// The choices are driven by a seeded RNG, so a given seed produces the same sequence of
+/// operations for the same cluster state. The last started executor is never killed.
+pub struct ChaosController {
+    cluster: Arc<dyn WorkerExecutorCluster + Send + Sync + 'static>,
+    restart_backoff: Option<RestartBackoff>,
+    running: Mutex<Option<(oneshot::Sender<()>, JoinHandle<()>)>>,
+}
+
+impl ChaosController {
+    pub fn new(cluster: Arc<dyn WorkerExecutorCluster + Send + Sync + 'static>) -> Self {
+        Self {
+            cluster,
+            restart_backoff: None,
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Delays the successive restarts of the same executor, for clusters whose executors have
+    /// no backoff of their own, such as the ones not built with
+    /// `SpawnedWorkerExecutorClusterBuilder::with_restart_backoff`
+    pub fn with_restart_backoff(mut self, restart_backoff: RestartBackoff) -> Self {
+        self.restart_backoff = Some(restart_backoff);
+        self
+    }
+
+    pub fn start_chaos(&self, interval: Duration, seed: u64) {
+        let mut running = self.running.lock().unwrap();
+        if running.is_some() {
+            warn!("Chaos is already running");
+            return;
+        }
+
+        info!("Starting chaos with interval {interval:?} and seed {seed}");
+        let cluster = self.cluster.clone();
+        let restart_backoff = self.restart_backoff.clone();
+        let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+        let handle = tokio::spawn(async move {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut restart_histories = HashMap::new();
+            let mut ticker = interval_at(Instant::now() + interval, interval);
+            loop {
+                select! {
+                    _ = &mut stop_rx => break,
+                    _ = ticker.tick() => Self::step(
+                        cluster.as_ref(),
+                        &mut rng,
+                        restart_backoff.as_ref(),
+                        &mut restart_histories,
+                    ).await,
+                }
+            }
+        });
+
+        *running = Some((stop_tx, handle));
+    }
+
+    /// Stops the chaos loop, letting an in-progress kill or restart finish first
+    pub async fn stop_chaos(&self) {
+        let running = self.running.lock().unwrap().take();
+        if let Some((stop_tx, handle)) = running {
+            info!("Stopping chaos");
+            let _ = stop_tx.send(());
+            let _ = handle.await;
+        }
+    }
+
+    async fn step(
+        cluster: &(dyn WorkerExecutorCluster + Send + Sync),
+        rng: &mut StdRng,
+        restart_backoff: Option<&RestartBackoff>,
+        restart_histories: &mut HashMap<usize, RestartHistory>,
+    ) {
+        // Sorting makes the random choices independent of the hash set iteration order
+        let mut started = cluster.started_indices().await;
+        started.sort();
+        let mut stopped = cluster.stopped_indices().await;
+        stopped.sort();
+
+        if started.len() > 1 {
+            if let Some(index) = started.choose(rng) {
+                info!("Chaos: stopping worker executor {index}");
+                cluster.stop(*index).await;
+            }
+        }
+
+        if let Some(index) = stopped.choose(rng) {
+            if let Some(restart_backoff) = restart_backoff {
+                let delay = restart_histories
+                    .entry(*index)
+                    .or_default()
+                    .record_restart(restart_backoff, Instant::now().into_std());
+                if !delay.is_zero() {
+                    info!("Chaos: delaying the start of worker executor {index} by {delay:?}");
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            info!("Chaos: starting worker executor {index}");
+            cluster.start(*index).await;
+        }
+    }
+}
@@ -18,18 +18,22 @@ use crate::components::redis::Redis;
 use crate::components::shard_manager::ShardManager;
 use crate::components::worker_executor::k8s::K8sWorkerExecutor;
 use crate::components::worker_executor::WorkerExecutor;
-use crate::components::worker_executor_cluster::WorkerExecutorCluster;
+use crate::components::worker_executor_cluster::{
+    cluster_event_sender, ClusterEvent, WorkerExecutorCluster,
+};
 use crate::components::worker_service::WorkerService;
 use async_trait::async_trait;
 use std::collections::HashSet;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 use tracing::{info, Level};
 
 pub struct K8sWorkerExecutorCluster {
     worker_executors: Vec<Arc<dyn WorkerExecutor + Send + Sync + 'static>>,
     stopped_indices: Arc<Mutex<HashSet<usize>>>,
+    events: broadcast::Sender<ClusterEvent>,
 }
 
 impl K8sWorkerExecutorCluster {
@@ -107,6 +111,7 @@ impl K8sWorkerExecutorCluster {
         Self {
             worker_executors,
             stopped_indices: Arc::new(Mutex::new(HashSet::new())),
+            events: cluster_event_sender(),
         }
     }
 }
@@ -119,15 +124,17 @@ impl WorkerExecutorCluster for K8sWorkerExecutorCluster {
 
     async fn kill_all(&self) {
         info!("Killing all worker executors");
-        for worker_executor in &self.worker_executors {
+        for (index, worker_executor) in self.worker_executors.iter().enumerate() {
             worker_executor.kill().await;
+            self.publish_event(ClusterEvent::Stopped { index });
         }
     }
 
     async fn restart_all(&self) {
         info!("Restarting all worker executors");
-        for worker_executor in &self.worker_executors {
+        for (index, worker_executor) in self.worker_executors.iter().enumerate() {
             worker_executor.restart().await;
+            self.publish_event(ClusterEvent::Restarted { index });
         }
     }
 
@@ -136,6 +143,7 @@ impl WorkerExecutorCluster for K8sWorkerExecutorCluster {
         if !stopped.contains(&index) {
             self.worker_executors[index].kill().await;
             stopped.insert(index);
+            self.publish_event(ClusterEvent::Stopped { index });
         }
     }
 
@@ -143,6 +151,29 @@ impl WorkerExecutorCluster for K8sWorkerExecutorCluster {
         if self.stopped_indices().await.contains(&index) {
             self.worker_executors[index].restart().await;
             self.stopped_indices.lock().await.remove(&index);
+            self.publish_event(ClusterEvent::Started { index });
+        }
+    }
+
+    async fn stop_range(&self, range: RangeInclusive<usize>) {
+        let mut stopped = self.stopped_indices.lock().await;
+        for index in range {
+            if !stopped.contains(&index) {
+                self.worker_executors[index].kill().await;
+                stopped.insert(index);
+                self.publish_event(ClusterEvent::Stopped { index });
+            }
+        }
+    }
+
+    async fn start_range(&self, range: RangeInclusive<usize>) {
+        let mut stopped = self.stopped_indices.lock().await;
+        for index in range {
+            if stopped.contains(&index) {
+                self.worker_executors[index].restart().await;
+                stopped.remove(&index);
+                self.publish_event(ClusterEvent::Started { index });
+            }
         }
     }
 
@@ -159,4 +190,20 @@ impl WorkerExecutorCluster for K8sWorkerExecutorCluster {
         let stopped_indices = self.stopped_indices.lock().await;
         all_indices.difference(&stopped_indices).copied().collect()
     }
+
+    async fn started_executors(
+        &self,
+    ) -> Vec<(usize, Arc<dyn WorkerExecutor + Send + Sync + 'static>)> {
+        let stopped_indices = self.stopped_indices.lock().await;
+        self.worker_executors
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !stopped_indices.contains(index))
+            .map(|(index, worker_executor)| (index, worker_executor.clone()))
+            .collect()
+    }
+
+    fn event_sender(&self) -> &broadcast::Sender<ClusterEvent> {
+        &self.events
+    }
 }
@@ -12,15 +12,103 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::components::component_service::ComponentService;
+use crate::components::shard_manager::ShardManager;
 use crate::components::worker_executor::WorkerExecutor;
+use crate::components::{is_serving_grpc, strip_ansi_escapes, wait_for_startup_grpc};
+use anyhow::anyhow;
 use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use futures::future::join_all;
+use golem_api_grpc::proto::golem::shardmanager::v1::{
+    get_routing_table_response, GetRoutingTableRequest, GetRoutingTableResponse,
+};
+use golem_api_grpc::proto::golem::shardmanager::{Pod, RoutingTable};
+use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
+use golem_common::model::ShardId;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tonic::transport::Channel;
+use tracing::{debug, info, warn};
 
+pub mod chaos;
 pub mod docker;
 pub mod k8s;
 pub mod provided;
 pub mod spawned;
 
+/// Metrics of a single executor of a cluster, as reported by its metrics endpoint.
+/// The values are `None` when the executor is stopped, its metrics could not be fetched, or the
+/// metric is missing from what it reported.
+#[derive(Debug, Clone)]
+pub struct ExecutorMetrics {
+    pub index: usize,
+    pub stopped: bool,
+    pub active_workers: Option<u64>,
+    pub memory_bytes: Option<u64>,
+    pub uptime: Option<Duration>,
+}
+
+impl ExecutorMetrics {
+    fn unavailable(index: usize, stopped: bool) -> Self {
+        Self {
+            index,
+            stopped,
+            active_workers: None,
+            memory_bytes: None,
+            uptime: None,
+        }
+    }
+
+    /// True if any of the metrics could be read from the executor
+    pub fn is_available(&self) -> bool {
+        !self.stopped
+            && (self.active_workers.is_some()
+                || self.memory_bytes.is_some()
+                || self.uptime.is_some())
+    }
+}
+
+/// Summary of probing the health endpoint of every executor of a cluster. Started executors
+/// which are not serving, for example because they crashed, are counted as unreachable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterHealth {
+    pub total: usize,
+    pub healthy: usize,
+    pub stopped: usize,
+    pub unreachable: usize,
+}
+
+impl ClusterHealth {
+    /// True if every started executor is serving
+    pub fn all_started_healthy(&self) -> bool {
+        self.unreachable == 0
+    }
+}
+
+/// A change of the cluster, published as it happens. `index` is the index of the executor at
+/// the time of the event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusterEvent {
+    Started { index: usize },
+    Stopped { index: usize },
+    Restarted { index: usize },
+    Added { index: usize },
+    Removed { index: usize },
+}
+
+// Events are dropped for subscribers lagging behind by more than this
+const CLUSTER_EVENT_CAPACITY: usize = 1024;
+
+pub fn cluster_event_sender() -> broadcast::Sender<ClusterEvent> {
+    broadcast::channel(CLUSTER_EVENT_CAPACITY).0
+}
+
 #[async_trait]
 pub trait WorkerExecutorCluster {
     fn size(&self) -> usize;
@@ -30,8 +118,571 @@ pub trait WorkerExecutorCluster {
     async fn stop(&self, index: usize);
     async fn start(&self, index: usize);
 
+    /// Stops every executor in the range, holding the cluster lock for the whole operation
+    async fn stop_range(&self, range: RangeInclusive<usize>);
+    /// Starts every stopped executor in the range, holding the cluster lock for the whole operation
+    async fn start_range(&self, range: RangeInclusive<usize>);
+
     fn to_vec(&self) -> Vec<Arc<dyn WorkerExecutor + Send + Sync + 'static>>;
 
     async fn stopped_indices(&self) -> Vec<usize>;
     async fn started_indices(&self) -> Vec<usize>;
+
+    /// The started executors with their indices, in index order. Taken as a single snapshot, so
+    /// the indices belong to the executors even while executors are added or removed.
+    async fn started_executors(
+        &self,
+    ) -> Vec<(usize, Arc<dyn WorkerExecutor + Send + Sync + 'static>)>;
+
+    /// The started executors in index order, leaving out the stopped ones
+    async fn live_executors(&self) -> Vec<Arc<dyn WorkerExecutor + Send + Sync + 'static>> {
+        self.started_executors()
+            .await
+            .into_iter()
+            .map(|(_, worker_executor)| worker_executor)
+            .collect()
+    }
+
+    fn event_sender(&self) -> &broadcast::Sender<ClusterEvent>;
+
+    /// Subscribes to the events of the cluster published from now on
+    fn subscribe_events(&self) -> broadcast::Receiver<ClusterEvent> {
+        self.event_sender().subscribe()
+    }
+
+    // Sending only fails when nobody is subscribed, which is fine
+    fn publish_event(&self, event: ClusterEvent) {
+        let _ = self.event_sender().send(event);
+    }
+
+    /// Kills every executor, then waits until none of them accepts connections on its gRPC port,
+    /// so the ports can be reused right away. Returns the indices of the executors that were
+    /// still reachable when `timeout` elapsed.
+    async fn kill_all_and_wait(&self, timeout: Duration) -> Vec<usize> {
+        let worker_executors = self.to_vec();
+        self.kill_all().await;
+
+        let start = Instant::now();
+        let still_running = join_all(
+            worker_executors
+                .iter()
+                .enumerate()
+                .map(|(index, worker_executor)| async move {
+                    let host = worker_executor.public_host();
+                    let port = worker_executor.public_grpc_port();
+                    while accepts_connections(&host, port).await {
+                        if start.elapsed() > timeout {
+                            warn!("Worker executor {index} did not terminate in {timeout:?}");
+                            return Some(index);
+                        }
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                    None
+                }),
+        )
+        .await;
+
+        still_running.into_iter().flatten().collect()
+    }
+
+    /// Stops the executor at `index` after draining it with `WorkerExecutor::drain`: the shard
+    /// manager assigns its shards to the other executors, then its running workers are waited
+    /// for. The executor is killed once none of them is running, or when `timeout` elapsed, in
+    /// which case the workers still running lose their in-flight invocations and an error is
+    /// returned. Draining failures are returned too, after the executor was stopped.
+    async fn drain_and_stop(
+        &self,
+        index: usize,
+        shard_manager: &(dyn ShardManager + Send + Sync),
+        component_service: &(dyn ComponentService + Send + Sync),
+        timeout: Duration,
+    ) -> crate::Result<()> {
+        let worker_executor = self.to_vec()[index].clone();
+        let result = worker_executor
+            .drain(shard_manager, component_service, timeout)
+            .await;
+        self.stop(index).await;
+        result
+    }
+
+    /// Restarts the started executors in waves of `parallelism` executors, waiting for
+    /// each wave to become healthy before moving on to the next one.
+    async fn rolling_restart(&self, parallelism: usize) {
+        let parallelism = parallelism.max(1);
+        info!("Rolling restart of worker executors, {parallelism} at a time");
+
+        let started_executors = self.started_executors().await;
+
+        for wave in started_executors.chunks(parallelism) {
+            let indices: Vec<usize> = wave.iter().map(|(index, _)| *index).collect();
+            info!("Restarting worker executors {indices:?}");
+            join_all(wave.iter().map(|(_, worker_executor)| async move {
+                worker_executor.kill().await;
+                worker_executor.restart().await;
+                wait_for_startup_grpc(
+                    &worker_executor.public_host(),
+                    worker_executor.public_grpc_port(),
+                    "golem-worker-executor",
+                    Duration::from_secs(90),
+                )
+                .await;
+            }))
+            .await;
+
+            for index in indices {
+                self.publish_event(ClusterEvent::Restarted { index });
+            }
+        }
+    }
+
+    /// Polls the health endpoint of every started executor until all of them are serving.
+    /// Fails with the list of indices that did not become healthy within `timeout`.
+    async fn wait_until_healthy(&self, timeout: Duration) -> crate::Result<()> {
+        let mut pending = self.started_executors().await;
+
+        let start = Instant::now();
+        loop {
+            let health = join_all(pending.iter().map(|(_, worker_executor)| async move {
+                is_serving_grpc(
+                    &worker_executor.public_host(),
+                    worker_executor.public_grpc_port(),
+                    "golem-worker-executor",
+                )
+                .await
+            }))
+            .await;
+
+            pending = pending
+                .into_iter()
+                .zip(health)
+                .filter(|(_, healthy)| !healthy)
+                .map(|(pending, _)| pending)
+                .collect();
+            let indices: Vec<usize> = pending.iter().map(|(index, _)| *index).collect();
+
+            if indices.is_empty() {
+                break Ok(());
+            } else if start.elapsed() > timeout {
+                break Err(anyhow!(
+                    "Worker executors {indices:?} did not become healthy in {}s",
+                    timeout.as_secs()
+                ));
+            } else {
+                debug!("Waiting for worker executors {indices:?} to become healthy");
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        }
+    }
+
+    /// Probes the health endpoint of every started executor once, see `ClusterHealth`
+    async fn health(&self) -> ClusterHealth {
+        let started_executors = self.started_executors().await;
+        let stopped = self.stopped_indices().await.len();
+
+        let health = join_all(
+            started_executors
+                .into_iter()
+                .map(|(_, worker_executor)| async move {
+                    is_serving_grpc(
+                        &worker_executor.public_host(),
+                        worker_executor.public_grpc_port(),
+                        "golem-worker-executor",
+                    )
+                    .await
+                }),
+        )
+        .await;
+
+        let started = health.len();
+        let healthy = health.into_iter().filter(|healthy| *healthy).count();
+        ClusterHealth {
+            total: started + stopped,
+            healthy,
+            stopped,
+            unreachable: started - healthy,
+        }
+    }
+
+    /// Waits until the shard manager assigned at least one shard to every started executor.
+    /// Fails with the list of indices that were not assigned any shards within `timeout`.
+    async fn wait_for_shard_assignment(
+        &self,
+        shard_manager: &(dyn ShardManager + Send + Sync),
+        timeout: Duration,
+    ) -> crate::Result<()> {
+        let started_executors = self.started_executors().await;
+
+        let results = join_all(started_executors.iter().map(|(_, worker_executor)| async move {
+            worker_executor
+                .wait_for_shard_assignment(shard_manager, timeout)
+                .await
+        }))
+        .await;
+
+        let unassigned: Vec<usize> = started_executors
+            .into_iter()
+            .zip(results)
+            .filter(|(_, result)| result.is_err())
+            .map(|((index, _), _)| index)
+            .collect();
+
+        if unassigned.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Worker executors {unassigned:?} were not assigned any shards in {}s",
+                timeout.as_secs()
+            ))
+        }
+    }
+
+    /// Returns the executor the shard manager currently assigns `shard_id` to, if any.
+    async fn executor_for_shard(
+        &self,
+        shard_manager: &(dyn ShardManager + Send + Sync),
+        shard_id: &ShardId,
+    ) -> Option<Arc<dyn WorkerExecutor + Send + Sync + 'static>> {
+        let routing_table = fetch_routing_table(shard_manager).await.ok()?;
+
+        let pod = routing_table
+            .shard_assignments
+            .into_iter()
+            .find(|entry| {
+                entry
+                    .shard_id
+                    .as_ref()
+                    .is_some_and(|id| ShardId::new(id.value) == *shard_id)
+            })?
+            .pod?;
+
+        let worker_executors = self.to_vec();
+        let index = executor_index_of_pod(&worker_executors, &pod)?;
+        Some(worker_executors[index].clone())
+    }
+
+    /// Returns the shards the shard manager currently assigns to each executor of the cluster,
+    /// by executor index. Executors without shards are included with an empty list. Shards which
+    /// are not assigned to any executor of the cluster are logged as warnings.
+    async fn shard_distribution(
+        &self,
+        shard_manager: &(dyn ShardManager + Send + Sync),
+    ) -> crate::Result<HashMap<usize, Vec<ShardId>>> {
+        let routing_table = fetch_routing_table(shard_manager).await?;
+        let worker_executors = self.to_vec();
+
+        let mut distribution: HashMap<usize, Vec<ShardId>> = (0..worker_executors.len())
+            .map(|index| (index, Vec::new()))
+            .collect();
+        let mut assigned = HashSet::new();
+
+        for entry in routing_table.shard_assignments {
+            let (Some(shard_id), Some(pod)) = (entry.shard_id, entry.pod) else {
+                continue;
+            };
+            let shard_id = ShardId::new(shard_id.value);
+
+            match executor_index_of_pod(&worker_executors, &pod) {
+                Some(index) => {
+                    distribution.entry(index).or_default().push(shard_id);
+                    assigned.insert(shard_id);
+                }
+                None => warn!(
+                    "Shard {shard_id} is assigned to {}:{}, not to an executor of the cluster",
+                    pod.host, pod.port
+                ),
+            }
+        }
+
+        let unassigned: Vec<ShardId> = (0..routing_table.number_of_shards as i64)
+            .map(ShardId::new)
+            .filter(|shard_id| !assigned.contains(shard_id))
+            .collect();
+        if !unassigned.is_empty() {
+            warn!(
+                "{} of {} shards are not assigned to any executor: {unassigned:?}",
+                unassigned.len(),
+                routing_table.number_of_shards
+            );
+        }
+
+        for shard_ids in distribution.values_mut() {
+            shard_ids.sort();
+        }
+        Ok(distribution)
+    }
+
+    /// Fetches the metrics of every executor of the cluster. Stopped or unreachable executors
+    /// are reported as unavailable instead of failing the whole call.
+    async fn collect_metrics(&self) -> Vec<ExecutorMetrics> {
+        let worker_executors = self.to_vec();
+        let stopped_indices = self.stopped_indices().await;
+        let client = reqwest::Client::new();
+
+        join_all(
+            worker_executors
+                .into_iter()
+                .enumerate()
+                .map(|(index, worker_executor)| {
+                    let stopped = stopped_indices.contains(&index);
+                    let client = client.clone();
+                    async move {
+                        if stopped {
+                            return ExecutorMetrics::unavailable(index, true);
+                        }
+
+                        let url = format!(
+                            "http://{}:{}/metrics",
+                            worker_executor.public_host(),
+                            worker_executor.public_http_port()
+                        );
+                        match fetch_metrics(&client, &url).await {
+                            Ok(body) => parse_executor_metrics(index, &body),
+                            Err(err) => {
+                                warn!("Failed to fetch metrics of worker executor {index}: {err}");
+                                ExecutorMetrics::unavailable(index, false)
+                            }
+                        }
+                    }
+                }),
+        )
+        .await
+    }
+
+    /// Reads the captured output of every executor, see `WorkerExecutor::log_files`, and merges
+    /// it into a single log ordered by the timestamps the lines start with. Lines without a
+    /// timestamp, such as the continuation of a multi-line message, stay after their preceding
+    /// line. Each line is returned with the index of the executor it comes from.
+    fn collect_logs(&self) -> Vec<(usize, String)> {
+        let mut lines = Vec::new();
+        for (index, worker_executor) in self.to_vec().iter().enumerate() {
+            for path in worker_executor.log_files() {
+                let content = match std::fs::read_to_string(&path) {
+                    Ok(content) => content,
+                    Err(err) => {
+                        warn!("Failed to read log file {}: {err}", path.display());
+                        continue;
+                    }
+                };
+
+                let mut timestamp = None;
+                for line in content.lines() {
+                    timestamp = line_timestamp(line).or(timestamp);
+                    lines.push((timestamp, index, line.to_string()));
+                }
+            }
+        }
+
+        // The sort is stable, so the lines of the same instant keep their order
+        lines.sort_by_key(|(timestamp, _, _)| *timestamp);
+        lines
+            .into_iter()
+            .map(|(_, index, line)| (index, line))
+            .collect()
+    }
+}
+
+// Generic methods would make the trait unusable as `dyn WorkerExecutorCluster`
+impl dyn WorkerExecutorCluster + Send + Sync + '_ {
+    /// Runs `f` concurrently with a client of every started executor, returning the results with
+    /// the executor indices, in index order. Fails if any of the clients can't be created.
+    pub async fn for_each_executor<F, Fut, T>(&self, f: F) -> crate::Result<Vec<(usize, T)>>
+    where
+        F: Fn(usize, WorkerExecutorClient<Channel>) -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let started_executors = self.started_executors().await;
+
+        let f = &f;
+        join_all(started_executors.into_iter().map(|(index, worker_executor)| async move {
+            let client = worker_executor.client().await?;
+            Ok::<_, anyhow::Error>((index, f(index, client).await))
+        }))
+        .await
+        .into_iter()
+        .collect()
+    }
+}
+
+async fn fetch_routing_table(
+    shard_manager: &(dyn ShardManager + Send + Sync),
+) -> crate::Result<RoutingTable> {
+    let response = shard_manager
+        .client()
+        .await
+        .get_routing_table(GetRoutingTableRequest {})
+        .await?
+        .into_inner();
+
+    match response {
+        GetRoutingTableResponse {
+            result: Some(get_routing_table_response::Result::Success(routing_table)),
+        } => Ok(routing_table),
+        _ => Err(anyhow!("Failed to get the routing table from the shard manager")),
+    }
+}
+
+// Executors may share a port when each of them runs on its own host (e.g. docker),
+// so the host is only used to disambiguate
+fn executor_index_of_pod(
+    worker_executors: &[Arc<dyn WorkerExecutor + Send + Sync + 'static>],
+    pod: &Pod,
+) -> Option<usize> {
+    let candidates: Vec<usize> = worker_executors
+        .iter()
+        .enumerate()
+        .filter(|(_, worker_executor)| worker_executor.private_grpc_port() as u32 == pod.port)
+        .map(|(index, _)| index)
+        .collect();
+    if candidates.len() == 1 {
+        candidates.first().copied()
+    } else {
+        candidates
+            .into_iter()
+            .find(|index| worker_executors[*index].private_host() == pod.host)
+    }
+}
+
+// The text output of tracing starts with an RFC 3339 timestamp, while the JSON output has it in
+// its `timestamp` field
+fn line_timestamp(line: &str) -> Option<DateTime<FixedOffset>> {
+    let line = strip_ansi_escapes(line);
+    let line = line.trim_start();
+    if line.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        DateTime::parse_from_rfc3339(value.get("timestamp")?.as_str()?).ok()
+    } else {
+        DateTime::parse_from_rfc3339(line.split_whitespace().next()?).ok()
+    }
+}
+
+async fn accepts_connections(host: &str, port: u16) -> bool {
+    tokio::net::TcpStream::connect((host, port)).await.is_ok()
+}
+
+async fn fetch_metrics(client: &reqwest::Client, url: &str) -> reqwest::Result<String> {
+    client
+        .get(url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await
+}
+
+fn parse_executor_metrics(index: usize, body: &str) -> ExecutorMetrics {
+    let mut active_workers = None;
+    let mut memory_bytes = None;
+    let mut start_time = None;
+
+    for line in body.lines().filter(|line| !line.starts_with('#')) {
+        let Some((name, value)) = line.rsplit_once(' ') else {
+            continue;
+        };
+        let Ok(value) = value.parse::<f64>() else {
+            continue;
+        };
+        match name {
+            "cache_size{cache=\"active_workers\"}" => active_workers = Some(value as u64),
+            "process_resident_memory_bytes" => memory_bytes = Some(value as u64),
+            "process_start_time_seconds" => start_time = Some(value),
+            _ => {}
+        }
+    }
+
+    // A start time which is not a valid duration, such as NaN or a negative value, is ignored
+    let uptime = start_time.and_then(|start_time| {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+        now.checked_sub(Duration::try_from_secs_f64(start_time).ok()?)
+    });
+
+    ExecutorMetrics {
+        index,
+        stopped: false,
+        active_workers,
+        memory_bytes,
+        uptime,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::components::worker_executor_cluster::{line_timestamp, parse_executor_metrics};
+    use chrono::DateTime;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn executor_metrics_are_parsed() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let start_time = now.as_secs_f64() - 60.0;
+        let body = format!(
+            "# HELP cache_size Number of entries in the cache\n\
+             # TYPE cache_size gauge\n\
+             cache_size{{cache=\"active_workers\"}} 3\n\
+             cache_size{{cache=\"components\"}} 7\n\
+             process_resident_memory_bytes 1.2e6\n\
+             process_start_time_seconds {start_time}\n"
+        );
+
+        let metrics = parse_executor_metrics(2, &body);
+
+        assert_eq!(metrics.index, 2);
+        assert!(!metrics.stopped);
+        assert_eq!(metrics.active_workers, Some(3));
+        assert_eq!(metrics.memory_bytes, Some(1_200_000));
+        let uptime = metrics.uptime.unwrap();
+        assert!(uptime >= Duration::from_secs(59) && uptime < Duration::from_secs(70));
+    }
+
+    #[test]
+    fn missing_and_malformed_metrics_are_none() {
+        let body = "process_resident_memory_bytes many\nnot a metric\n";
+        let metrics = parse_executor_metrics(0, body);
+
+        assert_eq!(metrics.active_workers, None);
+        assert_eq!(metrics.memory_bytes, None);
+        assert_eq!(metrics.uptime, None);
+    }
+
+    #[test]
+    fn invalid_start_times_are_ignored() {
+        for start_time in ["NaN", "inf", "-inf", "-1", "1e300"] {
+            let body = format!("process_start_time_seconds {start_time}\n");
+            let metrics = parse_executor_metrics(0, &body);
+            assert_eq!(metrics.uptime, None, "start time {start_time}");
+        }
+    }
+
+    #[test]
+    fn line_timestamps_are_parsed() {
+        let timestamp = DateTime::parse_from_rfc3339("2025-01-01T10:00:00.123456Z");
+        let timestamp = timestamp.unwrap();
+        let lines = [
+            "2025-01-01T10:00:00.123456Z  INFO golem: started",
+            "  2025-01-01T10:00:00.123456Z  INFO golem: indented",
+            "\x1b[2m2025-01-01T10:00:00.123456Z\x1b[0m \x1b[32m INFO\x1b[0m golem: colored",
+            r#"{"timestamp":"2025-01-01T10:00:00.123456Z","level":"INFO","fields":{}}"#,
+        ];
+        for line in lines {
+            assert_eq!(line_timestamp(line), Some(timestamp), "{line}");
+        }
+    }
+
+    #[test]
+    fn malformed_line_timestamps_are_none() {
+        let lines = [
+            "",
+            "    at golem::worker (src/worker.rs:10)",
+            "2025-13-01T10:00:00Z  INFO golem: no such month",
+            "2025-01-01 10:00:00  INFO golem: not RFC 3339",
+            r#"{"timestamp":5,"level":"INFO"}"#,
+            r#"{"level":"INFO"}"#,
+            r#"{"timestamp":"2025-01-01T10:00:00Z""#,
+        ];
+        for line in lines {
+            assert_eq!(line_timestamp(line), None, "{line}");
+        }
+    }
 }
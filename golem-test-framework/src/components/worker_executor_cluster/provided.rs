@@ -14,13 +14,18 @@
 
 use crate::components::worker_executor::provided::ProvidedWorkerExecutor;
 use crate::components::worker_executor::WorkerExecutor;
-use crate::components::worker_executor_cluster::WorkerExecutorCluster;
+use crate::components::worker_executor_cluster::{
+    cluster_event_sender, ClusterEvent, WorkerExecutorCluster,
+};
 use async_trait::async_trait;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tracing::info;
 
 pub struct ProvidedWorkerExecutorCluster {
     worker_executor: Arc<dyn WorkerExecutor + Send + Sync + 'static>,
+    events: broadcast::Sender<ClusterEvent>,
 }
 
 impl ProvidedWorkerExecutorCluster {
@@ -30,6 +35,7 @@ impl ProvidedWorkerExecutorCluster {
             ProvidedWorkerExecutor::new(host, http_port, grpc_port, shared_client);
         Self {
             worker_executor: Arc::new(worker_executor),
+            events: cluster_event_sender(),
         }
     }
 }
@@ -41,17 +47,23 @@ impl WorkerExecutorCluster for ProvidedWorkerExecutorCluster {
     }
 
     async fn kill_all(&self) {
-        self.worker_executor.kill().await
+        self.worker_executor.kill().await;
+        self.publish_event(ClusterEvent::Stopped { index: 0 });
     }
 
     async fn restart_all(&self) {
-        self.worker_executor.restart().await
+        self.worker_executor.restart().await;
+        self.publish_event(ClusterEvent::Restarted { index: 0 });
     }
 
     async fn stop(&self, _index: usize) {}
 
     async fn start(&self, _index: usize) {}
 
+    async fn stop_range(&self, _range: RangeInclusive<usize>) {}
+
+    async fn start_range(&self, _range: RangeInclusive<usize>) {}
+
     fn to_vec(&self) -> Vec<Arc<dyn WorkerExecutor + Send + Sync + 'static>> {
         vec![self.worker_executor.clone()]
     }
@@ -63,4 +75,14 @@ impl WorkerExecutorCluster for ProvidedWorkerExecutorCluster {
     async fn started_indices(&self) -> Vec<usize> {
         vec![0]
     }
+
+    async fn started_executors(
+        &self,
+    ) -> Vec<(usize, Arc<dyn WorkerExecutor + Send + Sync + 'static>)> {
+        vec![(0, self.worker_executor.clone())]
+    }
+
+    fn event_sender(&self) -> &broadcast::Sender<ClusterEvent> {
+        &self.events
+    }
 }
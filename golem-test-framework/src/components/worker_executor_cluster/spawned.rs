@@ -15,28 +15,87 @@
 use crate::components::component_service::ComponentService;
 use crate::components::redis::Redis;
 use crate::components::shard_manager::ShardManager;
-use crate::components::worker_executor::spawned::SpawnedWorkerExecutor;
-use crate::components::worker_executor::{WorkerExecutor, WorkerExecutorEnvVars};
-use crate::components::worker_executor_cluster::WorkerExecutorCluster;
+use crate::components::worker_executor::spawned::{RestartBackoff, SpawnedWorkerExecutor};
+use crate::components::worker_executor::{
+    WorkerExecutor, WorkerExecutorEnvVars, WorkerExecutorEnvVarsWithOverrides,
+};
+use crate::components::worker_executor_cluster::{
+    cluster_event_sender, ClusterEvent, WorkerExecutorCluster,
+};
 use crate::components::worker_service::WorkerService;
 use crate::components::GolemEnvVars;
+use anyhow::anyhow;
 use async_trait::async_trait;
-use std::collections::HashSet;
+use futures::future::join_all;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::{info, Level};
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinError;
+use tracing::{info, warn, Level};
+
+/// A worker executor which could not be started with the cluster
+#[derive(Debug, Clone)]
+pub struct WorkerExecutorStartFailure {
+    /// Index of the executor in the cluster, if all of them had started
+    pub index: usize,
+    pub error: String,
+}
+
+impl Display for WorkerExecutorStartFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "worker executor {}: {}", self.index, self.error)
+    }
+}
+
+// Failed starts are returned as errors, but a panicking task is still reported as a failure
+fn join_error_message(err: JoinError) -> String {
+    if err.is_panic() {
+        let panic = err.into_panic();
+        panic
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "unknown panic".to_string())
+    } else {
+        err.to_string()
+    }
+}
 
 pub struct SpawnedWorkerExecutorCluster {
-    worker_executors: Vec<Arc<dyn WorkerExecutor + Send + Sync + 'static>>,
+    // Only locked for short, non-async sections, as `size` and `to_vec` are synchronous
+    worker_executors: std::sync::Mutex<Vec<Arc<dyn WorkerExecutor + Send + Sync + 'static>>>,
+    // Every directory an executor was started in, removed when the cluster is dropped
+    executor_directories: std::sync::Mutex<HashSet<PathBuf>>,
     stopped_indices: Arc<Mutex<HashSet<usize>>>,
+    base_http_port: u16,
+    base_grpc_port: u16,
+    env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+    env_overrides: Vec<HashMap<String, String>>,
+    executable: PathBuf,
+    working_directory: PathBuf,
+    redis: Arc<dyn Redis + Send + Sync + 'static>,
+    component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+    shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
+    worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
+    verbosity: Level,
+    out_level: Level,
+    err_level: Level,
+    shared_client: bool,
+    extra_args: Vec<String>,
+    log_directory: Option<PathBuf>,
+    restart_backoff: Option<RestartBackoff>,
+    events: broadcast::Sender<ClusterEvent>,
 }
-
 impl SpawnedWorkerExecutorCluster {
     async fn make_worker_executor(
         env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+        env_overrides: HashMap<String, String>,
         executable: PathBuf,
         working_directory: PathBuf,
+        executor_directory: PathBuf,
         http_port: u16,
         grpc_port: u16,
         redis: Arc<dyn Redis + Send + Sync + 'static>,
@@ -47,25 +106,34 @@ impl SpawnedWorkerExecutorCluster {
         out_level: Level,
         err_level: Level,
         shared_client: bool,
-    ) -> Arc<dyn WorkerExecutor + Send + Sync + 'static> {
-        Arc::new(
-            SpawnedWorkerExecutor::new(
-                env_vars,
-                &executable,
-                &working_directory,
-                http_port,
-                grpc_port,
-                redis,
-                component_service,
-                shard_manager,
-                worker_service,
-                verbosity,
-                out_level,
-                err_level,
-                shared_client,
-            )
-            .await,
+        extra_args: Vec<String>,
+        log_directory: Option<PathBuf>,
+        restart_backoff: Option<RestartBackoff>,
+    ) -> crate::Result<Arc<dyn WorkerExecutor + Send + Sync + 'static>> {
+        let executor_directory =
+            Self::prepare_executor_directory(&working_directory, &executor_directory)?;
+        let worker_executor = SpawnedWorkerExecutor::try_new(
+            Self::env_vars_with_overrides(env_vars, env_overrides, &executor_directory),
+            &executable,
+            &executor_directory,
+            http_port,
+            grpc_port,
+            redis,
+            component_service,
+            shard_manager,
+            worker_service,
+            verbosity,
+            out_level,
+            err_level,
+            shared_client,
+            extra_args,
+            log_directory.as_deref(),
         )
+        .await?;
+        Ok(Arc::new(match restart_backoff {
+            Some(restart_backoff) => worker_executor.with_restart_backoff(restart_backoff),
+            None => worker_executor,
+        }))
     }
 
     pub async fn new(
@@ -83,8 +151,46 @@ impl SpawnedWorkerExecutorCluster {
         err_level: Level,
         shared_client: bool,
     ) -> Self {
-        Self::new_base(
-            Arc::new(GolemEnvVars()),
+        SpawnedWorkerExecutorClusterBuilder::new(
+            executable,
+            working_directory,
+            redis,
+            component_service,
+            shard_manager,
+            worker_service,
+        )
+        .with_size(size)
+        .with_base_ports(base_http_port, base_grpc_port)
+        .with_levels(verbosity, out_level, err_level)
+        .with_shared_client(shared_client)
+        .build()
+        .await
+    }
+
+    /// `env_overrides[i]`, if present, is merged into the environment of the executor with index `i`.
+    /// `extra_args` are passed to every executor on its command line.
+    /// When `log_directory` is given, the output of every executor is captured in files there too.
+    pub async fn new_base(
+        env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+        size: usize,
+        base_http_port: u16,
+        base_grpc_port: u16,
+        executable: &Path,
+        working_directory: &Path,
+        redis: Arc<dyn Redis + Send + Sync + 'static>,
+        component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+        shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
+        worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
+        verbosity: Level,
+        out_level: Level,
+        err_level: Level,
+        shared_client: bool,
+        env_overrides: Vec<HashMap<String, String>>,
+        extra_args: Vec<String>,
+        log_directory: Option<&Path>,
+    ) -> Self {
+        Self::builder_base(
+            env_vars,
             size,
             base_http_port,
             base_grpc_port,
@@ -98,11 +204,19 @@ impl SpawnedWorkerExecutorCluster {
             out_level,
             err_level,
             shared_client,
+            env_overrides,
+            extra_args,
+            log_directory,
         )
+        .build()
         .await
     }
 
-    pub async fn new_base(
+    /// Same as `new_base`, but instead of failing when some of the executors cannot be started,
+    /// returns a cluster of the ones which could, together with the failures.
+    /// The started executors are numbered consecutively, so an executor's index in the cluster
+    /// can be lower than the one in its failure; its ports are based on the latter.
+    pub async fn new_best_effort(
         env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
         size: usize,
         base_http_port: u16,
@@ -117,83 +231,257 @@ impl SpawnedWorkerExecutorCluster {
         out_level: Level,
         err_level: Level,
         shared_client: bool,
-    ) -> Self {
-        info!("Starting a cluster of golem-worker-executors of size {size}");
-        let mut worker_executors_joins = Vec::new();
-
-        for i in 0..size {
-            let http_port = base_http_port + i as u16;
-            let grpc_port = base_grpc_port + i as u16;
-
-            let worker_executor_join = tokio::spawn(Self::make_worker_executor(
-                env_vars.clone(),
-                executable.to_path_buf(),
-                working_directory.to_path_buf(),
-                http_port,
-                grpc_port,
-                redis.clone(),
-                component_service.clone(),
-                shard_manager.clone(),
-                worker_service.clone(),
-                verbosity,
-                out_level,
-                err_level,
-                shared_client,
-            ));
-
-            worker_executors_joins.push(worker_executor_join);
+        env_overrides: Vec<HashMap<String, String>>,
+        extra_args: Vec<String>,
+        log_directory: Option<&Path>,
+    ) -> (Self, Vec<WorkerExecutorStartFailure>) {
+        Self::builder_base(
+            env_vars,
+            size,
+            base_http_port,
+            base_grpc_port,
+            executable,
+            working_directory,
+            redis,
+            component_service,
+            shard_manager,
+            worker_service,
+            verbosity,
+            out_level,
+            err_level,
+            shared_client,
+            env_overrides,
+            extra_args,
+            log_directory,
+        )
+        .build_best_effort()
+        .await
+    }
+
+    fn builder_base(
+        env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+        size: usize,
+        base_http_port: u16,
+        base_grpc_port: u16,
+        executable: &Path,
+        working_directory: &Path,
+        redis: Arc<dyn Redis + Send + Sync + 'static>,
+        component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+        shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
+        worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
+        verbosity: Level,
+        out_level: Level,
+        err_level: Level,
+        shared_client: bool,
+        env_overrides: Vec<HashMap<String, String>>,
+        extra_args: Vec<String>,
+        log_directory: Option<&Path>,
+    ) -> SpawnedWorkerExecutorClusterBuilder {
+        let builder = SpawnedWorkerExecutorClusterBuilder::new(
+            executable,
+            working_directory,
+            redis,
+            component_service,
+            shard_manager,
+            worker_service,
+        )
+        .with_env_vars(env_vars)
+        .with_size(size)
+        .with_base_ports(base_http_port, base_grpc_port)
+        .with_levels(verbosity, out_level, err_level)
+        .with_shared_client(shared_client)
+        .with_env_overrides(env_overrides)
+        .with_extra_args(extra_args);
+        match log_directory {
+            Some(log_directory) => builder.with_log_directory(log_directory),
+            None => builder,
         }
+    }
 
-        let mut worker_executors = Vec::new();
+    /// Spawns a new worker executor on the first free port pair and returns its index,
+    /// or the error if it could not be started
+    pub async fn add_executor(&self) -> crate::Result<usize> {
+        // Holding the stopped indices lock serializes cluster mutations
+        let _stopped = self.stopped_indices.lock().await;
+
+        let used_grpc_ports: HashSet<u16> = self
+            .to_vec()
+            .iter()
+            .map(|worker_executor| worker_executor.private_grpc_port())
+            .collect();
+        let offset = (0..)
+            .find(|offset| !used_grpc_ports.contains(&(self.base_grpc_port + offset)))
+            .ok_or_else(|| anyhow!("No free port for a new worker executor"))?;
+
+        info!("Adding a new worker executor to the cluster");
+        let index = self.size();
+        let executor_directory = Self::executor_directory(&self.working_directory, offset);
+        self.executor_directories
+            .lock()
+            .unwrap()
+            .insert(executor_directory.clone());
+        let worker_executor = Self::make_worker_executor(
+            self.env_vars.clone(),
+            self.env_overrides.get(index).cloned().unwrap_or_default(),
+            self.executable.clone(),
+            self.working_directory.clone(),
+            executor_directory,
+            self.base_http_port + offset,
+            self.base_grpc_port + offset,
+            self.redis.clone(),
+            self.component_service.clone(),
+            self.shard_manager.clone(),
+            self.worker_service.clone(),
+            self.verbosity,
+            self.out_level,
+            self.err_level,
+            self.shared_client,
+            self.extra_args.clone(),
+            self.log_directory.clone(),
+            self.restart_backoff.clone(),
+        )
+        .await?;
+
+        // The cluster lock is held, so nothing was pushed since `index` was taken
+        self.worker_executors.lock().unwrap().push(worker_executor);
+        self.publish_event(ClusterEvent::Added { index });
+        Ok(index)
+    }
 
-        for join in worker_executors_joins {
-            worker_executors.push(join.await.expect("Failed to join"));
+    /// Kills and drops the worker executor at the given index.
+    /// Executors with a higher index are shifted down by one.
+    pub async fn remove_executor(&self, index: usize) {
+        let mut stopped = self.stopped_indices.lock().await;
+
+        info!("Removing worker executor {index} from the cluster");
+        let worker_executor = self.worker_executors.lock().unwrap().remove(index);
+        if !stopped.contains(&index) {
+            worker_executor.kill().await;
         }
 
-        Self {
-            worker_executors,
-            stopped_indices: Arc::new(Mutex::new(HashSet::new())),
+        *stopped = stopped
+            .iter()
+            .filter(|stopped_index| **stopped_index != index)
+            .map(|stopped_index| {
+                if *stopped_index > index {
+                    stopped_index - 1
+                } else {
+                    *stopped_index
+                }
+            })
+            .collect();
+        self.publish_event(ClusterEvent::Removed { index });
+    }
+
+    fn env_vars_with_overrides(
+        env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+        mut env_overrides: HashMap<String, String>,
+        executor_directory: &Path,
+    ) -> Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static> {
+        env_overrides
+            .entry("GOLEM__TRACING__FILE_DIR".to_string())
+            .or_insert_with(|| executor_directory.to_string_lossy().to_string());
+        Arc::new(WorkerExecutorEnvVarsWithOverrides::new(env_vars, env_overrides))
+    }
+
+    // Every executor runs in its own `executor-{offset}` directory under the working directory,
+    // where the offset is the distance of its ports from the base ports. This equals the index
+    // for the executors the cluster is started with, and stays unique when executors are removed
+    // and added. The blob storage is still shared, as workers move between the executors.
+    fn executor_directory(working_directory: &Path, offset: u16) -> PathBuf {
+        working_directory.join(format!("executor-{offset}"))
+    }
+
+    // Creates the directory with a copy of the configuration of the working directory, where
+    // the executor looks it up, and returns its absolute path, as the process is started in it
+    fn prepare_executor_directory(
+        working_directory: &Path,
+        executor_directory: &Path,
+    ) -> crate::Result<PathBuf> {
+        let config_directory = executor_directory.join("config");
+        std::fs::create_dir_all(&config_directory).map_err(|err| {
+            anyhow!("Failed to create worker executor directory {executor_directory:?}: {err}")
+        })?;
+
+        let config_file = working_directory.join("config").join("worker-executor.toml");
+        if config_file.exists() {
+            std::fs::copy(&config_file, config_directory.join("worker-executor.toml"))
+                .map_err(|err| anyhow!("Failed to copy {config_file:?}: {err}"))?;
         }
+
+        std::fs::canonicalize(executor_directory).map_err(|err| {
+            anyhow!("Failed to resolve worker executor directory {executor_directory:?}: {err}")
+        })
+    }
+
+    fn worker_executor(&self, index: usize) -> Arc<dyn WorkerExecutor + Send + Sync + 'static> {
+        self.worker_executors.lock().unwrap()[index].clone()
     }
 }
 
 #[async_trait]
 impl WorkerExecutorCluster for SpawnedWorkerExecutorCluster {
     fn size(&self) -> usize {
-        self.worker_executors.len()
+        self.worker_executors.lock().unwrap().len()
     }
 
     async fn kill_all(&self) {
         info!("Killing all worker executors");
-        for worker_executor in &self.worker_executors {
+        for (index, worker_executor) in self.to_vec().into_iter().enumerate() {
             worker_executor.kill().await;
+            self.publish_event(ClusterEvent::Stopped { index });
         }
     }
 
     async fn restart_all(&self) {
         info!("Restarting all worker executors");
-        for worker_executor in &self.worker_executors {
+        for (index, worker_executor) in self.to_vec().into_iter().enumerate() {
             worker_executor.restart().await;
+            self.publish_event(ClusterEvent::Restarted { index });
         }
     }
 
     async fn stop(&self, index: usize) {
         let mut stopped = self.stopped_indices.lock().await;
         if !stopped.contains(&index) {
-            self.worker_executors[index].kill().await;
+            self.worker_executor(index).kill().await;
             stopped.insert(index);
+            self.publish_event(ClusterEvent::Stopped { index });
         }
     }
 
     async fn start(&self, index: usize) {
         if self.stopped_indices().await.contains(&index) {
-            self.worker_executors[index].restart().await;
+            self.worker_executor(index).restart().await;
             self.stopped_indices.lock().await.remove(&index);
+            self.publish_event(ClusterEvent::Started { index });
+        }
+    }
+
+    async fn stop_range(&self, range: RangeInclusive<usize>) {
+        let mut stopped = self.stopped_indices.lock().await;
+        for index in range {
+            if !stopped.contains(&index) {
+                self.worker_executor(index).kill().await;
+                stopped.insert(index);
+                self.publish_event(ClusterEvent::Stopped { index });
+            }
+        }
+    }
+
+    async fn start_range(&self, range: RangeInclusive<usize>) {
+        let mut stopped = self.stopped_indices.lock().await;
+        for index in range {
+            if stopped.contains(&index) {
+                self.worker_executor(index).restart().await;
+                stopped.remove(&index);
+                self.publish_event(ClusterEvent::Started { index });
+            }
         }
     }
 
     fn to_vec(&self) -> Vec<Arc<dyn WorkerExecutor + Send + Sync + 'static>> {
-        self.worker_executors.to_vec()
+        self.worker_executors.lock().unwrap().to_vec()
     }
 
     async fn stopped_indices(&self) -> Vec<usize> {
@@ -201,8 +489,259 @@ impl WorkerExecutorCluster for SpawnedWorkerExecutorCluster {
     }
 
     async fn started_indices(&self) -> Vec<usize> {
-        let all_indices = HashSet::from_iter(0..self.worker_executors.len());
+        let all_indices = HashSet::from_iter(0..self.size());
         let stopped_indices = self.stopped_indices.lock().await;
         all_indices.difference(&stopped_indices).copied().collect()
     }
+
+    async fn started_executors(
+        &self,
+    ) -> Vec<(usize, Arc<dyn WorkerExecutor + Send + Sync + 'static>)> {
+        // Adding and removing executors holds the stopped indices lock, so the executors read
+        // while holding it match the indices
+        let stopped_indices = self.stopped_indices.lock().await;
+        self.to_vec()
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !stopped_indices.contains(index))
+            .collect()
+    }
+
+    fn event_sender(&self) -> &broadcast::Sender<ClusterEvent> {
+        &self.events
+    }
+}
+
+impl Drop for SpawnedWorkerExecutorCluster {
+    fn drop(&mut self) {
+        // Kills the executors first, unless they are still referenced from elsewhere
+        if let Ok(worker_executors) = self.worker_executors.get_mut() {
+            worker_executors.clear();
+        }
+
+        if let Ok(executor_directories) = self.executor_directories.get_mut() {
+            for executor_directory in executor_directories.drain() {
+                match std::fs::remove_dir_all(&executor_directory) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => warn!(
+                        "Failed to remove worker executor directory {executor_directory:?}: {err}"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Builds a `SpawnedWorkerExecutorCluster` with named settings instead of positional arguments.
+/// Every setting except the executable, the working directory and the dependencies has a
+/// default: a single executor on base ports 9000 and 9100, `INFO` verbosity and stdout level,
+/// `ERROR` stderr level, no shared client, no overrides, extra arguments or log files, and no
+/// restart backoff.
+pub struct SpawnedWorkerExecutorClusterBuilder {
+    env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+    size: usize,
+    base_http_port: u16,
+    base_grpc_port: u16,
+    executable: PathBuf,
+    working_directory: PathBuf,
+    redis: Arc<dyn Redis + Send + Sync + 'static>,
+    component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+    shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
+    worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
+    verbosity: Level,
+    out_level: Level,
+    err_level: Level,
+    shared_client: bool,
+    env_overrides: Vec<HashMap<String, String>>,
+    extra_args: Vec<String>,
+    log_directory: Option<PathBuf>,
+    restart_backoff: Option<RestartBackoff>,
+}
+
+impl SpawnedWorkerExecutorClusterBuilder {
+    pub fn new(
+        executable: &Path,
+        working_directory: &Path,
+        redis: Arc<dyn Redis + Send + Sync + 'static>,
+        component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+        shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
+        worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
+    ) -> Self {
+        Self {
+            env_vars: Arc::new(GolemEnvVars()),
+            size: 1,
+            base_http_port: 9000,
+            base_grpc_port: 9100,
+            executable: executable.to_path_buf(),
+            working_directory: working_directory.to_path_buf(),
+            redis,
+            component_service,
+            shard_manager,
+            worker_service,
+            verbosity: Level::INFO,
+            out_level: Level::INFO,
+            err_level: Level::ERROR,
+            shared_client: false,
+            env_overrides: Vec::new(),
+            extra_args: Vec::new(),
+            log_directory: None,
+            restart_backoff: None,
+        }
+    }
+
+    pub fn with_env_vars(
+        mut self,
+        env_vars: Arc<dyn WorkerExecutorEnvVars + Send + Sync + 'static>,
+    ) -> Self {
+        self.env_vars = env_vars;
+        self
+    }
+
+    pub fn with_size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// The executor with index `i` listens on `base_http_port + i` and `base_grpc_port + i`
+    pub fn with_base_ports(mut self, base_http_port: u16, base_grpc_port: u16) -> Self {
+        self.base_http_port = base_http_port;
+        self.base_grpc_port = base_grpc_port;
+        self
+    }
+
+    pub fn with_levels(mut self, verbosity: Level, out_level: Level, err_level: Level) -> Self {
+        self.verbosity = verbosity;
+        self.out_level = out_level;
+        self.err_level = err_level;
+        self
+    }
+
+    pub fn with_shared_client(mut self, shared_client: bool) -> Self {
+        self.shared_client = shared_client;
+        self
+    }
+
+    /// `env_overrides[i]`, if present, is merged into the environment of the executor `i`
+    pub fn with_env_overrides(mut self, env_overrides: Vec<HashMap<String, String>>) -> Self {
+        self.env_overrides = env_overrides;
+        self
+    }
+
+    /// Passed to every executor on its command line
+    pub fn with_extra_args(mut self, extra_args: Vec<String>) -> Self {
+        self.extra_args = extra_args;
+        self
+    }
+
+    /// Captures the output of every executor in files in `log_directory` too
+    pub fn with_log_directory(mut self, log_directory: &Path) -> Self {
+        self.log_directory = Some(log_directory.to_path_buf());
+        self
+    }
+
+    /// Delays the successive restarts of every executor, including the ones added later
+    pub fn with_restart_backoff(mut self, restart_backoff: RestartBackoff) -> Self {
+        self.restart_backoff = Some(restart_backoff);
+        self
+    }
+
+    /// Starts the executors, panicking if any of them cannot be started
+    pub async fn build(self) -> SpawnedWorkerExecutorCluster {
+        let (cluster, failures) = self.build_best_effort().await;
+
+        if !failures.is_empty() {
+            let failures = failures
+                .iter()
+                .map(|failure| failure.to_string())
+                .collect::<Vec<_>>();
+            panic!("Failed to start worker executors: {}", failures.join("; "));
+        }
+
+        cluster
+    }
+
+    /// Starts the executors, returning a cluster of the ones which could be started together
+    /// with the failures of the others. See `SpawnedWorkerExecutorCluster::new_best_effort`.
+    pub async fn build_best_effort(
+        self,
+    ) -> (SpawnedWorkerExecutorCluster, Vec<WorkerExecutorStartFailure>) {
+        let size = self.size;
+        info!("Starting a cluster of golem-worker-executors of size {size}");
+        // Resolved, as the executors are started in their own directories
+        let executable =
+            std::fs::canonicalize(&self.executable).unwrap_or_else(|_| self.executable.clone());
+        let executor_directories = (0..size)
+            .map(|i| {
+                SpawnedWorkerExecutorCluster::executor_directory(&self.working_directory, i as u16)
+            })
+            .collect::<Vec<_>>();
+
+        // Each executor starts on its own task, so the startup waits overlap
+        let started = join_all(executor_directories.iter().enumerate().map(|(i, directory)| {
+            tokio::spawn(SpawnedWorkerExecutorCluster::make_worker_executor(
+                self.env_vars.clone(),
+                self.env_overrides.get(i).cloned().unwrap_or_default(),
+                executable.clone(),
+                self.working_directory.clone(),
+                directory.clone(),
+                self.base_http_port + i as u16,
+                self.base_grpc_port + i as u16,
+                self.redis.clone(),
+                self.component_service.clone(),
+                self.shard_manager.clone(),
+                self.worker_service.clone(),
+                self.verbosity,
+                self.out_level,
+                self.err_level,
+                self.shared_client,
+                self.extra_args.clone(),
+                self.log_directory.clone(),
+                self.restart_backoff.clone(),
+            ))
+        }))
+        .await;
+
+        let mut worker_executors = Vec::new();
+        let mut failures = Vec::new();
+        for (index, join_result) in started.into_iter().enumerate() {
+            match join_result {
+                Ok(Ok(worker_executor)) => worker_executors.push(worker_executor),
+                Ok(Err(err)) => failures.push(WorkerExecutorStartFailure {
+                    index,
+                    error: err.to_string(),
+                }),
+                Err(err) => failures.push(WorkerExecutorStartFailure {
+                    index,
+                    error: join_error_message(err),
+                }),
+            }
+        }
+
+        let cluster = SpawnedWorkerExecutorCluster {
+            worker_executors: std::sync::Mutex::new(worker_executors),
+            executor_directories: std::sync::Mutex::new(HashSet::from_iter(executor_directories)),
+            stopped_indices: Arc::new(Mutex::new(HashSet::new())),
+            base_http_port: self.base_http_port,
+            base_grpc_port: self.base_grpc_port,
+            env_vars: self.env_vars,
+            env_overrides: self.env_overrides,
+            executable,
+            working_directory: self.working_directory,
+            redis: self.redis,
+            component_service: self.component_service,
+            shard_manager: self.shard_manager,
+            worker_service: self.worker_service,
+            verbosity: self.verbosity,
+            out_level: self.out_level,
+            err_level: self.err_level,
+            shared_client: self.shared_client,
+            extra_args: self.extra_args,
+            log_directory: self.log_directory,
+            restart_backoff: self.restart_backoff,
+            events: cluster_event_sender(),
+        };
+
+        (cluster, failures)
+    }
 }
@@ -21,6 +21,7 @@ use crate::components::rdb::Rdb;
 use crate::components::shard_manager::ShardManager;
 use crate::components::worker_service::{
     new_client, wait_for_startup, WorkerService, WorkerServiceEnvVars,
+    DEFAULT_STARTUP_POLL_INTERVAL,
 };
 use crate::components::GolemEnvVars;
 use async_dropper_simple::AsyncDropper;
@@ -208,7 +209,13 @@ impl K8sWorkerService {
             routing: managed_routing,
         } = Routing::create(Self::NAME, Self::GRPC_PORT, namespace, routing_type).await;
 
-        wait_for_startup(&local_host, local_port, timeout).await;
+        wait_for_startup(
+            &local_host,
+            local_port,
+            timeout,
+            DEFAULT_STARTUP_POLL_INTERVAL,
+        )
+        .await;
 
         info!("Golem Worker Service pod started");
 
@@ -13,15 +13,19 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use rand::Rng;
 use tonic::codec::CompressionEncoding;
-use tonic::transport::{Channel, Endpoint};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
 use tonic::Streaming;
-use tracing::Level;
+use tokio::time::Instant;
+use tracing::{debug, info, Level};
 
 use anyhow::anyhow;
 use golem_api_grpc::proto::golem::worker::v1::worker_service_client::WorkerServiceClient;
@@ -41,7 +45,7 @@ use golem_api_grpc::proto::golem::worker::LogEvent;
 use crate::components::component_service::ComponentService;
 use crate::components::rdb::Rdb;
 use crate::components::shard_manager::ShardManager;
-use crate::components::{wait_for_startup_grpc, EnvVarBuilder, GolemEnvVars};
+use crate::components::{is_serving_grpc, is_serving_grpc_channel, EnvVarBuilder, GolemEnvVars};
 
 pub mod docker;
 pub mod forwarding;
@@ -279,20 +283,185 @@ pub trait WorkerService {
     async fn kill(&self);
 }
 
+/// Connection settings of the worker service gRPC clients.
+/// The defaults are the settings `new_client` has always used.
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    pub connect_timeout: Duration,
+    pub max_decoding_message_size: Option<usize>,
+    pub max_encoding_message_size: Option<usize>,
+    pub keep_alive_interval: Option<Duration>,
+    pub keep_alive_timeout: Option<Duration>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            max_decoding_message_size: None,
+            max_encoding_message_size: None,
+            keep_alive_interval: None,
+            keep_alive_timeout: None,
+        }
+    }
+}
+
+impl ClientOptions {
+    fn configure_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+        let mut endpoint = endpoint.connect_timeout(self.connect_timeout);
+        if let Some(interval) = self.keep_alive_interval {
+            endpoint = endpoint.http2_keep_alive_interval(interval);
+        }
+        if let Some(timeout) = self.keep_alive_timeout {
+            endpoint = endpoint.keep_alive_timeout(timeout);
+        }
+        endpoint
+    }
+
+    fn configure_client(&self, channel: Channel) -> WorkerServiceClient<Channel> {
+        let mut client = WorkerServiceClient::new(channel)
+            .send_compressed(CompressionEncoding::Gzip)
+            .accept_compressed(CompressionEncoding::Gzip);
+        if let Some(limit) = self.max_decoding_message_size {
+            client = client.max_decoding_message_size(limit);
+        }
+        if let Some(limit) = self.max_encoding_message_size {
+            client = client.max_encoding_message_size(limit);
+        }
+        client
+    }
+}
+
 async fn new_client(
     host: &str,
     grpc_port: u16,
 ) -> Result<WorkerServiceClient<Channel>, tonic::transport::Error> {
-    let endpoint = Endpoint::new(format!("http://{host}:{grpc_port}"))?
-        .connect_timeout(Duration::from_secs(10));
+    new_client_with_opts(host, grpc_port, &ClientOptions::default()).await
+}
+
+pub async fn new_client_with_opts(
+    host: &str,
+    grpc_port: u16,
+    options: &ClientOptions,
+) -> Result<WorkerServiceClient<Channel>, tonic::transport::Error> {
+    let endpoint = options.configure_endpoint(Endpoint::new(format!("http://{host}:{grpc_port}"))?);
     let channel = endpoint.connect().await?;
-    Ok(WorkerServiceClient::new(channel)
-        .send_compressed(CompressionEncoding::Gzip)
-        .accept_compressed(CompressionEncoding::Gzip))
+    Ok(options.configure_client(channel))
 }
 
-async fn wait_for_startup(host: &str, grpc_port: u16, timeout: Duration) {
-    wait_for_startup_grpc(host, grpc_port, "golem-worker-service", timeout).await
+/// Default interval between two health checks while waiting for the worker service to start
+pub const DEFAULT_STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+async fn wait_for_startup(host: &str, grpc_port: u16, timeout: Duration, poll_interval: Duration) {
+    info!(
+        "Waiting for golem-worker-service start on host {host}:{grpc_port}, timeout: {}s",
+        timeout.as_secs()
+    );
+    poll_for_startup(timeout, poll_interval, || {
+        is_serving_grpc(host, grpc_port, "golem-worker-service")
+    })
+    .await
+}
+
+// Checks readiness until it succeeds, sleeping `poll_interval` plus up to 10% jitter between
+// the attempts, and panics once `timeout` has elapsed
+async fn poll_for_startup<F, Fut>(timeout: Duration, poll_interval: Duration, is_ready: F)
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let start = Instant::now();
+    let mut attempt = 1;
+    while !is_ready().await {
+        if start.elapsed() > timeout {
+            panic!("Failed to verify that golem-worker-service is running");
+        }
+        debug!(
+            "golem-worker-service is not ready yet (attempt {attempt}, elapsed: {}ms)",
+            start.elapsed().as_millis()
+        );
+        let jitter = poll_interval.mul_f64(rand::thread_rng().gen_range(0.0..0.1));
+        tokio::time::sleep(poll_interval + jitter).await;
+        attempt += 1;
+    }
+}
+
+/// Certificate and private key (PEM files) the worker service uses to serve gRPC over TLS.
+/// Clients trust exactly this certificate, so it can be self-signed, but it has to be valid
+/// for the host the service is reached on.
+#[derive(Debug, Clone)]
+pub struct WorkerServiceTls {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl WorkerServiceTls {
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+        }
+    }
+
+    fn env_vars(&self) -> HashMap<String, String> {
+        HashMap::from([
+            (
+                "GOLEM__WORKER_GRPC_TLS__CERT_PATH".to_string(),
+                self.cert_path.to_string_lossy().to_string(),
+            ),
+            (
+                "GOLEM__WORKER_GRPC_TLS__KEY_PATH".to_string(),
+                self.key_path.to_string_lossy().to_string(),
+            ),
+        ])
+    }
+}
+
+async fn new_tls_channel(
+    host: &str,
+    grpc_port: u16,
+    tls: &WorkerServiceTls,
+    options: &ClientOptions,
+) -> crate::Result<Channel> {
+    let cert = std::fs::read(&tls.cert_path)?;
+    let endpoint = options
+        .configure_endpoint(Endpoint::new(format!("https://{host}:{grpc_port}"))?)
+        .tls_config(
+            ClientTlsConfig::new()
+                .ca_certificate(Certificate::from_pem(cert))
+                .domain_name(host),
+        )?;
+    Ok(endpoint.connect().await?)
+}
+
+async fn new_tls_client(
+    host: &str,
+    grpc_port: u16,
+    tls: &WorkerServiceTls,
+    options: &ClientOptions,
+) -> crate::Result<WorkerServiceClient<Channel>> {
+    let channel = new_tls_channel(host, grpc_port, tls, options).await?;
+    Ok(options.configure_client(channel))
+}
+
+async fn wait_for_startup_tls(
+    host: &str,
+    grpc_port: u16,
+    tls: &WorkerServiceTls,
+    timeout: Duration,
+    poll_interval: Duration,
+) {
+    info!(
+        "Waiting for golem-worker-service start on host {host}:{grpc_port} (TLS), timeout: {}s",
+        timeout.as_secs()
+    );
+    poll_for_startup(timeout, poll_interval, move || async move {
+        match new_tls_channel(host, grpc_port, tls, &ClientOptions::default()).await {
+            Ok(channel) => is_serving_grpc_channel(channel, "golem-worker-service").await,
+            Err(_) => false,
+        }
+    })
+    .await
 }
 
 #[async_trait]
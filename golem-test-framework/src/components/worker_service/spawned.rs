@@ -16,30 +16,48 @@ use crate::components::component_service::ComponentService;
 use crate::components::rdb::Rdb;
 use crate::components::shard_manager::ShardManager;
 use crate::components::worker_service::{
-    new_client, wait_for_startup, WorkerService, WorkerServiceEnvVars,
+    new_client_with_opts, new_tls_client, wait_for_startup, wait_for_startup_tls, ClientOptions,
+    WorkerService, WorkerServiceEnvVars, WorkerServiceTls, DEFAULT_STARTUP_POLL_INTERVAL,
 };
-use crate::components::{ChildProcessLogger, GolemEnvVars};
+use crate::components::{ChildProcessLogger, GolemEnvVars, PortLock};
+use anyhow::anyhow;
 use async_trait::async_trait;
 
 use golem_api_grpc::proto::golem::worker::v1::worker_service_client::WorkerServiceClient;
-use std::path::Path;
-use std::process::{Child, Command, Stdio};
+use golem_common::model::RetryConfig;
+use golem_common::retries::get_delay;
+#[cfg(unix)]
+use nix::sys::signal::Signal;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tonic::transport::Channel;
-use tracing::info;
+use tracing::{info, warn};
 use tracing::Level;
 
 pub struct SpawnedWorkerService {
+    host: String,
     http_port: u16,
     grpc_port: u16,
     custom_request_port: u16,
     child: Arc<Mutex<Option<Child>>>,
-    _logger: ChildProcessLogger,
+    logger: ChildProcessLogger,
+    log_paths: Option<(PathBuf, PathBuf)>,
+    tls: Option<WorkerServiceTls>,
+    client_options: ClientOptions,
     client: Option<WorkerServiceClient<Channel>>,
+    #[cfg(unix)]
+    kill_signal: Option<Signal>,
+    leak_on_drop: bool,
+    _port_locks: Vec<PortLock>,
 }
 
 impl SpawnedWorkerService {
+    /// Starts the service with the default settings of `SpawnedWorkerServiceBuilder`, which
+    /// also configures everything else, such as the host, TLS or the environment variables
     pub async fn new(
         executable: &Path,
         working_directory: &Path,
@@ -54,92 +72,131 @@ impl SpawnedWorkerService {
         err_level: Level,
         shared_client: bool,
     ) -> Self {
-        Self::new_base(
-            Box::new(GolemEnvVars()),
+        SpawnedWorkerServiceBuilder::new(
             executable,
             working_directory,
-            http_port,
-            grpc_port,
-            custom_request_port,
             component_service,
             shard_manager,
             rdb,
-            verbosity,
-            out_level,
-            err_level,
-            shared_client,
         )
+        .with_ports(http_port, grpc_port, custom_request_port)
+        .with_levels(verbosity, out_level, err_level)
+        .with_shared_client(shared_client)
+        .build()
         .await
     }
 
-    pub async fn new_base(
-        env_vars: Box<dyn WorkerServiceEnvVars + Send + Sync + 'static>,
-        executable: &Path,
-        working_directory: &Path,
-        http_port: u16,
+    async fn connect(
+        host: &str,
         grpc_port: u16,
-        custom_request_port: u16,
-        component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
-        shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
-        rdb: Arc<dyn Rdb + Send + Sync + 'static>,
-        verbosity: Level,
-        out_level: Level,
-        err_level: Level,
-        shared_client: bool,
-    ) -> Self {
-        info!("Starting golem-worker-service process");
-
-        if !executable.exists() {
-            panic!("Expected to have precompiled golem-worker-service at {executable:?}");
+        tls: &Option<WorkerServiceTls>,
+        client_options: &ClientOptions,
+    ) -> crate::Result<WorkerServiceClient<Channel>> {
+        match tls {
+            Some(tls) => new_tls_client(host, grpc_port, tls, client_options).await,
+            None => Ok(new_client_with_opts(host, grpc_port, client_options).await?),
         }
+    }
 
-        let mut child = Command::new(executable)
-            .current_dir(working_directory)
-            .envs(
-                env_vars
-                    .env_vars(
-                        http_port,
-                        grpc_port,
-                        custom_request_port,
-                        component_service,
-                        shard_manager,
-                        rdb,
-                        verbosity,
-                    )
-                    .await,
-            )
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .expect("Failed to start golem-worker-service");
+    /// Port the service's HTTP API listens on
+    pub fn http_port(&self) -> u16 {
+        self.http_port
+    }
 
-        let logger =
-            ChildProcessLogger::log_child_process("[workersvc]", out_level, err_level, &mut child);
+    /// Port the service's gRPC API listens on
+    pub fn grpc_port(&self) -> u16 {
+        self.grpc_port
+    }
 
-        wait_for_startup("localhost", grpc_port, Duration::from_secs(90)).await;
+    /// Port the service serves the deployed API definitions on
+    pub fn custom_request_port(&self) -> u16 {
+        self.custom_request_port
+    }
 
-        Self {
-            http_port,
-            grpc_port,
-            custom_request_port,
-            child: Arc::new(Mutex::new(Some(child))),
-            _logger: logger,
-            client: if shared_client {
-                Some(
-                    new_client("localhost", grpc_port)
-                        .await
-                        .expect("Failed to create client"),
-                )
-            } else {
-                None
+    /// Path of the file capturing the service's stdout, if file logging was enabled
+    pub fn stdout_log_path(&self) -> Option<&Path> {
+        self.log_paths.as_ref().map(|(out_path, _)| out_path.as_path())
+    }
+
+    /// Path of the file capturing the service's stderr, if file logging was enabled
+    pub fn stderr_log_path(&self) -> Option<&Path> {
+        self.log_paths.as_ref().map(|(_, err_path)| err_path.as_path())
+    }
+
+    /// Subscribes to the stdout and stderr lines the service logs from now on.
+    /// Use with `wait_for_log_line` to wait for a specific line to appear.
+    pub fn subscribe_logs(&self) -> broadcast::Receiver<String> {
+        self.logger
+            .subscribe()
+            .expect("golem-worker-service logger has no log channel")
+    }
+
+    /// Checks, without blocking, whether the service process has already exited.
+    /// Returns `None` if it is still running or if it was stopped with `kill`.
+    pub fn try_wait(&self) -> Option<ExitStatus> {
+        self.child
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|child| child.try_wait().ok().flatten())
+    }
+
+    /// True if the service process exited without being stopped by the test framework
+    pub fn has_crashed(&self) -> bool {
+        self.try_wait().is_some()
+    }
+
+    /// Fails with a descriptive error if the service process is no longer running
+    pub fn ensure_running(&self) -> crate::Result<()> {
+        match self.try_wait() {
+            Some(status) => match status.code() {
+                Some(code) => Err(anyhow!("golem-worker-service exited with code {code}")),
+                None => Err(anyhow!("golem-worker-service exited: {status}")),
             },
+            None => Ok(()),
+        }
+    }
+
+    /// Connects a new client, retrying with backoff to bridge the short period in which
+    /// a just (re)started service does not accept connections yet
+    async fn reconnect(&self) -> crate::Result<WorkerServiceClient<Channel>> {
+        let retries = RetryConfig::max_attempts_5();
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match Self::connect(&self.host, self.grpc_port, &self.tls, &self.client_options).await {
+                Ok(client) => return Ok(client),
+                Err(err) => match get_delay(&retries, attempts) {
+                    Some(delay) => {
+                        warn!("golem-worker-service connection attempt {attempts} failed: {err:#}");
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => {
+                        return Err(err.context(format!(
+                            "Failed to connect to golem-worker-service after {attempts} attempts"
+                        )));
+                    }
+                },
+            }
         }
     }
 
+    /// Drops the shared client, closing its connection once the clones handed out by `client`
+    /// are dropped too. Afterwards `client` connects a new client on every call.
+    pub fn close_client(&mut self) {
+        self.client = None;
+    }
+
+    // Only used on drop, where the process can't be waited for asynchronously
     fn blocking_kill(&self) {
         info!("Stopping golem-worker-service");
         if let Some(mut child) = self.child.lock().unwrap().take() {
+            #[cfg(unix)]
+            if let Some(signal) = self.kill_signal {
+                if blocking_stop_with_signal(&mut child, signal) {
+                    return;
+                }
+            }
             let _ = child.kill();
         }
     }
@@ -148,14 +205,15 @@ impl SpawnedWorkerService {
 #[async_trait]
 impl WorkerService for SpawnedWorkerService {
     async fn client(&self) -> crate::Result<WorkerServiceClient<Channel>> {
+        self.ensure_running()?;
         match &self.client {
             Some(client) => Ok(client.clone()),
-            None => Ok(new_client("localhost", self.grpc_port).await?),
+            None => self.reconnect().await,
         }
     }
 
     fn private_host(&self) -> String {
-        "localhost".to_string()
+        self.host.clone()
     }
 
     fn private_http_port(&self) -> u16 {
@@ -171,12 +229,460 @@ impl WorkerService for SpawnedWorkerService {
     }
 
     async fn kill(&self) {
-        self.blocking_kill()
+        info!("Stopping golem-worker-service");
+        let child = self.child.lock().unwrap().take();
+        if let Some(mut child) = child {
+            #[cfg(unix)]
+            if let Some(signal) = self.kill_signal {
+                if stop_with_signal(&mut child, signal).await {
+                    return;
+                }
+            }
+            let _ = child.kill();
+        }
+    }
+}
+
+/// Builds a `SpawnedWorkerService` with named settings instead of positional arguments.
+/// Every setting except the executable, the working directory and the dependencies has a
+/// default: ports 8082, 9092 and 9093 on `localhost`, `INFO` verbosity and stdout level,
+/// `ERROR` stderr level, no shared client, no log files, no TLS and no port lock.
+pub struct SpawnedWorkerServiceBuilder {
+    env_vars: Box<dyn WorkerServiceEnvVars + Send + Sync + 'static>,
+    env_overrides: HashMap<String, String>,
+    executable: PathBuf,
+    working_directory: PathBuf,
+    host: String,
+    http_port: u16,
+    grpc_port: u16,
+    custom_request_port: u16,
+    component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+    shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
+    rdb: Arc<dyn Rdb + Send + Sync + 'static>,
+    verbosity: Level,
+    out_level: Level,
+    err_level: Level,
+    shared_client: bool,
+    client: Option<WorkerServiceClient<Channel>>,
+    log_directory: Option<PathBuf>,
+    tls: Option<WorkerServiceTls>,
+    client_options: ClientOptions,
+    startup_poll_interval: Duration,
+    port_lock_timeout: Option<Duration>,
+    #[cfg(unix)]
+    kill_signal: Option<Signal>,
+    leak_on_drop: bool,
+}
+
+impl SpawnedWorkerServiceBuilder {
+    pub fn new(
+        executable: &Path,
+        working_directory: &Path,
+        component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+        shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
+        rdb: Arc<dyn Rdb + Send + Sync + 'static>,
+    ) -> Self {
+        Self {
+            env_vars: Box::new(GolemEnvVars()),
+            env_overrides: HashMap::new(),
+            executable: executable.to_path_buf(),
+            working_directory: working_directory.to_path_buf(),
+            host: "localhost".to_string(),
+            http_port: 8082,
+            grpc_port: 9092,
+            custom_request_port: 9093,
+            component_service,
+            shard_manager,
+            rdb,
+            verbosity: Level::INFO,
+            out_level: Level::INFO,
+            err_level: Level::ERROR,
+            shared_client: false,
+            client: None,
+            log_directory: None,
+            tls: None,
+            client_options: ClientOptions::default(),
+            startup_poll_interval: DEFAULT_STARTUP_POLL_INTERVAL,
+            port_lock_timeout: None,
+            #[cfg(unix)]
+            kill_signal: None,
+            leak_on_drop: false,
+        }
+    }
+
+    pub fn with_env_vars(
+        mut self,
+        env_vars: Box<dyn WorkerServiceEnvVars + Send + Sync + 'static>,
+    ) -> Self {
+        self.env_vars = env_vars;
+        self
+    }
+
+    /// Sets an environment variable of the process, overriding the one coming from the
+    /// `WorkerServiceEnvVars` implementation, if any
+    pub fn with_env_var(mut self, name: &str, value: &str) -> Self {
+        self.env_overrides
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self
+    }
+
+    pub fn with_http_port(mut self, http_port: u16) -> Self {
+        self.http_port = http_port;
+        self
+    }
+
+    pub fn with_grpc_port(mut self, grpc_port: u16) -> Self {
+        self.grpc_port = grpc_port;
+        self
+    }
+
+    pub fn with_custom_request_port(mut self, custom_request_port: u16) -> Self {
+        self.custom_request_port = custom_request_port;
+        self
+    }
+
+    pub fn with_ports(self, http_port: u16, grpc_port: u16, custom_request_port: u16) -> Self {
+        self.with_http_port(http_port)
+            .with_grpc_port(grpc_port)
+            .with_custom_request_port(custom_request_port)
+    }
+
+    pub fn with_verbosity(mut self, verbosity: Level) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    pub fn with_out_level(mut self, out_level: Level) -> Self {
+        self.out_level = out_level;
+        self
+    }
+
+    pub fn with_err_level(mut self, err_level: Level) -> Self {
+        self.err_level = err_level;
+        self
+    }
+
+    pub fn with_levels(self, verbosity: Level, out_level: Level, err_level: Level) -> Self {
+        self.with_verbosity(verbosity)
+            .with_out_level(out_level)
+            .with_err_level(err_level)
+    }
+
+    pub fn with_shared_client(mut self, shared_client: bool) -> Self {
+        self.shared_client = shared_client;
+        self
+    }
+
+    /// Uses `client` for every call instead of connecting to the started process, for example to
+    /// go through a channel wrapped with a custom interceptor. Overrides `with_shared_client`.
+    pub fn with_client(mut self, client: WorkerServiceClient<Channel>) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn with_log_directory(mut self, log_directory: &Path) -> Self {
+        self.log_directory = Some(log_directory.to_path_buf());
+        self
+    }
+
+    pub fn with_tls(mut self, tls: WorkerServiceTls) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    pub fn with_client_options(mut self, client_options: ClientOptions) -> Self {
+        self.client_options = client_options;
+        self
+    }
+
+    pub fn with_startup_poll_interval(mut self, startup_poll_interval: Duration) -> Self {
+        self.startup_poll_interval = startup_poll_interval;
+        self
+    }
+
+    /// Claims the ports with lock files in the working directory before starting the process,
+    /// so that another test harness using the same ports waits for them at most `timeout`, then
+    /// fails. A zero `timeout` fails fast.
+    pub fn with_port_lock(mut self, timeout: Duration) -> Self {
+        self.port_lock_timeout = Some(timeout);
+        self
+    }
+
+    /// Stops the process by sending `signal` instead of killing it, for example `SIGINT` to go
+    /// through the graceful shutdown of the service. The process is still killed if it does not
+    /// exit within 10 seconds after the signal.
+    #[cfg(unix)]
+    pub fn with_kill_signal(mut self, signal: Signal) -> Self {
+        self.kill_signal = Some(signal);
+        self
+    }
+
+    /// Keeps the process running when the `SpawnedWorkerService` is dropped, logging its PID
+    /// and ports instead, so that its state can be inspected after a failed test. Calling `kill`
+    /// still stops it. The port locks are released on drop regardless.
+    pub fn with_leak_on_drop(mut self, leak_on_drop: bool) -> Self {
+        self.leak_on_drop = leak_on_drop;
+        self
+    }
+
+    /// Starts the process and waits until its gRPC API is serving
+    pub async fn build(self) -> SpawnedWorkerService {
+        info!("Starting golem-worker-service process");
+
+        if !self.executable.exists() {
+            panic!(
+                "Expected to have precompiled golem-worker-service at {:?}",
+                self.executable
+            );
+        }
+        // Resolved, so that startup failures name the exact file that was started
+        let executable =
+            std::fs::canonicalize(&self.executable).unwrap_or_else(|_| self.executable.clone());
+
+        let mut port_locks = Vec::new();
+        if let Some(timeout) = self.port_lock_timeout {
+            let mut ports = vec![self.http_port, self.grpc_port, self.custom_request_port];
+            // Always locked in the same order, so two harnesses can't wait for each other
+            ports.sort();
+            ports.dedup();
+            for port in ports {
+                let port_lock = PortLock::acquire(&self.working_directory, port, timeout)
+                    .await
+                    .expect("Failed to lock the ports of golem-worker-service");
+                port_locks.push(port_lock);
+            }
+        }
+
+        let mut child = Command::new(&executable)
+            .current_dir(&self.working_directory)
+            .envs(
+                self.env_vars
+                    .env_vars(
+                        self.http_port,
+                        self.grpc_port,
+                        self.custom_request_port,
+                        self.component_service,
+                        self.shard_manager,
+                        self.rdb,
+                        self.verbosity,
+                    )
+                    .await,
+            )
+            .envs(self.tls.iter().flat_map(|tls| tls.env_vars()))
+            .envs(self.env_overrides)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap_or_else(|err| {
+                panic!(
+                    "{}",
+                    startup_failure(&executable, &self.working_directory, &err.to_string(), &[])
+                )
+            });
+
+        let log_paths = self.log_directory.map(|dir| {
+            (
+                dir.join("golem-worker-service.stdout.log"),
+                dir.join("golem-worker-service.stderr.log"),
+            )
+        });
+
+        let logger = ChildProcessLogger::log_child_process_to_channel(
+            "[workersvc]",
+            self.out_level,
+            self.err_level,
+            log_paths
+                .as_ref()
+                .map(|(out_path, err_path)| (out_path.as_path(), err_path.as_path())),
+            &mut child,
+        )
+        .expect("Failed to create golem-worker-service log files");
+
+        let child = Arc::new(Mutex::new(Some(child)));
+
+        let host = self.host.as_str();
+        let grpc_port = self.grpc_port;
+        let timeout = Duration::from_secs(90);
+        let interval = self.startup_poll_interval;
+        let startup = async {
+            match &self.tls {
+                Some(tls) => wait_for_startup_tls(host, grpc_port, tls, timeout, interval).await,
+                None => wait_for_startup(host, grpc_port, timeout, interval).await,
+            }
+        };
+        tokio::select! {
+            _ = startup => {}
+            status = wait_for_exit(&child) => {
+                // The output of the process may still be being read
+                let deadline = Instant::now() + Duration::from_secs(1);
+                while !logger.is_finished() && Instant::now() < deadline {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                panic!(
+                    "{}",
+                    startup_failure(
+                        &executable,
+                        &self.working_directory,
+                        &format!("the process exited with {status}"),
+                        &logger.stderr_head(),
+                    )
+                );
+            }
+        }
+
+        let client = if self.client.is_some() {
+            self.client
+        } else if self.shared_client {
+            Some(
+                SpawnedWorkerService::connect(host, grpc_port, &self.tls, &self.client_options)
+                    .await
+                    .expect("Failed to create client"),
+            )
+        } else {
+            None
+        };
+
+        SpawnedWorkerService {
+            host: self.host,
+            http_port: self.http_port,
+            grpc_port,
+            custom_request_port: self.custom_request_port,
+            child,
+            logger,
+            log_paths,
+            tls: self.tls,
+            client_options: self.client_options,
+            client,
+            #[cfg(unix)]
+            kill_signal: self.kill_signal,
+            leak_on_drop: self.leak_on_drop,
+            _port_locks: port_locks,
+        }
     }
 }
 
+// How long a process stopped with a signal is waited for before it gets killed
+#[cfg(unix)]
+const KILL_SIGNAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+// How often a process stopped with a signal is checked for having exited
+#[cfg(unix)]
+const KILL_SIGNAL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Sends the signal, and waits for the process to exit. False if the process is still running.
+#[cfg(unix)]
+async fn stop_with_signal(child: &mut Child, signal: Signal) -> bool {
+    if !send_signal(child, signal) {
+        return false;
+    }
+
+    let deadline = Instant::now() + KILL_SIGNAL_TIMEOUT;
+    while Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(Some(_)) => return true,
+            Ok(None) => tokio::time::sleep(KILL_SIGNAL_POLL_INTERVAL).await,
+            Err(_) => return false,
+        }
+    }
+    warn!("golem-worker-service did not exit after {signal}, killing it");
+    false
+}
+
+// Same as `stop_with_signal`, but blocks the thread while waiting
+#[cfg(unix)]
+fn blocking_stop_with_signal(child: &mut Child, signal: Signal) -> bool {
+    if !send_signal(child, signal) {
+        return false;
+    }
+
+    let deadline = Instant::now() + KILL_SIGNAL_TIMEOUT;
+    while Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(Some(_)) => return true,
+            Ok(None) => std::thread::sleep(KILL_SIGNAL_POLL_INTERVAL),
+            Err(_) => return false,
+        }
+    }
+    warn!("golem-worker-service did not exit after {signal}, killing it");
+    false
+}
+
+#[cfg(unix)]
+fn send_signal(child: &Child, signal: Signal) -> bool {
+    let pid = nix::unistd::Pid::from_raw(child.id() as i32);
+    match nix::sys::signal::kill(pid, signal) {
+        Ok(()) => true,
+        Err(err) => {
+            warn!("Failed to send {signal} to golem-worker-service: {err}");
+            false
+        }
+    }
+}
+
+// Resolves once the process exited on its own
+async fn wait_for_exit(child: &Mutex<Option<Child>>) -> ExitStatus {
+    loop {
+        let status = child
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|child| child.try_wait().ok().flatten());
+        if let Some(status) = status {
+            return status;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+// Message of the panic of a failed startup, with the details needed to find out what went wrong
+fn startup_failure(
+    executable: &Path,
+    working_directory: &Path,
+    reason: &str,
+    stderr: &[String],
+) -> String {
+    let mut message = format!(
+        "Failed to start golem-worker-service: {reason}\n  executable: {}",
+        executable.display()
+    );
+    message.push_str(&format!(
+        "\n  working directory: {}",
+        working_directory.display()
+    ));
+    if !stderr.is_empty() {
+        message.push_str("\n  stderr:");
+        for line in stderr {
+            message.push_str("\n    ");
+            message.push_str(line);
+        }
+    }
+    message
+}
+
 impl Drop for SpawnedWorkerService {
     fn drop(&mut self) {
-        self.blocking_kill()
+        // Closing the connection before the process goes away avoids connection reset warnings
+        self.close_client();
+
+        if self.leak_on_drop {
+            if let Some(child) = self.child.lock().unwrap().as_ref() {
+                warn!(
+                    "Leaving golem-worker-service running with PID {} on {} (http: {}, grpc: {}, custom requests: {})",
+                    child.id(),
+                    self.host,
+                    self.http_port,
+                    self.grpc_port,
+                    self.custom_request_port
+                );
+            }
+        } else {
+            self.blocking_kill()
+        }
     }
 }
@@ -62,7 +62,7 @@ use crate::components::worker_executor_cluster::WorkerExecutorCluster;
 use crate::components::worker_service::docker::DockerWorkerService;
 use crate::components::worker_service::k8s::K8sWorkerService;
 use crate::components::worker_service::provided::ProvidedWorkerService;
-use crate::components::worker_service::spawned::SpawnedWorkerService;
+use crate::components::worker_service::spawned::SpawnedWorkerServiceBuilder;
 use crate::components::worker_service::WorkerService;
 use crate::config::{TestDependencies, TestService};
 use crate::dsl::benchmark::{BenchmarkConfig, RunConfig};
@@ -74,14 +74,16 @@ use crate::dsl::benchmark::{BenchmarkConfig, RunConfig};
 #[allow(dead_code)]
 #[derive(Clone)]
 pub struct CliTestDependencies {
+    // Fields are dropped in declaration order, so without an explicit shutdown the worker
+    // executors go first and the stores last, as in `Teardown::shutdown`
+    worker_executor_cluster: Arc<dyn WorkerExecutorCluster + Send + Sync + 'static>,
+    worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
+    component_compilation_service: Arc<dyn ComponentCompilationService + Send + Sync + 'static>,
+    component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+    shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
     rdb: Arc<dyn Rdb + Send + Sync + 'static>,
-    redis: Arc<dyn Redis + Send + Sync + 'static>,
     redis_monitor: Arc<dyn RedisMonitor + Send + Sync + 'static>,
-    shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
-    component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
-    component_compilation_service: Arc<dyn ComponentCompilationService + Send + Sync + 'static>,
-    worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
-    worker_executor_cluster: Arc<dyn WorkerExecutorCluster + Send + Sync + 'static>,
+    redis: Arc<dyn Redis + Send + Sync + 'static>,
     blob_storage: Arc<dyn BlobStorage + Send + Sync + 'static>,
     initial_component_files_service: Arc<InitialComponentFilesService>,
     component_directory: PathBuf,
@@ -562,20 +564,21 @@ impl CliTestDependencies {
                 .expect("Failed to join.");
 
         let worker_service: Arc<dyn WorkerService + Send + Sync + 'static> = Arc::new(
-            SpawnedWorkerService::new(
+            SpawnedWorkerServiceBuilder::new(
                 &build_root.join("golem-worker-service"),
                 &workspace_root.join("golem-worker-service"),
-                worker_service_http_port,
-                worker_service_grpc_port,
-                worker_service_custom_request_port,
                 component_service.clone(),
                 shard_manager.clone(),
                 rdb.clone(),
-                params.service_verbosity(),
-                out_level,
-                Level::ERROR,
-                true,
             )
+            .with_ports(
+                worker_service_http_port,
+                worker_service_grpc_port,
+                worker_service_custom_request_port,
+            )
+            .with_levels(params.service_verbosity(), out_level, Level::ERROR)
+            .with_shared_client(true)
+            .build()
             .await,
         );
         let worker_executor_cluster: Arc<dyn WorkerExecutorCluster + Send + Sync + 'static> =
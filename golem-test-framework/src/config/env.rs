@@ -35,7 +35,7 @@ use crate::components::worker_executor_cluster::docker::DockerWorkerExecutorClus
 use crate::components::worker_executor_cluster::spawned::SpawnedWorkerExecutorCluster;
 use crate::components::worker_executor_cluster::WorkerExecutorCluster;
 use crate::components::worker_service::docker::DockerWorkerService;
-use crate::components::worker_service::spawned::SpawnedWorkerService;
+use crate::components::worker_service::spawned::SpawnedWorkerServiceBuilder;
 use crate::components::worker_service::WorkerService;
 use crate::config::{DbType, TestDependencies};
 use async_trait::async_trait;
@@ -156,14 +156,16 @@ impl Default for EnvBasedTestDependenciesConfig {
 #[derive(Clone)]
 pub struct EnvBasedTestDependencies {
     config: Arc<EnvBasedTestDependenciesConfig>,
+    // Fields are dropped in declaration order, so without an explicit shutdown the worker
+    // executors go first and the stores last, as in `Teardown::shutdown`
+    worker_executor_cluster: Arc<dyn WorkerExecutorCluster + Send + Sync + 'static>,
+    worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
+    component_compilation_service: Arc<dyn ComponentCompilationService + Send + Sync + 'static>,
+    component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
+    shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
     rdb: Arc<dyn Rdb + Send + Sync + 'static>,
-    redis: Arc<dyn Redis + Send + Sync + 'static>,
     redis_monitor: Arc<dyn RedisMonitor + Send + Sync + 'static>,
-    shard_manager: Arc<dyn ShardManager + Send + Sync + 'static>,
-    component_service: Arc<dyn ComponentService + Send + Sync + 'static>,
-    component_compilation_service: Arc<dyn ComponentCompilationService + Send + Sync + 'static>,
-    worker_service: Arc<dyn WorkerService + Send + Sync + 'static>,
-    worker_executor_cluster: Arc<dyn WorkerExecutorCluster + Send + Sync + 'static>,
+    redis: Arc<dyn Redis + Send + Sync + 'static>,
     blob_storage: Arc<dyn BlobStorage + Send + Sync + 'static>,
     initial_component_files_service: Arc<InitialComponentFilesService>,
 }
@@ -346,20 +348,21 @@ impl EnvBasedTestDependencies {
             )
         } else {
             Arc::new(
-                SpawnedWorkerService::new(
+                SpawnedWorkerServiceBuilder::new(
                     Path::new("../target/debug/golem-worker-service"),
                     Path::new("../golem-worker-service"),
-                    8082,
-                    9092,
-                    9093,
                     component_service,
                     shard_manager,
                     rdb,
+                )
+                .with_ports(8082, 9092, 9093)
+                .with_levels(
                     config.default_verbosity(),
                     config.default_stdout_level(),
                     config.default_stderr_level(),
-                    config.shared_client,
                 )
+                .with_shared_client(config.shared_client)
+                .build()
                 .await,
             )
         }
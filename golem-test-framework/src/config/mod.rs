@@ -21,6 +21,8 @@ use golem_service_base::service::initial_component_files::InitialComponentFilesS
 use golem_service_base::storage::blob::BlobStorage;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
 
 use crate::components::component_service::ComponentService;
 use crate::components::rdb::Rdb;
@@ -63,6 +65,39 @@ pub trait TestDependencies {
     }
 }
 
+/// Shuts down the test dependencies in dependency order: the worker executors, the worker
+/// service, the component services, the shard manager and finally the stores. Dropping the
+/// dependencies is only a fallback for when the harness could not call `shutdown`.
+#[async_trait]
+pub trait Teardown {
+    async fn shutdown(&self);
+}
+
+#[async_trait]
+impl<T: TestDependencies + Send + Sync> Teardown for T {
+    async fn shutdown(&self) {
+        info!("Shutting down the test dependencies");
+
+        // Waiting for the executors to exit, so none of them outlives the shard manager
+        let still_running = self
+            .worker_executor_cluster()
+            .kill_all_and_wait(Duration::from_secs(10))
+            .await;
+        if !still_running.is_empty() {
+            warn!("Worker executors {still_running:?} were still running at shutdown");
+        }
+
+        self.worker_service().kill().await;
+        self.component_compilation_service().kill().await;
+        self.component_service().kill().await;
+        self.shard_manager().kill().await;
+
+        self.rdb().kill().await;
+        self.redis_monitor().kill();
+        self.redis().kill().await;
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum DbType {
     Postgres,
@@ -13,12 +13,12 @@
 // limitations under the License.
 
 use std::fmt::{Debug, Formatter};
+use std::time::Duration;
 
 use golem_api_grpc::proto::golem::apidefinition::v1::{api_definition_error, ApiDefinitionError};
 use golem_api_grpc::proto::golem::worker;
 use golem_common::metrics::api::TraceErrorKind;
 use golem_common::SafeDisplay;
-use golem_service_base::model::ErrorBody;
 use poem_openapi::payload::Json;
 use poem_openapi::{ApiResponse, Object, Union};
 
@@ -34,11 +34,21 @@ pub enum WorkerServiceErrorsBody {
 #[derive(Clone, Debug, Object)]
 pub struct MessagesErrorsBody {
     errors: Vec<String>,
+    code: String,
 }
 
 #[derive(Clone, Debug, Object)]
 pub struct ValidationErrorsBody {
     errors: Vec<String>,
+    code: String,
+}
+
+// Every error body carries a stable, machine-readable `code` (e.g. `api_definition.not_found`)
+// next to the human-readable message, so clients can branch on the code instead of the text.
+#[derive(Clone, Debug, Object)]
+pub struct ApiEndpointErrorBody {
+    pub error: String,
+    pub code: String,
 }
 
 #[derive(ApiResponse, Clone, Debug)]
@@ -46,15 +56,22 @@ pub enum ApiEndpointError {
     #[oai(status = 400)]
     BadRequest(Json<WorkerServiceErrorsBody>),
     #[oai(status = 401)]
-    Unauthorized(Json<ErrorBody>),
+    Unauthorized(Json<ApiEndpointErrorBody>),
     #[oai(status = 403)]
-    Forbidden(Json<ErrorBody>),
+    Forbidden(Json<ApiEndpointErrorBody>),
     #[oai(status = 404)]
-    NotFound(Json<ErrorBody>),
+    NotFound(Json<ApiEndpointErrorBody>),
     #[oai(status = 409)]
-    AlreadyExists(Json<String>),
+    AlreadyExists(Json<ApiEndpointErrorBody>),
+    #[oai(status = 412)]
+    PreconditionFailed(Json<ApiEndpointErrorBody>),
+    #[oai(status = 429)]
+    TooManyRequests(
+        Json<ApiEndpointErrorBody>,
+        #[oai(header = "Retry-After")] u64,
+    ),
     #[oai(status = 500)]
-    InternalError(Json<ErrorBody>),
+    InternalError(Json<ApiEndpointErrorBody>),
 }
 
 impl TraceErrorKind for ApiEndpointError {
@@ -63,6 +80,8 @@ impl TraceErrorKind for ApiEndpointError {
             ApiEndpointError::BadRequest(_) => "BadRequest",
             ApiEndpointError::NotFound(_) => "NotFound",
             ApiEndpointError::AlreadyExists(_) => "AlreadyExists",
+            ApiEndpointError::PreconditionFailed(_) => "PreconditionFailed",
+            ApiEndpointError::TooManyRequests(_, _) => "TooManyRequests",
             ApiEndpointError::Forbidden(_) => "Forbidden",
             ApiEndpointError::Unauthorized(_) => "Unauthorized",
             ApiEndpointError::InternalError(_) => "InternalError",
@@ -71,40 +90,76 @@ impl TraceErrorKind for ApiEndpointError {
 }
 
 impl ApiEndpointError {
-    pub fn unauthorized<T: SafeDisplay>(error: T) -> Self {
-        Self::Unauthorized(Json(ErrorBody {
-            error: error.to_safe_string(),
-        }))
+    pub fn unauthorized<T: SafeDisplay>(code: &'static str, error: T) -> Self {
+        Self::Unauthorized(Json(ApiEndpointErrorBody::new(code, error)))
     }
 
-    pub fn forbidden<T: SafeDisplay>(error: T) -> Self {
-        Self::Forbidden(Json(ErrorBody {
-            error: error.to_safe_string(),
-        }))
+    pub fn forbidden<T: SafeDisplay>(code: &'static str, error: T) -> Self {
+        Self::Forbidden(Json(ApiEndpointErrorBody::new(code, error)))
     }
 
-    pub fn internal<T: SafeDisplay>(error: T) -> Self {
-        Self::InternalError(Json(ErrorBody {
-            error: error.to_safe_string(),
-        }))
+    pub fn internal<T: SafeDisplay>(code: &'static str, error: T) -> Self {
+        Self::InternalError(Json(ApiEndpointErrorBody::new(code, error)))
     }
 
-    pub fn bad_request<T: SafeDisplay>(error: T) -> Self {
+    pub fn bad_request<T: SafeDisplay>(code: &'static str, error: T) -> Self {
         Self::BadRequest(Json(WorkerServiceErrorsBody::Messages(
             MessagesErrorsBody {
                 errors: vec![error.to_safe_string()],
+                code: code.to_string(),
             },
         )))
     }
 
-    pub fn not_found<T: SafeDisplay>(error: T) -> Self {
-        Self::NotFound(Json(ErrorBody {
-            error: error.to_safe_string(),
-        }))
+    pub fn not_found<T: SafeDisplay>(code: &'static str, error: T) -> Self {
+        Self::NotFound(Json(ApiEndpointErrorBody::new(code, error)))
+    }
+
+    pub fn already_exists<T: SafeDisplay>(code: &'static str, error: T) -> Self {
+        Self::AlreadyExists(Json(ApiEndpointErrorBody::new(code, error)))
+    }
+
+    pub fn precondition_failed<T: SafeDisplay>(code: &'static str, error: T) -> Self {
+        Self::PreconditionFailed(Json(ApiEndpointErrorBody::new(code, error)))
+    }
+
+    // The Retry-After header is in whole seconds, rounded up so clients never retry too early
+    pub fn too_many_requests<T: SafeDisplay>(
+        code: &'static str,
+        error: T,
+        retry_after: Duration,
+    ) -> Self {
+        let retry_after = retry_after
+            .as_secs()
+            .saturating_add(u64::from(retry_after.subsec_nanos() > 0));
+        Self::TooManyRequests(Json(ApiEndpointErrorBody::new(code, error)), retry_after)
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            ApiEndpointError::BadRequest(Json(WorkerServiceErrorsBody::Messages(body))) => {
+                &body.code
+            }
+            ApiEndpointError::BadRequest(Json(WorkerServiceErrorsBody::Validation(body))) => {
+                &body.code
+            }
+            ApiEndpointError::Unauthorized(Json(body))
+            | ApiEndpointError::Forbidden(Json(body))
+            | ApiEndpointError::NotFound(Json(body))
+            | ApiEndpointError::AlreadyExists(Json(body))
+            | ApiEndpointError::PreconditionFailed(Json(body))
+            | ApiEndpointError::TooManyRequests(Json(body), _)
+            | ApiEndpointError::InternalError(Json(body)) => &body.code,
+        }
     }
+}
 
-    pub fn already_exists<T: SafeDisplay>(error: T) -> Self {
-        Self::AlreadyExists(Json(error.to_safe_string()))
+impl ApiEndpointErrorBody {
+    fn new<T: SafeDisplay>(code: &'static str, error: T) -> Self {
+        Self {
+            error: error.to_safe_string(),
+            code: code.to_string(),
+        }
     }
 }
 
@@ -181,8 +236,12 @@ mod conversion {
                 SecuritySchemeServiceError::IdentityProviderError(identity_provider_error) => {
                     ApiEndpointError::from(identity_provider_error)
                 }
-                SecuritySchemeServiceError::InternalError(_) => ApiEndpointError::internal(value),
-                SecuritySchemeServiceError::NotFound(_) => ApiEndpointError::not_found(value),
+                SecuritySchemeServiceError::InternalError(_) => {
+                    ApiEndpointError::internal("security_scheme.internal", value)
+                }
+                SecuritySchemeServiceError::NotFound(_) => {
+                    ApiEndpointError::not_found("security_scheme.not_found", value)
+                }
             }
         }
     }
@@ -191,19 +250,28 @@ mod conversion {
         fn from(value: IdentityProviderError) -> Self {
             match value {
                 IdentityProviderError::ClientInitError(error) => {
-                    ApiEndpointError::internal(safe(error))
+                    ApiEndpointError::internal("identity_provider.client_init_failed", safe(error))
                 }
                 IdentityProviderError::InvalidIssuerUrl(error) => {
-                    ApiEndpointError::bad_request(safe(error))
+                    ApiEndpointError::bad_request(
+                        "identity_provider.invalid_issuer_url",
+                        safe(error),
+                    )
                 }
                 IdentityProviderError::FailedToDiscoverProviderMetadata(error) => {
-                    ApiEndpointError::bad_request(safe(error))
+                    ApiEndpointError::bad_request("identity_provider.discovery_failed", safe(error))
                 }
                 IdentityProviderError::FailedToExchangeCodeForTokens(error) => {
-                    ApiEndpointError::unauthorized(safe(error))
+                    ApiEndpointError::unauthorized(
+                        "identity_provider.token_exchange_failed",
+                        safe(error),
+                    )
                 }
                 IdentityProviderError::IdTokenVerificationError(error) => {
-                    ApiEndpointError::unauthorized(safe(error))
+                    ApiEndpointError::unauthorized(
+                        "identity_provider.id_token_verification_failed",
+                        safe(error),
+                    )
                 }
             }
         }
@@ -214,25 +282,43 @@ mod conversion {
             match error {
                 ApiDefinitionServiceError::ValidationError(e) => e.into(),
                 ApiDefinitionServiceError::ComponentNotFoundError(_) => {
-                    ApiEndpointError::bad_request(error)
+                    ApiEndpointError::bad_request("api_definition.component_not_found", error)
                 }
                 ApiDefinitionServiceError::ApiDefinitionNotDraft(_) => {
-                    ApiEndpointError::bad_request(error)
+                    ApiEndpointError::bad_request("api_definition.not_draft", error)
                 }
                 ApiDefinitionServiceError::ApiDefinitionNotFound(_) => {
-                    ApiEndpointError::not_found(error)
+                    ApiEndpointError::not_found("api_definition.not_found", error)
                 }
                 ApiDefinitionServiceError::ApiDefinitionAlreadyExists(_) => {
-                    ApiEndpointError::already_exists(error)
+                    ApiEndpointError::already_exists("api_definition.already_exists", error)
                 }
                 ApiDefinitionServiceError::ApiDefinitionDeployed(_) => {
-                    ApiEndpointError::bad_request(error)
+                    ApiEndpointError::bad_request("api_definition.deployed", error)
+                }
+                ApiDefinitionServiceError::ApiDefinitionLimitExceeded(_) => {
+                    ApiEndpointError::already_exists("api_definition.limit_exceeded", error)
+                }
+                ApiDefinitionServiceError::ApiDefinitionReserved(_, _) => {
+                    ApiEndpointError::already_exists("api_definition.reserved", error)
+                }
+                ApiDefinitionServiceError::ApiDefinitionModified(_, _) => {
+                    ApiEndpointError::precondition_failed(
+                        "api_definition.precondition_failed",
+                        error,
+                    )
+                }
+                ApiDefinitionServiceError::ReservationNotFound => {
+                    ApiEndpointError::not_found("api_definition.reservation_not_found", error)
+                }
+                ApiDefinitionServiceError::ReservationMismatch(_, _) => {
+                    ApiEndpointError::bad_request("api_definition.reservation_mismatch", error)
                 }
                 ApiDefinitionServiceError::RibCompilationErrors(_) => {
-                    ApiEndpointError::bad_request(error)
+                    ApiEndpointError::bad_request("api_definition.rib_compilation_failed", error)
                 }
                 ApiDefinitionServiceError::InternalRepoError(_) => {
-                    ApiEndpointError::internal(error)
+                    ApiEndpointError::internal("api_definition.internal", error)
                 }
                 ApiDefinitionServiceError::SecuritySchemeError(error) => {
                     ApiEndpointError::from(error)
@@ -240,7 +326,9 @@ mod conversion {
                 ApiDefinitionServiceError::IdentityProviderError(error) => {
                     ApiEndpointError::from(error)
                 }
-                ApiDefinitionServiceError::Internal(_) => ApiEndpointError::internal(error),
+                ApiDefinitionServiceError::Internal(_) => {
+                    ApiEndpointError::internal("api_definition.internal", error)
+                }
             }
         }
     }
@@ -249,23 +337,25 @@ mod conversion {
         fn from(error: ApiDeploymentError<Namespace>) -> Self {
             match error {
                 ApiDeploymentError::ApiDefinitionNotFound(_, _) => {
-                    ApiEndpointError::not_found(error)
+                    ApiEndpointError::not_found("api_deployment.api_definition_not_found", error)
                 }
                 ApiDeploymentError::ApiDeploymentNotFound(_, _) => {
-                    ApiEndpointError::not_found(error)
+                    ApiEndpointError::not_found("api_deployment.not_found", error)
                 }
                 ApiDeploymentError::ApiDeploymentConflict(_) => {
-                    ApiEndpointError::already_exists(error)
+                    ApiEndpointError::already_exists("api_deployment.already_exists", error)
                 }
                 ApiDeploymentError::ApiDefinitionsConflict(_) => {
-                    ApiEndpointError::bad_request(error)
+                    ApiEndpointError::bad_request("api_deployment.api_definitions_conflict", error)
+                }
+                ApiDeploymentError::InternalRepoError(_) => {
+                    ApiEndpointError::internal("api_deployment.internal", error)
                 }
-                ApiDeploymentError::InternalRepoError(_) => ApiEndpointError::internal(error),
                 ApiDeploymentError::InternalConversionError { .. } => {
-                    ApiEndpointError::internal(error)
+                    ApiEndpointError::internal("api_deployment.internal", error)
                 }
                 ApiDeploymentError::ComponentConstraintCreateError(_) => {
-                    ApiEndpointError::internal(error)
+                    ApiEndpointError::internal("api_deployment.component_constraint_failed", error)
                 }
             }
         }
@@ -275,6 +365,7 @@ mod conversion {
         fn from(error: ValidationErrors) -> Self {
             let error = WorkerServiceErrorsBody::Validation(ValidationErrorsBody {
                 errors: error.errors,
+                code: "api_definition.validation_failed".to_string(),
             });
 
             ApiEndpointError::BadRequest(Json(error))
@@ -328,6 +419,31 @@ mod conversion {
                         errors: vec![error.to_safe_string()],
                     })),
                 },
+                ApiDefinitionServiceError::ApiDefinitionLimitExceeded(_) => ApiDefinitionError {
+                    error: Some(api_definition_error::Error::LimitExceeded(ErrorBody {
+                        error: error.to_safe_string(),
+                    })),
+                },
+                ApiDefinitionServiceError::ApiDefinitionReserved(_, _) => ApiDefinitionError {
+                    error: Some(api_definition_error::Error::AlreadyExists(ErrorBody {
+                        error: error.to_safe_string(),
+                    })),
+                },
+                ApiDefinitionServiceError::ApiDefinitionModified(_, _) => ApiDefinitionError {
+                    error: Some(api_definition_error::Error::BadRequest(ErrorsBody {
+                        errors: vec![error.to_safe_string()],
+                    })),
+                },
+                ApiDefinitionServiceError::ReservationNotFound => ApiDefinitionError {
+                    error: Some(api_definition_error::Error::NotFound(ErrorBody {
+                        error: error.to_safe_string(),
+                    })),
+                },
+                ApiDefinitionServiceError::ReservationMismatch(_, _) => ApiDefinitionError {
+                    error: Some(api_definition_error::Error::BadRequest(ErrorsBody {
+                        errors: vec![error.to_safe_string()],
+                    })),
+                },
                 ApiDefinitionServiceError::ComponentNotFoundError(error) => ApiDefinitionError {
                     error: Some(api_definition_error::Error::NotFound(ErrorBody {
                         error: format!(
@@ -354,3 +470,49 @@ mod conversion {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::ApiEndpointError;
+    use crate::service::gateway::api_definition::ApiDefinitionError;
+    use crate::service::gateway::security_scheme::SecuritySchemeServiceError;
+    use golem_service_base::repo::RepoError;
+    use http::StatusCode;
+    use poem::IntoResponse;
+
+    fn status(error: ApiDefinitionError) -> StatusCode {
+        ApiEndpointError::from(error).into_response().status()
+    }
+
+    #[test]
+    fn internal_api_definition_errors_are_server_errors() {
+        assert_eq!(
+            status(ApiDefinitionError::Internal("simulated failure".to_string())),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(
+            status(ApiDefinitionError::InternalRepoError(RepoError::Internal(
+                "simulated failure".to_string()
+            ))),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(
+            status(ApiDefinitionError::SecuritySchemeError(
+                SecuritySchemeServiceError::InternalError("simulated failure".to_string())
+            )),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn invalid_api_definitions_are_bad_requests() {
+        assert_eq!(
+            status(ApiDefinitionError::RibCompilationErrors(
+                "invalid expression".to_string()
+            )),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::api::HttpApiDefinitionRequest;
+use poem_openapi::registry::Registry;
+use poem_openapi::types::Type;
+use serde_json::{json, Map, Value};
+
+const OPEN_API_REF_PREFIX: &str = "#/components/schemas/";
+const JSON_SCHEMA_REF_PREFIX: &str = "#/$defs/";
+
+// JSON Schema of the API definition documents accepted when creating or updating an API
+// definition. It is generated from the OpenAPI schemas the API itself is described with, so
+// it follows the changes of the request types.
+pub fn api_definition_json_schema() -> Value {
+    let mut registry = Registry::new();
+    HttpApiDefinitionRequest::register(&mut registry);
+
+    let definitions: Map<String, Value> = registry
+        .schemas
+        .iter()
+        .map(|(name, schema)| {
+            let schema = serde_json::to_value(schema).unwrap_or_else(|_| json!({}));
+            (name.clone(), to_json_schema(schema))
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "HttpApiDefinitionRequest",
+        "$ref": format!("{JSON_SCHEMA_REF_PREFIX}HttpApiDefinitionRequest"),
+        "$defs": definitions
+    })
+}
+
+// OpenAPI 3.0 schemas are JSON Schemas, except for where they are referenced from and for
+// `nullable`, which is a `null` type in JSON Schema
+fn to_json_schema(schema: Value) -> Value {
+    match schema {
+        Value::Object(mut object) => {
+            if let Some(Value::String(reference)) = object.get_mut("$ref") {
+                if let Some(name) = reference.strip_prefix(OPEN_API_REF_PREFIX) {
+                    *reference = format!("{JSON_SCHEMA_REF_PREFIX}{name}");
+                }
+            }
+
+            if object.remove("nullable") == Some(Value::Bool(true)) {
+                if let Some(Value::String(ty)) = object.get("type") {
+                    let ty = json!([ty, "null"]);
+                    object.insert("type".to_string(), ty);
+                }
+            }
+
+            Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, to_json_schema(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(to_json_schema).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+
+    #[test]
+    fn references_are_resolved_within_the_schema() {
+        let schema = api_definition_json_schema();
+        let definitions = schema["$defs"].as_object().unwrap();
+
+        assert_eq!(schema["$ref"], json!("#/$defs/HttpApiDefinitionRequest"));
+        assert!(definitions.contains_key("HttpApiDefinitionRequest"));
+        assert!(definitions.contains_key("RouteRequestData"));
+        assert_references_exist(&schema, definitions);
+    }
+
+    fn assert_references_exist(value: &Value, definitions: &Map<String, Value>) {
+        match value {
+            Value::Object(object) => {
+                if let Some(Value::String(reference)) = object.get("$ref") {
+                    let name = reference.strip_prefix(JSON_SCHEMA_REF_PREFIX).unwrap();
+                    assert!(definitions.contains_key(name), "{reference} is not defined");
+                }
+                object
+                    .values()
+                    .for_each(|value| assert_references_exist(value, definitions));
+            }
+            Value::Array(items) => items
+                .iter()
+                .for_each(|value| assert_references_exist(value, definitions)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn nullable_becomes_a_null_type() {
+        let schema = to_json_schema(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "nullable": true },
+                "binding": { "$ref": "#/components/schemas/GatewayBindingData" }
+            }
+        }));
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": ["string", "null"] },
+                    "binding": { "$ref": "#/$defs/GatewayBindingData" }
+                }
+            })
+        );
+    }
+}
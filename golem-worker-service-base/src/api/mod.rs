@@ -16,6 +16,8 @@ pub use common::*;
 pub use custom_http_request_api::*;
 pub use error::*;
 pub use healthcheck::*;
+pub use json_schema::*;
+pub use open_api_export::*;
 pub use register_api_definition_api::*;
 
 // Components and request data that can be reused for implementing server API endpoints
@@ -23,4 +25,6 @@ mod common;
 mod custom_http_request_api;
 mod error;
 mod healthcheck;
+mod json_schema;
+mod open_api_export;
 mod register_api_definition_api;
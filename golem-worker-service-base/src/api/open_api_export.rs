@@ -0,0 +1,289 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::api::{GatewayBindingResponseData, HttpApiDefinitionResponseData, RouteResponseData};
+use crate::gateway_api_definition::http::{AllPathPatterns, PathPattern};
+use crate::gateway_middleware::CorsPreflightExpr;
+use golem_common::model::GatewayBindingType;
+use serde_json::{json, Map, Value};
+
+// OpenAPI 3.0 document of a registered API definition, meant for client generators such as
+// openapi-typescript. Query variables of the routes become query parameters, and every
+// operation keeps its binding in the same extension that is read when importing OpenAPI.
+pub fn to_open_api_document(definition: &HttpApiDefinitionResponseData) -> Result<Value, String> {
+    let mut paths = Map::new();
+
+    for route in &definition.routes {
+        let path_pattern = AllPathPatterns::parse(&route.path)?;
+        let method = route.method.to_string().to_lowercase();
+        let operation = to_operation(route, &path_pattern, &method)?;
+
+        let path_item = paths
+            .entry(open_api_path(&path_pattern))
+            .or_insert_with(|| json!({}));
+        path_item[method] = operation;
+    }
+
+    Ok(json!({
+        "openapi": "3.0.0",
+        "info": {
+            "title": definition.id.0,
+            "version": definition.version.0
+        },
+        "x-golem-api-definition-id": definition.id.0,
+        "x-golem-api-definition-version": definition.version.0,
+        "paths": paths
+    }))
+}
+
+fn to_operation(
+    route: &RouteResponseData,
+    path_pattern: &AllPathPatterns,
+    method: &str,
+) -> Result<Value, String> {
+    let path_parameters = path_pattern
+        .path_patterns
+        .iter()
+        .filter_map(|pattern| match pattern {
+            PathPattern::Literal(_) => None,
+            PathPattern::Var(info) | PathPattern::CatchAllVar(info) => Some(info),
+        })
+        .map(|info| parameter(&info.key_name, "path"));
+    let query_parameters = path_pattern
+        .query_params
+        .iter()
+        .map(|info| parameter(&info.key_name, "query"));
+    let parameters: Vec<Value> = path_parameters.chain(query_parameters).collect();
+
+    let mut operation = Map::new();
+    operation.insert(
+        "operationId".to_string(),
+        json!(operation_id(method, path_pattern)),
+    );
+    if !parameters.is_empty() {
+        operation.insert("parameters".to_string(), Value::Array(parameters));
+    }
+    operation.insert(
+        "responses".to_string(),
+        json!({ "default": { "description": "Response of the binding" } }),
+    );
+    if let Some(security) = &route.security {
+        let mut requirement = Map::new();
+        requirement.insert(security.clone(), json!([]));
+        operation.insert("security".to_string(), json!([requirement]));
+    }
+    operation.insert(
+        "x-golem-api-gateway-binding".to_string(),
+        binding_extension(&route.binding)?,
+    );
+
+    Ok(Value::Object(operation))
+}
+
+fn parameter(name: &str, location: &str) -> Value {
+    json!({
+        "name": name,
+        "in": location,
+        "required": true,
+        "schema": { "type": "string" }
+    })
+}
+
+fn binding_extension(binding: &GatewayBindingResponseData) -> Result<Value, String> {
+    let binding_type = binding.binding_type.clone().unwrap_or_default();
+    let mut extension = Map::new();
+    extension.insert(
+        "binding-type".to_string(),
+        serde_json::to_value(&binding_type).map_err(|err| err.to_string())?,
+    );
+
+    match binding_type {
+        GatewayBindingType::CorsPreflight => {
+            if let Some(cors) = &binding.cors_preflight {
+                let response = rib::to_string(&CorsPreflightExpr::from_cors(cors).0)
+                    .map_err(|err| err.to_string())?;
+                extension.insert("response".to_string(), json!(response));
+            }
+        }
+        GatewayBindingType::Default | GatewayBindingType::FileServer => {
+            if let Some(component_id) = &binding.component_id {
+                extension.insert(
+                    "component-id".to_string(),
+                    json!(component_id.component_id.0.to_string()),
+                );
+                extension.insert(
+                    "component-version".to_string(),
+                    json!(component_id.version),
+                );
+            }
+            let fields = [
+                ("worker-name", &binding.worker_name),
+                ("idempotency-key", &binding.idempotency_key),
+                ("response", &binding.response),
+            ];
+            for (name, value) in fields {
+                if let Some(value) = value {
+                    extension.insert(name.to_string(), json!(value));
+                }
+            }
+            if let Some(timeout_ms) = binding.timeout_ms {
+                extension.insert("timeout-ms".to_string(), json!(timeout_ms));
+            }
+            if let Some(component_version) = binding.component_version {
+                extension.insert(
+                    "pinned-component-version".to_string(),
+                    json!(component_version),
+                );
+            }
+            if let Some(param_mappings) = &binding.param_mappings {
+                extension.insert("param-mappings".to_string(), json!(param_mappings));
+            }
+        }
+    }
+
+    Ok(Value::Object(extension))
+}
+
+// OpenAPI paths can't contain query variables or catch-all markers
+fn open_api_path(path_pattern: &AllPathPatterns) -> String {
+    if path_pattern.path_patterns.is_empty() {
+        return "/".to_string();
+    }
+
+    path_pattern
+        .path_patterns
+        .iter()
+        .map(|pattern| match pattern {
+            PathPattern::Literal(info) => format!("/{}", info.0),
+            PathPattern::Var(info) | PathPattern::CatchAllVar(info) => {
+                format!("/{{{}}}", info.key_name)
+            }
+        })
+        .collect()
+}
+
+// For example `get_users_user_id_cart` for GET /users/{user-id}/cart
+fn operation_id(method: &str, path_pattern: &AllPathPatterns) -> String {
+    let mut parts = vec![method.to_string()];
+    for pattern in &path_pattern.path_patterns {
+        match pattern {
+            PathPattern::Literal(info) => parts.push(info.0.clone()),
+            PathPattern::Var(info) | PathPattern::CatchAllVar(info) => {
+                parts.push(info.key_name.clone())
+            }
+        }
+    }
+
+    parts
+        .join("_")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use crate::gateway_api_definition::http::MethodPattern;
+    use crate::gateway_api_definition::{ApiDefinitionId, ApiVersion};
+    use golem_common::model::ComponentId;
+    use golem_service_base::model::VersionedComponentId;
+    use std::collections::BTreeMap;
+    use uuid::Uuid;
+
+    fn worker_route(method: MethodPattern, path: &str) -> RouteResponseData {
+        RouteResponseData {
+            method,
+            path: path.to_string(),
+            security: None,
+            binding: GatewayBindingResponseData {
+                component_id: Some(VersionedComponentId {
+                    component_id: ComponentId(
+                        Uuid::parse_str("2696abdc-df3a-4771-8215-d6af7aa4c408").unwrap(),
+                    ),
+                    version: 0,
+                }),
+                worker_name: Some("\"worker\"".to_string()),
+                idempotency_key: None,
+                response: Some("${1}".to_string()),
+                binding_type: Some(GatewayBindingType::Default),
+                response_mapping_input: None,
+                worker_name_input: None,
+                idempotency_key_input: None,
+                cors_preflight: None,
+                response_mapping_output: None,
+                timeout_ms: None,
+                component_version: None,
+                param_mappings: None,
+            },
+        }
+    }
+
+    #[test]
+    fn routes_become_operations() {
+        let definition = HttpApiDefinitionResponseData {
+            id: ApiDefinitionId("shopping-cart".to_string()),
+            version: ApiVersion("0.1.0".to_string()),
+            routes: vec![
+                worker_route(MethodPattern::Get, "/users/{user-id}/cart?{limit}"),
+                worker_route(MethodPattern::Post, "/users/{user-id}/cart"),
+            ],
+            draft: true,
+            labels: BTreeMap::new(),
+            created_at: None,
+        };
+
+        let document = to_open_api_document(&definition).unwrap();
+
+        assert_eq!(document["x-golem-api-definition-id"], json!("shopping-cart"));
+        let path_item = &document["paths"]["/users/{user-id}/cart"];
+        assert_eq!(
+            path_item["get"]["operationId"],
+            json!("get_users_user_id_cart")
+        );
+        assert_eq!(
+            path_item["post"]["operationId"],
+            json!("post_users_user_id_cart")
+        );
+        assert_eq!(
+            path_item["get"]["parameters"],
+            json!([
+                {
+                    "name": "user-id",
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" }
+                },
+                {
+                    "name": "limit",
+                    "in": "query",
+                    "required": true,
+                    "schema": { "type": "string" }
+                }
+            ])
+        );
+        assert_eq!(
+            path_item["get"]["x-golem-api-gateway-binding"],
+            json!({
+                "binding-type": "default",
+                "component-id": "2696abdc-df3a-4771-8215-d6af7aa4c408",
+                "component-version": 0,
+                "worker-name": "\"worker\"",
+                "response": "${1}"
+            })
+        );
+    }
+}
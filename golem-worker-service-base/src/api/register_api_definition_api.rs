@@ -12,29 +12,35 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::gateway_api_definition::canonical::canonical_hash;
 use crate::gateway_api_definition::http::{
     AllPathPatterns, CompiledHttpApiDefinition, CompiledRoute, MethodPattern, Route, RouteRequest,
 };
-use crate::gateway_api_definition::{ApiDefinitionId, ApiVersion};
+use crate::gateway_api_definition::{validate_labels, ApiDefinitionId, ApiVersion};
 use crate::gateway_api_deployment::ApiSite;
 use crate::gateway_binding::{
-    GatewayBinding, GatewayBindingCompiled, StaticBinding, WorkerBinding, WorkerBindingCompiled,
+    GatewayBinding, GatewayBindingCompiled, ParamMapping, StaticBinding, WorkerBinding,
+    WorkerBindingCompiled,
 };
 use crate::gateway_middleware::{CorsPreflightExpr, HttpCors, HttpMiddleware, HttpMiddlewares};
 use crate::gateway_security::{
     Provider, SecurityScheme, SecuritySchemeIdentifier, SecuritySchemeReference,
     SecuritySchemeWithProviderMetadata,
 };
+use crate::service::gateway::api_definition::ApiDefinitionReservation;
+use crate::service::gateway::audit::{AuditAction, AuditEvent};
 use golem_api_grpc::proto::golem::apidefinition as grpc_apidefinition;
 use golem_common::model::GatewayBindingType;
 use golem_service_base::model::VersionedComponentId;
 use openidconnect::{ClientId, ClientSecret, RedirectUrl, Scope};
 use poem_openapi::*;
-use rib::{RibInputTypeInfo, RibOutputTypeInfo};
+use rib::{RegistryKey, RibInputTypeInfo, RibOutputTypeInfo};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::ops::Deref;
 use std::result::Result;
 use std::time::SystemTime;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
 #[serde(rename_all = "camelCase")]
@@ -74,6 +80,9 @@ pub struct HttpApiDefinitionRequest {
     pub routes: Vec<RouteRequestData>,
     #[serde(default)]
     pub draft: bool,
+    #[serde(default)]
+    #[oai(default)]
+    pub labels: BTreeMap<String, String>,
 }
 
 // Mostly this data structures that represents the actual incoming request
@@ -88,6 +97,8 @@ pub struct HttpApiDefinitionRequestData {
     pub routes: Vec<RouteRequestData>,
     #[serde(default)]
     pub draft: bool,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
@@ -160,6 +171,8 @@ pub struct HttpApiDefinitionResponseData {
     pub routes: Vec<RouteResponseData>,
     #[serde(default)]
     pub draft: bool,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
@@ -182,11 +195,153 @@ impl<Namespace> TryFrom<CompiledHttpApiDefinition<Namespace>> for HttpApiDefinit
             version: value.version,
             routes,
             draft: value.draft,
+            labels: value.labels,
             created_at: Some(value.created_at),
         })
     }
 }
 
+impl HttpApiDefinitionResponseData {
+    // Strong entity tag of the canonical form of the definition, quoted as in an ETag header
+    pub fn entity_tag(&self) -> Result<String, String> {
+        let hash = canonical_hash(self).map_err(|e| e.to_string())?;
+        Ok(format!("\"{hash}\""))
+    }
+}
+
+// Overview of an API definition, without the routes themselves
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ApiDefinitionSummary {
+    pub id: ApiDefinitionId,
+    pub version: ApiVersion,
+    pub route_count: u64,
+    pub draft: bool,
+    pub labels: BTreeMap<String, String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl<Namespace> From<CompiledHttpApiDefinition<Namespace>> for ApiDefinitionSummary {
+    fn from(value: CompiledHttpApiDefinition<Namespace>) -> Self {
+        // Auth call back routes are internal, and not listed in HttpApiDefinitionResponseData
+        let route_count = value
+            .routes
+            .iter()
+            .filter(|route| !route.binding.is_static_auth_call_back_binding())
+            .count();
+
+        Self {
+            id: value.id,
+            version: value.version,
+            route_count: route_count as u64,
+            draft: value.draft,
+            labels: value.labels,
+            created_at: value.created_at,
+        }
+    }
+}
+
+// A route of the routing table flattened across API definitions. Routes of different
+// definitions with the same method and path are flagged as conflicting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct RoutingTableEntry {
+    pub method: MethodPattern,
+    pub path: String,
+    pub binding_type: GatewayBindingType,
+    pub component_id: Option<VersionedComponentId>,
+    pub functions: Vec<String>,
+    pub definition_id: ApiDefinitionId,
+    pub definition_version: ApiVersion,
+    pub conflicting: bool,
+}
+
+impl RoutingTableEntry {
+    // Sorted by path, then method, then source definition
+    pub fn from_definitions<Namespace>(
+        definitions: Vec<CompiledHttpApiDefinition<Namespace>>,
+    ) -> Vec<Self> {
+        let mut entries = Vec::new();
+        for definition in definitions {
+            for route in definition.routes {
+                if route.binding.is_static_auth_call_back_binding() {
+                    continue;
+                }
+
+                let (binding_type, worker_binding) = match route.binding {
+                    GatewayBindingCompiled::Worker(binding) => {
+                        (GatewayBindingType::Default, Some(binding))
+                    }
+                    GatewayBindingCompiled::FileServer(binding) => {
+                        (GatewayBindingType::FileServer, Some(binding))
+                    }
+                    GatewayBindingCompiled::Static(_) => (GatewayBindingType::CorsPreflight, None),
+                };
+                let functions = worker_binding
+                    .as_ref()
+                    .and_then(|binding| binding.response_compiled.worker_calls.as_ref())
+                    .map(|calls| {
+                        let mut functions = calls
+                            .function_calls
+                            .iter()
+                            .map(|call| function_name(&call.function_key))
+                            .collect::<Vec<_>>();
+                        functions.sort();
+                        functions
+                    })
+                    .unwrap_or_default();
+
+                entries.push(Self {
+                    method: route.method,
+                    path: route.path.to_string(),
+                    binding_type,
+                    component_id: worker_binding.map(|binding| binding.component_id),
+                    functions,
+                    definition_id: definition.id.clone(),
+                    definition_version: definition.version.clone(),
+                    conflicting: false,
+                });
+            }
+        }
+
+        entries.sort_by_key(|entry| {
+            (
+                entry.path.clone(),
+                entry.method.to_string(),
+                entry.definition_id.0.clone(),
+                entry.definition_version.0.clone(),
+            )
+        });
+
+        let mut definitions_by_route: HashMap<(MethodPattern, String), Vec<ApiDefinitionId>> =
+            HashMap::new();
+        for entry in &entries {
+            definitions_by_route
+                .entry((entry.method.clone(), entry.path.clone()))
+                .or_default()
+                .push(entry.definition_id.clone());
+        }
+        for entry in &mut entries {
+            let definitions = &definitions_by_route[&(entry.method.clone(), entry.path.clone())];
+            entry.conflicting = definitions.iter().any(|id| id != &entry.definition_id);
+        }
+
+        entries
+    }
+}
+
+fn function_name(key: &RegistryKey) -> String {
+    match key {
+        RegistryKey::FunctionName(function_name) => function_name.clone(),
+        RegistryKey::FunctionNameWithInterface {
+            interface_name,
+            function_name,
+        } => format!("{interface_name}.{{{function_name}}}"),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
 pub struct RouteRequestData {
     pub method: MethodPattern,
@@ -321,6 +476,12 @@ pub struct GatewayBindingData {
     // For binding type - worker
     // Optional only to keep backward compatibility
     pub response: Option<String>,
+    // For binding type - worker
+    pub timeout_ms: Option<u64>,
+    // For binding type - worker
+    pub component_version: Option<u64>,
+    // For binding type - worker
+    pub param_mappings: Option<BTreeMap<String, String>>,
 
     // CORS binding type
     //  For binding type - cors-middleware
@@ -365,6 +526,9 @@ impl GatewayBindingData {
             worker_name: worker_id,
             idempotency_key,
             response: Some(response),
+            timeout_ms: worker_binding.timeout_ms,
+            component_version: worker_binding.component_version,
+            param_mappings: param_mappings_data(&worker_binding.param_mappings),
             allow_origin: None,
             allow_methods: None,
             allow_headers: None,
@@ -459,6 +623,9 @@ pub struct GatewayBindingResponseData {
     pub idempotency_key_input: Option<RibInputTypeInfo>, // If bindingType is Default or FilerServer
     pub cors_preflight: Option<HttpCors>, // If bindingType is CorsPreflight (internally, a static binding)
     pub response_mapping_output: Option<RibOutputTypeInfo>, // If bindingType is Default or FileServer
+    pub timeout_ms: Option<u64>, // If bindingType is Default or FileServer
+    pub component_version: Option<u64>, // If bindingType is Default or FileServer
+    pub param_mappings: Option<BTreeMap<String, String>>, // If bindingType is Default or FileServer
 }
 
 impl GatewayBindingResponseData {
@@ -491,10 +658,24 @@ impl GatewayBindingResponseData {
                 .map(|idempotency_key_compiled| idempotency_key_compiled.rib_input),
             cors_preflight: None,
             response_mapping_output: worker_binding.response_compiled.rib_output,
+            timeout_ms: worker_binding.timeout_ms,
+            component_version: worker_binding.component_version,
+            param_mappings: param_mappings_data(&worker_binding.param_mappings),
         }
     }
 }
 
+// Bindings without parameter mappings leave the field out, as before mappings existed
+fn param_mappings_data(
+    param_mappings: &BTreeMap<String, ParamMapping>,
+) -> Option<BTreeMap<String, String>> {
+    if param_mappings.is_empty() {
+        None
+    } else {
+        Some(ParamMapping::to_strings(param_mappings))
+    }
+}
+
 impl TryFrom<GatewayBindingCompiled> for GatewayBindingResponseData {
     type Error = String;
 
@@ -535,6 +716,9 @@ impl TryFrom<GatewayBindingCompiled> for GatewayBindingResponseData {
                     idempotency_key_input: None,
                     cors_preflight: static_binding.get_cors_preflight(),
                     response_mapping_output: None,
+                    timeout_ms: None,
+                    component_version: None,
+                    param_mappings: None,
                 })
             }
         }
@@ -583,6 +767,7 @@ impl TryFrom<crate::gateway_api_definition::http::HttpApiDefinition>
             version: value.version,
             routes,
             draft: value.draft,
+            labels: value.labels,
             created_at: Some(value.created_at),
         })
     }
@@ -596,10 +781,38 @@ impl TryInto<crate::gateway_api_definition::http::HttpApiDefinitionRequest>
     fn try_into(
         self,
     ) -> Result<crate::gateway_api_definition::http::HttpApiDefinitionRequest, Self::Error> {
+        self.into_core_request(false)
+    }
+}
+
+impl HttpApiDefinitionRequest {
+    // Routes with the same method and path are rejected, unless `allow_duplicate_routes` is set.
+    // In that case only the first of them is kept, as it shadows the others.
+    pub fn into_core_request(
+        self,
+        allow_duplicate_routes: bool,
+    ) -> Result<crate::gateway_api_definition::http::HttpApiDefinitionRequest, String> {
+        validate_labels(&self.labels)?;
+
         let mut routes = Vec::new();
+        let mut first_indices: HashMap<(MethodPattern, AllPathPatterns), usize> = HashMap::new();
+
+        for (index, route_request_data) in self.routes.into_iter().enumerate() {
+            let v = RouteRequest::try_from(route_request_data)
+                .map_err(|err| format!("Invalid route routes[{}]: {}", index, err))?;
+
+            let key = (v.method.clone(), v.path.clone());
+            if let Some(first_index) = first_indices.get(&key) {
+                if allow_duplicate_routes {
+                    continue;
+                }
+                return Err(format!(
+                    "Duplicate route: routes[{}] and routes[{}] are both {} {}",
+                    first_index, index, v.method, v.path
+                ));
+            }
+            first_indices.insert(key, index);
 
-        for route_request_data in self.routes {
-            let v = RouteRequest::try_from(route_request_data)?;
             routes.push(v);
         }
 
@@ -612,6 +825,7 @@ impl TryInto<crate::gateway_api_definition::http::HttpApiDefinitionRequest>
                     .map(|x| x.into_iter().map(SecuritySchemeReference::new).collect()),
                 routes,
                 draft: self.draft,
+                labels: self.labels,
             },
         )
     }
@@ -638,6 +852,9 @@ impl TryFrom<GatewayBinding> for GatewayBindingData {
                     worker_name: None,
                     idempotency_key: None,
                     response: None,
+                    timeout_ms: None,
+                    component_version: None,
+                    param_mappings: None,
                     allow_origin: Some(cors.get_allow_origin()),
                     allow_methods: Some(cors.get_allow_methods()),
                     allow_headers: Some(cors.get_allow_headers()),
@@ -685,11 +902,24 @@ impl TryFrom<GatewayBindingData> for GatewayBinding {
                     None
                 };
 
+                let timeout_ms =
+                    WorkerBinding::validate_timeout_ms(gateway_binding_data.timeout_ms)?;
+                let component_version = WorkerBinding::validate_component_version(
+                    gateway_binding_data.component_version,
+                    &worker_name,
+                )?;
+                let param_mappings = ParamMapping::parse_all(
+                    gateway_binding_data.param_mappings.unwrap_or_default(),
+                )?;
+
                 let worker_binding = WorkerBinding {
                     component_id,
                     worker_name,
                     idempotency_key,
                     response_mapping: response,
+                    timeout_ms,
+                    component_version,
+                    param_mappings,
                 };
 
                 if v == Some(GatewayBindingType::FileServer) {
@@ -793,6 +1023,8 @@ impl TryFrom<grpc_apidefinition::v1::ApiDefinitionRequest>
             version: ApiVersion(value.version),
             routes: route_requests,
             draft: value.draft,
+            // Labels are not part of the gRPC API
+            labels: BTreeMap::new(),
             security,
         };
 
@@ -854,7 +1086,7 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CompiledHttpRoute> for
     fn try_from(
         value: golem_api_grpc::proto::golem::apidefinition::CompiledHttpRoute,
     ) -> Result<Self, Self::Error> {
-        let method = MethodPattern::try_from(value.method)?;
+        let method = parse_method(value.method, &value.path)?;
         let path = AllPathPatterns::parse(value.path.as_str()).map_err(|e| e.to_string())?;
         let binding_proto = value.binding.ok_or("binding is missing")?;
         let binding = GatewayBindingCompiled::try_from(binding_proto)?;
@@ -872,6 +1104,12 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CompiledHttpRoute> for
     }
 }
 
+// Unknown methods are rejected with the path of the route, to make it easy to find
+fn parse_method(method: i32, path: &str) -> Result<MethodPattern, String> {
+    MethodPattern::try_from(method)
+        .map_err(|_| format!("Unknown HTTP method {method} for route {path}"))
+}
+
 impl From<MethodPattern> for grpc_apidefinition::HttpMethod {
     fn from(value: MethodPattern) -> Self {
         match value {
@@ -894,7 +1132,7 @@ impl TryFrom<grpc_apidefinition::HttpRoute> for crate::gateway_api_definition::h
     fn try_from(value: grpc_apidefinition::HttpRoute) -> Result<Self, Self::Error> {
         let path = AllPathPatterns::parse(value.path.as_str()).map_err(|e| e.to_string())?;
         let binding = value.binding.ok_or("binding is missing")?;
-        let method: MethodPattern = value.method.try_into()?;
+        let method = parse_method(value.method, &value.path)?;
 
         let gateway_binding = GatewayBinding::try_from(binding)?;
         let security = value.middleware.clone().and_then(|x| x.http_authentication);
@@ -921,12 +1159,298 @@ impl TryFrom<grpc_apidefinition::HttpRoute> for crate::gateway_api_definition::h
     }
 }
 
+// A sample request, which is routed to the routes of an API definition as if it was deployed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct RouteTestRequest {
+    pub method: MethodPattern,
+    // The path of the request, including its query
+    pub path: String,
+    pub headers: Option<HashMap<String, String>>,
+    pub body: Option<serde_json::Value>,
+}
+
+impl RouteTestRequest {
+    pub fn to_http_request(&self) -> Result<poem::Request, String> {
+        let uri: poem::http::Uri = self
+            .path
+            .parse()
+            .map_err(|err| format!("Invalid path {}: {err}", self.path))?;
+
+        let headers = self.headers.clone().unwrap_or_default();
+        let mut request = poem::Request::builder()
+            .method(self.method.clone().into())
+            .uri(uri);
+
+        // The gateway requires a host, even though it is not used for selecting the definition
+        if !headers
+            .keys()
+            .any(|name| name.eq_ignore_ascii_case(poem::http::header::HOST.as_str()))
+        {
+            request = request.header(poem::http::header::HOST, "localhost");
+        }
+        for (name, value) in &headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        let request = match &self.body {
+            Some(body) => request
+                .content_type("application/json")
+                .body(body.to_string()),
+            None => request.finish(),
+        };
+
+        Ok(request)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct RouteTestResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl RouteTestResponse {
+    pub async fn from_http_response(response: poem::Response) -> Result<Self, String> {
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).to_string(),
+                )
+            })
+            .collect();
+        let body = response
+            .into_body()
+            .into_vec()
+            .await
+            .map_err(|err| format!("Failed to read the response body: {err}"))?;
+
+        Ok(Self {
+            status,
+            headers,
+            body: String::from_utf8_lossy(&body).to_string(),
+        })
+    }
+}
+
+// Location of an OpenAPI document (JSON or YAML) to import an API definition from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct OpenApiUrlImportRequest {
+    pub url: String,
+}
+
+// A version of an API definition reserved until `expires_at`, which can only be created by
+// committing the reservation with `token`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ApiDefinitionReservationResponse {
+    pub id: ApiDefinitionId,
+    pub version: ApiVersion,
+    pub token: Uuid,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ApiDefinitionReservation> for ApiDefinitionReservationResponse {
+    fn from(reservation: ApiDefinitionReservation) -> Self {
+        Self {
+            id: reservation.id,
+            version: reservation.version,
+            token: reservation.token.0,
+            expires_at: reservation.expires_at,
+        }
+    }
+}
+
+// An event of watching the API definitions of a namespace. The first event is a snapshot of
+// the existing definitions, followed by an event for every change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Union)]
+#[oai(discriminator_name = "type", one_of = true)]
+#[serde(tag = "type")]
+pub enum ApiDefinitionWatchEvent {
+    Snapshot(ApiDefinitionSnapshot),
+    Change(ApiDefinitionChange),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ApiDefinitionSnapshot {
+    pub definitions: Vec<ApiDefinitionInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ApiDefinitionChange {
+    pub id: ApiDefinitionId,
+    pub version: ApiVersion,
+    pub action: ApiDefinitionChangeAction,
+}
+
+impl From<AuditEvent> for ApiDefinitionChange {
+    fn from(event: AuditEvent) -> Self {
+        Self {
+            id: event.id,
+            version: event.version,
+            action: event.action.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ApiDefinitionChangeAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl From<AuditAction> for ApiDefinitionChangeAction {
+    fn from(action: AuditAction) -> Self {
+        match action {
+            AuditAction::Create => ApiDefinitionChangeAction::Create,
+            AuditAction::Update => ApiDefinitionChangeAction::Update,
+            AuditAction::Delete => ApiDefinitionChangeAction::Delete,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::api::{GatewayBindingData, HttpApiDefinitionRequest};
     use crate::gateway_api_definition::http::MethodPattern;
+    use crate::gateway_binding::{GatewayBinding, ParamMapping};
     use golem_api_grpc::proto::golem::apidefinition as grpc_apidefinition;
+    use golem_common::model::GatewayBindingType;
+    use serde_json::json;
+    use std::collections::BTreeMap;
     use test_r::test;
 
+    fn definition_with_routes(paths: &[&str]) -> HttpApiDefinitionRequest {
+        let routes: Vec<_> = paths
+            .iter()
+            .map(|path| {
+                json!({
+                    "method": "Options",
+                    "path": path,
+                    "binding": { "bindingType": "cors-preflight" }
+                })
+            })
+            .collect();
+
+        serde_json::from_value(json!({
+            "id": "test",
+            "version": "1.0.0",
+            "routes": routes
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn duplicate_routes_are_rejected() {
+        let definition = definition_with_routes(&["/a", "/b", "/a"]);
+
+        let result: Result<crate::gateway_api_definition::http::HttpApiDefinitionRequest, _> =
+            definition.try_into();
+
+        assert_eq!(
+            result.map(|_| ()),
+            Err("Duplicate route: routes[0] and routes[2] are both Options /a".to_string())
+        );
+    }
+
+    #[test]
+    fn duplicate_routes_can_be_allowed() {
+        let definition = definition_with_routes(&["/a", "/b", "/a"]);
+
+        let result = definition.into_core_request(true).unwrap();
+
+        let paths: Vec<_> = result
+            .routes
+            .iter()
+            .map(|route| route.path.to_string())
+            .collect();
+        assert_eq!(paths, vec!["/a", "/b"]);
+    }
+
+    fn worker_binding_data(timeout_ms: Option<u64>) -> GatewayBindingData {
+        serde_json::from_value(json!({
+            "componentId": {
+                "componentId": "2696abdc-df3a-4771-8215-d6af7aa4c408",
+                "version": 0
+            },
+            "response": "${1}",
+            "timeoutMs": timeout_ms
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn worker_binding_timeouts_are_validated() {
+        let binding = GatewayBinding::try_from(worker_binding_data(Some(5000))).unwrap();
+        let GatewayBinding::Default(worker_binding) = binding else {
+            panic!("Expected a worker binding");
+        };
+        assert_eq!(worker_binding.timeout_ms, Some(5000));
+
+        assert!(GatewayBinding::try_from(worker_binding_data(None)).is_ok());
+        assert!(GatewayBinding::try_from(worker_binding_data(Some(0))).is_err());
+        assert!(GatewayBinding::try_from(worker_binding_data(Some(24 * 60 * 60 * 1000))).is_err());
+    }
+
+    #[test]
+    fn worker_binding_param_mappings_are_parsed() {
+        let mut data = worker_binding_data(None);
+        data.param_mappings = Some(BTreeMap::from([
+            ("id".into(), "path:id".into()),
+            ("user".into(), "header:x-user".into()),
+        ]));
+
+        let binding = GatewayBinding::try_from(data.clone()).unwrap();
+        let GatewayBinding::Default(worker_binding) = binding else {
+            panic!("Expected a worker binding");
+        };
+        assert_eq!(
+            worker_binding.param_mappings.get("id"),
+            Some(&ParamMapping::PathParam("id".to_string()))
+        );
+
+        let roundtrip =
+            GatewayBindingData::from_worker_binding(worker_binding, GatewayBindingType::Default);
+        assert_eq!(roundtrip.unwrap().param_mappings, data.param_mappings);
+
+        data.param_mappings = Some(BTreeMap::from([("id".into(), "cookie:id".into())]));
+        assert!(GatewayBinding::try_from(data).is_err());
+    }
+
+    #[test]
+    fn unknown_route_methods_are_rejected() {
+        let route = grpc_apidefinition::HttpRoute {
+            method: 42,
+            path: "/a".to_string(),
+            binding: Some(grpc_apidefinition::GatewayBinding::default()),
+            middleware: None,
+        };
+
+        let result: Result<crate::gateway_api_definition::http::RouteRequest, _> =
+            route.try_into();
+
+        assert_eq!(
+            result.map(|_| ()),
+            Err("Unknown HTTP method 42 for route /a".to_string())
+        );
+    }
+
     #[test]
     fn test_method_pattern() {
         for method in 0..8 {
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::fmt::Debug;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use golem_service_base::config::BlobStorageConfig;
@@ -40,11 +41,73 @@ pub struct WorkerServiceBaseConfig {
     pub port: u16,
     pub custom_request_port: u16,
     pub worker_grpc_port: u16,
+    #[serde(default)]
+    pub worker_grpc_tls: Option<GrpcTlsConfig>,
+    #[serde(default)]
+    pub api_cors: Option<ApiCorsConfig>,
+    #[serde(default)]
+    pub registration_rate_limit: Option<RateLimitConfig>,
+    #[serde(default)]
+    pub registration_retries: Option<RetryConfig>,
+    #[serde(default)]
+    pub max_api_definitions: Option<u64>,
+    // Larger API definition uploads are rejected with 413 Payload Too Large
+    #[serde(default = "default_max_api_definition_body_size")]
+    pub max_api_definition_body_size: u64,
+    // How long a reserved API definition version can be committed before the reservation expires
+    #[serde(with = "humantime_serde", default = "default_api_definition_reservation_ttl")]
+    pub api_definition_reservation_ttl: Duration,
+    // Allows importing OpenAPI documents from plain HTTP URLs, otherwise only HTTPS is accepted
+    #[serde(default)]
+    pub allow_insecure_open_api_urls: bool,
     pub routing_table: RoutingTableConfig,
     pub worker_executor_retries: RetryConfig,
     pub blob_storage: BlobStorageConfig,
 }
 
+// PEM encoded certificate chain and private key used to serve the gRPC API over TLS
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GrpcTlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+// CORS settings of the management HTTP API, for example to use it from a browser based admin UI.
+// An empty list allows any origin, method or header respectively.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ApiCorsConfig {
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    #[serde(default)]
+    pub allowed_methods: Vec<String>,
+    #[serde(default)]
+    pub allowed_headers: Vec<String>,
+}
+
+// Token bucket parameters: `burst` requests can be made at once, and the bucket refills
+// with `rate_per_second` tokens per second
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    #[serde(deserialize_with = "deserialize_rate_per_second")]
+    pub rate_per_second: f64,
+    pub burst: u32,
+}
+
+// A zero rate never refills the bucket, but a negative, infinite or NaN one can't be turned into
+// a delay to retry after
+fn deserialize_rate_per_second<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<f64, D::Error> {
+    let rate_per_second = f64::deserialize(deserializer)?;
+    if rate_per_second.is_finite() && rate_per_second >= 0.0 {
+        Ok(rate_per_second)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "rate_per_second must be a finite, non-negative number, got {rate_per_second}"
+        )))
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", content = "config")]
 pub enum GatewaySessionStorageConfig {
@@ -64,6 +127,14 @@ impl GatewaySessionStorageConfig {
     }
 }
 
+fn default_max_api_definition_body_size() -> u64 {
+    16 * 1024 * 1024
+}
+
+fn default_api_definition_reservation_ttl() -> Duration {
+    Duration::from_secs(600)
+}
+
 impl WorkerServiceBaseConfig {
     pub fn is_local_env(&self) -> bool {
         self.environment.to_lowercase() == "local"
@@ -84,6 +155,14 @@ impl Default for WorkerServiceBaseConfig {
             port: 9005,
             custom_request_port: 9006,
             worker_grpc_port: 9007,
+            worker_grpc_tls: None,
+            api_cors: None,
+            registration_rate_limit: None,
+            registration_retries: None,
+            max_api_definitions: None,
+            max_api_definition_body_size: default_max_api_definition_body_size(),
+            api_definition_reservation_ttl: default_api_definition_reservation_ttl(),
+            allow_insecure_open_api_urls: false,
             routing_table: RoutingTableConfig::default(),
             worker_executor_retries: RetryConfig {
                 max_attempts: 5,
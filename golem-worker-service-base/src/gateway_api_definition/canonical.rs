@@ -0,0 +1,137 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Canonical JSON form of API definitions.
+// The same logical definition always serializes to the same bytes: object keys are sorted,
+// and absent optional fields are not distinguished from explicit nulls (both are dropped).
+// Array order is preserved, as it is meaningful (e.g. the order of routes).
+// This is the form to use whenever a definition is hashed, compared or exported.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+pub fn to_canonical_value<T: Serialize>(value: &T) -> Result<Value, serde_json::Error> {
+    Ok(normalize(serde_json::to_value(value)?))
+}
+
+pub fn to_canonical_json<T: Serialize>(value: &T) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec(&to_canonical_value(value)?)
+}
+
+// Hex encoded SHA-256 of the canonical JSON
+pub fn canonical_hash<T: Serialize>(value: &T) -> Result<String, serde_json::Error> {
+    let bytes = to_canonical_json(value)?;
+    Ok(hex::encode(Sha256::digest(bytes)))
+}
+
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => {
+            let mut sorted: Vec<(String, Value)> = fields
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, normalize(value)))
+                .collect();
+            sorted.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            // Map keeps insertion order when serde_json's `preserve_order` is enabled anywhere
+            // in the dependency graph, and is sorted otherwise - inserting in sorted order is
+            // correct in both cases.
+            let mut result = Map::new();
+            for (key, value) in sorted {
+                result.insert(key, value);
+            }
+            Value::Object(result)
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(normalize).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use crate::api::HttpApiDefinitionRequest;
+    use crate::gateway_api_definition::{ApiDefinitionId, ApiVersion};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn equal_definitions_have_identical_canonical_bytes() {
+        let from_json: HttpApiDefinitionRequest = serde_json::from_str(
+            r#"{
+                "version": "0.0.1",
+                "routes": [],
+                "security": null,
+                "id": "shopping-cart",
+                "draft": true
+            }"#,
+        )
+        .unwrap();
+
+        let constructed = HttpApiDefinitionRequest {
+            id: ApiDefinitionId("shopping-cart".to_string()),
+            version: ApiVersion("0.0.1".to_string()),
+            security: None,
+            routes: vec![],
+            draft: true,
+            labels: BTreeMap::new(),
+        };
+
+        assert_eq!(
+            to_canonical_json(&from_json).unwrap(),
+            to_canonical_json(&constructed).unwrap()
+        );
+        assert_eq!(
+            canonical_hash(&from_json).unwrap(),
+            canonical_hash(&constructed).unwrap()
+        );
+    }
+
+    #[test]
+    fn canonical_json_sorts_keys_and_drops_nulls() {
+        let value = serde_json::json!({
+            "b": { "z": 1, "y": null, "x": [ { "d": 2, "c": null } ] },
+            "a": "value"
+        });
+
+        let canonical = String::from_utf8(to_canonical_json(&value).unwrap()).unwrap();
+
+        assert_eq!(canonical, r#"{"a":"value","b":{"x":[{"d":2}],"z":1}}"#);
+    }
+
+    #[test]
+    fn different_definitions_have_different_hashes() {
+        let first = HttpApiDefinitionRequest {
+            id: ApiDefinitionId("shopping-cart".to_string()),
+            version: ApiVersion("0.0.1".to_string()),
+            security: None,
+            routes: vec![],
+            draft: true,
+            labels: BTreeMap::new(),
+        };
+
+        let second = HttpApiDefinitionRequest {
+            version: ApiVersion("0.0.2".to_string()),
+            ..first.clone()
+        };
+
+        assert_ne!(
+            canonical_hash(&first).unwrap(),
+            canonical_hash(&second).unwrap()
+        );
+    }
+}
@@ -33,7 +33,7 @@ use golem_wasm_ast::analysis::AnalysedExport;
 use poem_openapi::Enum;
 use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Display};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -46,6 +46,7 @@ pub struct HttpApiDefinition {
     pub version: ApiVersion,
     pub routes: Vec<Route>,
     pub draft: bool,
+    pub labels: BTreeMap<String, String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -131,6 +132,7 @@ impl HttpApiDefinition {
             version: request.version,
             routes,
             draft: request.draft,
+            labels: request.labels,
             created_at,
         };
 
@@ -159,6 +161,7 @@ impl From<HttpApiDefinition> for HttpApiDefinitionRequest {
             security,
             routes: value.routes.into_iter().map(RouteRequest::from).collect(),
             draft: value.draft,
+            labels: value.labels,
         }
     }
 }
@@ -183,6 +186,7 @@ impl<Namespace> From<CompiledHttpApiDefinition<Namespace>> for HttpApiDefinition
                 .map(Route::from)
                 .collect(),
             draft: compiled_http_api_definition.draft,
+            labels: compiled_http_api_definition.labels,
             created_at: compiled_http_api_definition.created_at,
         }
     }
@@ -210,6 +214,8 @@ impl TryFrom<grpc_apidefinition::ApiDefinition>
             version: ApiVersion(value.version),
             routes,
             draft: value.draft,
+            // Labels are not part of the gRPC API
+            labels: BTreeMap::new(),
             created_at: created_at.into(),
         };
         Ok(result)
@@ -227,6 +233,7 @@ pub struct CompiledHttpApiDefinition<Namespace> {
     pub version: ApiVersion,
     pub routes: Vec<CompiledRoute>,
     pub draft: bool,
+    pub labels: BTreeMap<String, String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub namespace: Namespace,
 }
@@ -249,12 +256,22 @@ impl<Namespace: Clone> CompiledHttpApiDefinition<Namespace> {
             version: http_api_definition.version.clone(),
             routes: compiled_routes,
             draft: http_api_definition.draft,
+            labels: http_api_definition.labels.clone(),
             created_at: http_api_definition.created_at,
             namespace: namespace.clone(),
         })
     }
 }
 
+impl<Namespace> CompiledHttpApiDefinition<Namespace> {
+    // True if the definition has all the given labels, with the same values
+    pub fn has_labels(&self, labels: &BTreeMap<String, String>) -> bool {
+        labels
+            .iter()
+            .all(|(key, value)| self.labels.get(key) == Some(value))
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -12,14 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::gateway_api_definition::http::swagger_upgrade::{is_swagger_2, upgrade_swagger_2};
 use crate::gateway_api_definition::http::HttpApiDefinitionRequest;
 use crate::gateway_api_definition::{ApiDefinitionId, ApiVersion};
 use internal::*;
 use openapiv3::OpenAPI;
 use poem_openapi::registry::{MetaSchema, MetaSchemaRef};
 use poem_openapi::types::{ParseError, ParseFromJSON, ParseFromYAML, ParseResult};
+use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 pub struct OpenApiHttpApiDefinitionRequest(pub OpenAPI);
 
@@ -45,21 +48,38 @@ impl OpenApiHttpApiDefinitionRequest {
             version: api_definition_version,
             routes,
             draft: true,
+            labels: BTreeMap::new(),
             security,
         })
     }
+
+    // Parses an OpenAPI 3.0 or 3.1, or a Swagger 2.0 document. Documents other than
+    // OpenAPI 3.0 are first brought to the 3.0 shape
+    pub fn from_json_value(value: Value) -> Result<Self, String> {
+        let value = if is_swagger_2(&value) {
+            upgrade_swagger_2(value)
+                .map_err(|e| format!("Failed to upgrade Swagger 2.0 document: {}", e))?
+        } else {
+            normalize_open_api_3_1(value)
+        };
+
+        serde_json::from_value::<OpenAPI>(value)
+            .map(OpenApiHttpApiDefinitionRequest)
+            .map_err(|e| format!("Failed to parse OpenAPI: {}", e))
+    }
+}
+
+impl<'de> Deserialize<'de> for OpenApiHttpApiDefinitionRequest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Self::from_json_value(value).map_err(serde::de::Error::custom)
+    }
 }
 
 impl ParseFromJSON for OpenApiHttpApiDefinitionRequest {
     fn parse_from_json(value: Option<serde_json::Value>) -> ParseResult<Self> {
         match value {
-            Some(value) => match serde_json::from_value::<openapiv3::OpenAPI>(value) {
-                Ok(openapi) => Ok(OpenApiHttpApiDefinitionRequest(openapi)),
-                Err(e) => Err(ParseError::<Self>::custom(format!(
-                    "Failed to parse OpenAPI: {}",
-                    e
-                ))),
-            },
+            Some(value) => Self::from_json_value(value).map_err(ParseError::<Self>::custom),
 
             _ => Err(ParseError::<Self>::custom(
                 "OpenAPI spec missing".to_string(),
@@ -71,13 +91,7 @@ impl ParseFromJSON for OpenApiHttpApiDefinitionRequest {
 impl ParseFromYAML for OpenApiHttpApiDefinitionRequest {
     fn parse_from_yaml(value: Option<Value>) -> ParseResult<Self> {
         match value {
-            Some(value) => match serde_json::from_value::<openapiv3::OpenAPI>(value) {
-                Ok(openapi) => Ok(OpenApiHttpApiDefinitionRequest(openapi)),
-                Err(e) => Err(ParseError::<Self>::custom(format!(
-                    "Failed to parse OpenAPI: {}",
-                    e
-                ))),
-            },
+            Some(value) => Self::from_json_value(value).map_err(ParseError::<Self>::custom),
 
             _ => Err(ParseError::<Self>::custom(
                 "OpenAPI spec missing".to_string(),
@@ -121,9 +135,14 @@ mod internal {
     use golem_common::model::{ComponentId, GatewayBindingType};
     use openapiv3::{OpenAPI, Operation, Paths, ReferenceOr};
     use rib::Expr;
+    use serde::Deserialize;
     use serde_json::Value;
+    use std::collections::BTreeMap;
+    use tracing::warn;
 
-    use crate::gateway_binding::{GatewayBinding, ResponseMapping, StaticBinding, WorkerBinding};
+    use crate::gateway_binding::{
+        GatewayBinding, ParamMapping, ResponseMapping, StaticBinding, WorkerBinding,
+    };
     use crate::gateway_middleware::{CorsPreflightExpr, HttpCors};
     use crate::gateway_security::{SecuritySchemeIdentifier, SecuritySchemeReference};
     use golem_service_base::model::VersionedComponentId;
@@ -137,6 +156,68 @@ mod internal {
 
     pub(crate) const GOLEM_API_GATEWAY_BINDING: &str = "x-golem-api-gateway-binding";
 
+    // Rewrites the OpenAPI 3.1 constructs which have a 3.0 equivalent, so the document can be
+    // parsed as 3.0. Webhooks have no equivalent, and are dropped.
+    pub(crate) fn normalize_open_api_3_1(mut open_api: Value) -> Value {
+        let is_3_1 = open_api
+            .get("openapi")
+            .and_then(Value::as_str)
+            .is_some_and(|version| version.starts_with("3.1"));
+
+        if is_3_1 {
+            if let Some(root) = open_api.as_object_mut() {
+                if root.remove("webhooks").is_some() {
+                    warn!("Webhooks are not supported in API definitions, ignoring them");
+                }
+            }
+            normalize_type_arrays(&mut open_api);
+        }
+
+        open_api
+    }
+
+    // 3.1 schemas express nullability as a `null` member of a `type` array, while 3.0 uses
+    // a single `type` and `nullable`. Multiple non-null types become an `anyOf`.
+    fn normalize_type_arrays(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::Array(types)) = map.get("type") {
+                    let non_null_types: Vec<Value> = types
+                        .iter()
+                        .filter(|typ| typ.as_str() != Some("null"))
+                        .cloned()
+                        .collect();
+                    let nullable = non_null_types.len() < types.len();
+
+                    match non_null_types.as_slice() {
+                        [] => {
+                            map.remove("type");
+                        }
+                        [typ] => {
+                            map.insert("type".to_string(), typ.clone());
+                        }
+                        _ => {
+                            map.remove("type");
+                            let any_of = non_null_types
+                                .into_iter()
+                                .map(|typ| serde_json::json!({ "type": typ }))
+                                .collect();
+                            map.insert("anyOf".to_string(), Value::Array(any_of));
+                        }
+                    }
+
+                    if nullable {
+                        map.insert("nullable".to_string(), Value::Bool(true));
+                    }
+                }
+
+                map.values_mut().for_each(normalize_type_arrays);
+            }
+            Value::Array(items) => items.iter_mut().for_each(normalize_type_arrays),
+            _ => {}
+        }
+    }
+
     pub(crate) fn get_global_security(open_api: &OpenAPI) -> Option<Vec<SecuritySchemeReference>> {
         open_api.security.as_ref().and_then(|requirements| {
             let global_security: Vec<_> = requirements
@@ -183,8 +264,12 @@ mod internal {
                     let path_pattern = get_path_pattern(path)?;
 
                     for (method, method_operation) in item.iter() {
-                        let route =
-                            get_route_from_path_item(method, method_operation, &path_pattern)?;
+                        let route = get_route_from_path_item(
+                            path,
+                            method,
+                            method_operation,
+                            &path_pattern,
+                        )?;
                         routes.push(route);
                     }
                 }
@@ -201,10 +286,13 @@ mod internal {
     }
 
     pub(crate) fn get_route_from_path_item(
+        path: &str,
         method: &str,
         method_operation: &Operation,
         path_pattern: &AllPathPatterns,
     ) -> Result<RouteRequest, String> {
+        let operation_pointer = json_pointer(&["paths", path, method]);
+
         let method_res = match method {
             "get" => Ok(MethodPattern::Get),
             "post" => Ok(MethodPattern::Post),
@@ -214,7 +302,7 @@ mod internal {
             "head" => Ok(MethodPattern::Head),
             "patch" => Ok(MethodPattern::Patch),
             "trace" => Ok(MethodPattern::Trace),
-            _ => Err("Other methods not supported".to_string()),
+            _ => Err(format!("Unsupported HTTP method {method} for route {path}")),
         };
 
         let method = method_res?;
@@ -235,16 +323,26 @@ mod internal {
         let worker_gateway_info_optional = method_operation
             .extensions
             // TO keep backward compatibility with the old extension
-            .get(GOLEM_WORKER_GATEWAY_EXTENSION_LEGACY)
-            .or(method_operation.extensions.get(GOLEM_API_GATEWAY_BINDING));
+            .get_key_value(GOLEM_WORKER_GATEWAY_EXTENSION_LEGACY)
+            .or(method_operation
+                .extensions
+                .get_key_value(GOLEM_API_GATEWAY_BINDING));
 
         match worker_gateway_info_optional {
-            Some(worker_gateway_info) => {
-                let binding_type = get_binding_type(worker_gateway_info)?;
+            Some((extension_name, worker_gateway_info)) => {
+                let extension_pointer =
+                    format!("{}{}", operation_pointer, json_pointer(&[extension_name]));
+                let invalid_extension = |err: String| {
+                    format!("Invalid {extension_name} extension at {extension_pointer}: {err}")
+                };
+
+                let binding_type = get_binding_type(worker_gateway_info)
+                    .map_err(&invalid_extension)?;
 
                 match (&binding_type, &method) {
                     (GatewayBindingType::CorsPreflight, MethodPattern::Options) => {
-                        let binding = get_cors_static_binding(worker_gateway_info)?;
+                        let binding = get_cors_static_binding(worker_gateway_info)
+                            .map_err(&invalid_extension)?;
 
                         Ok(RouteRequest {
                             method,
@@ -256,7 +354,8 @@ mod internal {
                     }
 
                     (GatewayBindingType::Default, _) => {
-                        let binding = get_gateway_binding(worker_gateway_info)?;
+                        let binding = get_gateway_binding(worker_gateway_info)
+                            .map_err(&invalid_extension)?;
 
                         Ok(RouteRequest {
                             path: path_pattern.clone(),
@@ -267,7 +366,8 @@ mod internal {
                         })
                     }
                     (GatewayBindingType::FileServer, _) => {
-                        let binding = get_gateway_binding(worker_gateway_info)?;
+                        let binding = get_gateway_binding(worker_gateway_info)
+                            .map_err(&invalid_extension)?;
 
                         Ok(RouteRequest {
                             path: path_pattern.clone(),
@@ -304,19 +404,69 @@ mod internal {
         }
     }
 
+    // Typed form of the worker binding extension. Unknown fields (such as binding-type) are ignored
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub(crate) struct WorkerBindingExtension {
+        pub component_id: Uuid,
+        pub component_version: u64,
+        pub worker_name: Option<String>,
+        pub idempotency_key: Option<String>,
+        pub response: String,
+        pub timeout_ms: Option<u64>,
+        // component-version is the one the Rib expressions are compiled against
+        pub pinned_component_version: Option<u64>,
+        #[serde(default)]
+        pub param_mappings: BTreeMap<String, ParamMapping>,
+    }
+
     pub(crate) fn get_gateway_binding(
         gateway_binding_value: &Value,
     ) -> Result<WorkerBinding, String> {
+        let extension = WorkerBindingExtension::deserialize(gateway_binding_value)
+            .map_err(|err| err.to_string())?;
+
+        let worker_name = extension
+            .worker_name
+            .map(|worker_name| rib_expr("worker-name", &worker_name))
+            .transpose()?;
+        let component_version = WorkerBinding::validate_component_version(
+            extension.pinned_component_version,
+            &worker_name,
+        )?;
+
         let binding = WorkerBinding {
-            worker_name: get_worker_id_expr(gateway_binding_value)?,
-            component_id: get_component_id(gateway_binding_value)?,
-            idempotency_key: get_idempotency_key(gateway_binding_value)?,
-            response_mapping: get_response_mapping(gateway_binding_value)?,
+            worker_name,
+            component_id: VersionedComponentId {
+                component_id: ComponentId(extension.component_id),
+                version: extension.component_version,
+            },
+            idempotency_key: extension
+                .idempotency_key
+                .map(|key| rib_expr("idempotency-key", &key))
+                .transpose()?,
+            response_mapping: ResponseMapping(rib_expr("response", &extension.response)?),
+            timeout_ms: WorkerBinding::validate_timeout_ms(extension.timeout_ms)?,
+            component_version,
+            param_mappings: extension.param_mappings,
         };
 
         Ok(binding)
     }
 
+    fn rib_expr(field: &str, expr: &str) -> Result<Expr, String> {
+        rib::from_string(expr)
+            .map_err(|err| format!("{} is not a valid Rib expression: {}", field, err))
+    }
+
+    // JSON pointer (RFC 6901) built from unescaped reference tokens
+    pub(crate) fn json_pointer(tokens: &[impl AsRef<str>]) -> String {
+        tokens
+            .iter()
+            .map(|token| format!("/{}", token.as_ref().replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+
     pub(crate) fn get_cors_static_binding(
         worker_gateway_info: &Value,
     ) -> Result<StaticBinding, String> {
@@ -341,30 +491,6 @@ mod internal {
         }
     }
 
-    pub(crate) fn get_component_id(
-        gateway_binding_value: &Value,
-    ) -> Result<VersionedComponentId, String> {
-        let component_id_str = gateway_binding_value
-            .get("component-id")
-            .ok_or("No component-id found")?
-            .as_str()
-            .ok_or("component-id is not a string")?;
-
-        let version = gateway_binding_value
-            .get("component-version")
-            .ok_or("No component-version found")?
-            .as_u64()
-            .ok_or("component-version is not a u64")?;
-
-        let component_id =
-            ComponentId(Uuid::parse_str(component_id_str).map_err(|err| err.to_string())?);
-
-        Ok(VersionedComponentId {
-            component_id,
-            version,
-        })
-    }
-
     pub(crate) fn get_binding_type(
         worker_gateway_info: &Value,
     ) -> Result<GatewayBindingType, String> {
@@ -377,55 +503,6 @@ mod internal {
         Ok(binding_type_optional.unwrap_or(GatewayBindingType::Default))
     }
 
-    pub(crate) fn get_response_mapping(
-        gateway_binding_value: &Value,
-    ) -> Result<ResponseMapping, String> {
-        let response = {
-            let response_mapping_optional = gateway_binding_value.get("response").ok_or(
-                "No response mapping found. It should be a string representing expression"
-                    .to_string(),
-            )?;
-
-            match response_mapping_optional {
-                Value::String(expr) => rib::from_string(expr).map_err(|err| err.to_string()),
-                _ => Err(
-                    "Invalid response mapping type. It should be a string representing expression"
-                        .to_string(),
-                ),
-            }
-        }?;
-
-        Ok(ResponseMapping(response.clone()))
-    }
-
-    pub(crate) fn get_worker_id_expr(
-        gateway_binding_value: &Value,
-    ) -> Result<Option<Expr>, String> {
-        let worker_id_str_opt = gateway_binding_value
-            .get("worker-name")
-            .map(|json_value| json_value.as_str().ok_or("worker-name is not a string"))
-            .transpose()?;
-
-        let worker_id_expr_opt = worker_id_str_opt
-            .map(|worker_id| rib::from_string(worker_id).map_err(|err| err.to_string()))
-            .transpose()?;
-
-        Ok(worker_id_expr_opt)
-    }
-
-    pub(crate) fn get_idempotency_key(
-        gateway_binding_value: &Value,
-    ) -> Result<Option<Expr>, String> {
-        if let Some(key) = gateway_binding_value.get("idempotency-key") {
-            let key_expr = key.as_str().ok_or("idempotency-key is not a string")?;
-            Ok(Some(
-                rib::from_string(key_expr).map_err(|err| err.to_string())?,
-            ))
-        } else {
-            Ok(None)
-        }
-    }
-
     pub(crate) fn get_path_pattern(path: &str) -> Result<AllPathPatterns, String> {
         AllPathPatterns::parse(path)
     }
@@ -461,7 +538,7 @@ mod tests {
 
         let path_pattern = AllPathPatterns::parse("/test").unwrap();
 
-        let result = get_route_from_path_item("options", &path_item, &path_pattern);
+        let result = get_route_from_path_item("/test", "options", &path_item, &path_pattern);
 
         let expected = expected_route_with_cors_preflight_binding(&path_pattern);
         assert_eq!(result, Ok(expected));
@@ -483,7 +560,7 @@ mod tests {
 
         let path_pattern = AllPathPatterns::parse("/test").unwrap();
 
-        let result = get_route_from_path_item("options", &path_item, &path_pattern);
+        let result = get_route_from_path_item("/test", "options", &path_item, &path_pattern);
 
         let expected = expected_route_with_cors_preflight_binding_default(&path_pattern);
         assert_eq!(result, Ok(expected));
@@ -495,12 +572,63 @@ mod tests {
 
         let path_pattern = AllPathPatterns::parse("/test").unwrap();
 
-        let result = get_route_from_path_item("options", &path_item, &path_pattern);
+        let result = get_route_from_path_item("/test", "options", &path_item, &path_pattern);
 
         let expected = expected_route_with_cors_preflight_binding_default(&path_pattern);
         assert_eq!(result, Ok(expected));
     }
 
+    #[test]
+    fn test_get_route_with_missing_worker_binding_field() {
+        let path_item = Operation {
+            extensions: vec![(
+                "x-golem-api-gateway-binding".to_string(),
+                json!({
+                    "component-version": 0,
+                    "response": "${1}"
+                }),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        let path_pattern = AllPathPatterns::parse("/users/{user-id}").unwrap();
+
+        let result = get_route_from_path_item("/users/{user-id}", "get", &path_item, &path_pattern);
+
+        let expected = "Invalid x-golem-api-gateway-binding extension at \
+                        /paths/~1users~1{user-id}/get/x-golem-api-gateway-binding: \
+                        missing field `component-id`";
+        assert_eq!(result, Err(expected.to_string()));
+    }
+
+    #[test]
+    fn test_get_route_with_mistyped_worker_binding_field() {
+        let path_item = Operation {
+            extensions: vec![(
+                "x-golem-worker-bridge".to_string(),
+                json!({
+                    "component-id": "2696abdc-df3a-4771-8215-d6af7aa4c408",
+                    "component-version": "0",
+                    "response": "${1}"
+                }),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        let path_pattern = AllPathPatterns::parse("/test").unwrap();
+
+        let result = get_route_from_path_item("/test", "post", &path_item, &path_pattern);
+
+        let expected = "Invalid x-golem-worker-bridge extension at \
+                        /paths/~1test/post/x-golem-worker-bridge: \
+                        invalid type: string \"0\", expected u64";
+        assert_eq!(result, Err(expected.to_string()));
+    }
+
     fn expected_route_with_cors_preflight_binding_default(
         path_pattern: &AllPathPatterns,
     ) -> RouteRequest {
@@ -526,4 +654,123 @@ mod tests {
             cors: None,
         }
     }
+
+    // The same API, described with OpenAPI 3.0 and 3.1 constructs
+    fn shopping_cart_open_api_3_0() -> Value {
+        shopping_cart_open_api(
+            "3.0.3",
+            json!({ "type": "string", "nullable": true }),
+            json!({ "type": "integer", "format": "int64" }),
+        )
+    }
+
+    fn shopping_cart_open_api_3_1() -> Value {
+        let mut open_api = shopping_cart_open_api(
+            "3.1.0",
+            json!({ "type": ["string", "null"] }),
+            json!({ "type": ["integer"], "format": "int64" }),
+        );
+        open_api["webhooks"] = json!({
+            "cartUpdated": {
+                "post": {
+                    "requestBody": {
+                        "content": {
+                            "application/json": { "schema": { "type": ["object", "null"] } }
+                        }
+                    },
+                    "responses": { "200": { "description": "OK" } }
+                }
+            }
+        });
+        open_api
+    }
+
+    fn shopping_cart_open_api(version: &str, name_schema: Value, count_schema: Value) -> Value {
+        json!({
+            "openapi": version,
+            "info": { "title": "Shopping cart", "version": "1.0.0" },
+            "x-golem-api-definition-id": "shopping-cart",
+            "x-golem-api-definition-version": "0.1.0",
+            "paths": {
+                "/{user-id}/get-cart-contents": {
+                    "get": {
+                        "x-golem-api-gateway-binding": {
+                            "worker-name": "worker-${request.path.user-id}",
+                            "component-id": "2696abdc-df3a-4771-8215-d6af7aa4c408",
+                            "component-version": 0,
+                            "response": "${1}"
+                        },
+                        "responses": {
+                            "200": {
+                                "description": "OK",
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": {
+                                                "name": name_schema,
+                                                "count": count_schema
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn parse(open_api: Value) -> OpenApiHttpApiDefinitionRequest {
+        OpenApiHttpApiDefinitionRequest::from_json_value(open_api)
+            .unwrap()
+    }
+
+    #[test]
+    fn open_api_3_0_and_3_1_produce_equal_definitions() {
+        let from_3_0 = parse(shopping_cart_open_api_3_0());
+        let from_3_1 = parse(shopping_cart_open_api_3_1());
+
+        assert_eq!(
+            serde_json::to_value(&from_3_0.0.paths).unwrap(),
+            serde_json::to_value(&from_3_1.0.paths).unwrap()
+        );
+        assert_eq!(
+            from_3_0.to_http_api_definition_request(),
+            from_3_1.to_http_api_definition_request()
+        );
+        assert!(from_3_1.to_http_api_definition_request().is_ok());
+    }
+
+    #[test]
+    fn open_api_3_1_multiple_types_become_any_of() {
+        let normalized = normalize_open_api_3_1(json!({
+            "openapi": "3.1.0",
+            "components": {
+                "schemas": {
+                    "Id": { "type": ["string", "integer", "null"] }
+                }
+            }
+        }));
+
+        assert_eq!(
+            normalized["components"]["schemas"]["Id"],
+            json!({
+                "anyOf": [{ "type": "string" }, { "type": "integer" }],
+                "nullable": true
+            })
+        );
+    }
+
+    #[test]
+    fn open_api_3_0_is_not_rewritten() {
+        let open_api = json!({
+            "openapi": "3.0.3",
+            "webhooks": {},
+            "components": { "schemas": { "Id": { "type": ["string", "null"] } } }
+        });
+
+        assert_eq!(normalize_open_api_3_1(open_api.clone()), open_api);
+    }
 }
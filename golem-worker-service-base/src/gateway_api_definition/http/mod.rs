@@ -21,3 +21,4 @@ mod http_api_definition_request;
 mod http_oas_api_definition;
 pub(crate) mod path_pattern_parser;
 pub(crate) mod place_holder_parser;
+pub(crate) mod swagger_upgrade;
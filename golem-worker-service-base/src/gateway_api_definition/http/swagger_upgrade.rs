@@ -0,0 +1,701 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Conversion of Swagger 2.0 documents to the OpenAPI 3.0 shape, so they can be imported
+// the same way as OpenAPI documents.
+// - `basePath` is prepended to every path, as API definition routes are absolute
+// - `host` and `schemes` become `servers`
+// - `definitions`, `parameters`, `responses` and `securityDefinitions` move to `components`
+// - body parameters become request bodies, and `consumes` / `produces` become content types
+// Constructs without an OpenAPI 3.0 equivalent in this conversion (form data parameters,
+// file types, operation level schemes) are rejected instead of being dropped.
+
+use serde_json::{json, Map, Value};
+
+const DEFAULT_MEDIA_TYPE: &str = "application/json";
+
+const HTTP_METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch"];
+
+// Fields which describe the value of a non-body parameter or a header, and are part of the
+// schema object in OpenAPI 3.0
+const SCHEMA_FIELDS: &[&str] = &[
+    "type",
+    "format",
+    "items",
+    "default",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "enum",
+    "multipleOf",
+];
+
+const REFERENCE_PREFIXES: &[(&str, &str)] = &[
+    ("#/definitions/", "#/components/schemas/"),
+    ("#/parameters/", "#/components/parameters/"),
+    ("#/responses/", "#/components/responses/"),
+];
+
+pub(crate) fn is_swagger_2(document: &Value) -> bool {
+    document.get("swagger").and_then(Value::as_str) == Some("2.0")
+}
+
+pub(crate) fn upgrade_swagger_2(document: Value) -> Result<Value, String> {
+    let Value::Object(mut swagger) = document else {
+        return Err("The Swagger document is not an object".to_string());
+    };
+
+    swagger.remove("swagger");
+    let host = swagger.remove("host");
+    let base_path = swagger.remove("basePath");
+    let schemes = swagger.remove("schemes");
+    let consumes = media_types(swagger.remove("consumes"), "consumes")?;
+    let produces = media_types(swagger.remove("produces"), "produces")?;
+
+    let mut open_api = Map::new();
+    open_api.insert("openapi".to_string(), json!("3.0.0"));
+
+    if let Some(servers) = servers(host, schemes)? {
+        open_api.insert("servers".to_string(), servers);
+    }
+
+    let mut components = Map::new();
+    if let Some(definitions) = swagger.remove("definitions") {
+        components.insert(
+            "schemas".to_string(),
+            map_values(definitions, "definitions", convert_schema)?,
+        );
+    }
+    if let Some(parameters) = swagger.remove("parameters") {
+        components.insert(
+            "parameters".to_string(),
+            map_values(parameters, "parameters", convert_parameter)?,
+        );
+    }
+    if let Some(responses) = swagger.remove("responses") {
+        components.insert(
+            "responses".to_string(),
+            map_values(responses, "responses", |response| {
+                convert_response(response, &produces)
+            })?,
+        );
+    }
+    if let Some(security_definitions) = swagger.remove("securityDefinitions") {
+        components.insert(
+            "securitySchemes".to_string(),
+            map_values(
+                security_definitions,
+                "securityDefinitions",
+                convert_security_scheme,
+            )?,
+        );
+    }
+
+    let base_path = base_path_prefix(base_path)?;
+    let paths = match swagger.remove("paths") {
+        Some(Value::Object(paths)) => paths
+            .into_iter()
+            .map(|(path, item)| {
+                let item = convert_path_item(item, &consumes, &produces)
+                    .map_err(|err| format!("{err} (in path {path})"))?;
+                Ok((format!("{base_path}{path}"), item))
+            })
+            .collect::<Result<Map<_, _>, String>>()?,
+        Some(_) => return Err("paths is not an object".to_string()),
+        None => Map::new(),
+    };
+    open_api.insert("paths".to_string(), Value::Object(paths));
+
+    if !components.is_empty() {
+        open_api.insert("components".to_string(), Value::Object(components));
+    }
+
+    // info, tags, security, externalDocs and extensions have the same shape in both versions
+    open_api.extend(swagger);
+
+    let mut open_api = Value::Object(open_api);
+    rewrite_references(&mut open_api);
+    Ok(open_api)
+}
+
+fn media_types(value: Option<Value>, field: &str) -> Result<Vec<String>, String> {
+    match value {
+        None => Ok(vec![DEFAULT_MEDIA_TYPE.to_string()]),
+        Some(Value::Array(items)) => {
+            let media_types = items
+                .into_iter()
+                .map(|item| match item {
+                    Value::String(media_type) => Ok(media_type),
+                    _ => Err(format!("{field} must only contain media type strings")),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if media_types.is_empty() {
+                Ok(vec![DEFAULT_MEDIA_TYPE.to_string()])
+            } else {
+                Ok(media_types)
+            }
+        }
+        Some(_) => Err(format!("{field} is not an array")),
+    }
+}
+
+fn servers(host: Option<Value>, schemes: Option<Value>) -> Result<Option<Value>, String> {
+    let host = match host {
+        Some(Value::String(host)) => host,
+        Some(_) => return Err("host is not a string".to_string()),
+        None => return Ok(None),
+    };
+
+    let schemes = match schemes {
+        Some(Value::Array(schemes)) => schemes
+            .into_iter()
+            .map(|scheme| match scheme {
+                Value::String(scheme) => Ok(scheme),
+                _ => Err("schemes must only contain strings".to_string()),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        Some(_) => return Err("schemes is not an array".to_string()),
+        None => vec!["https".to_string()],
+    };
+
+    let servers = schemes
+        .into_iter()
+        .map(|scheme| json!({ "url": format!("{scheme}://{host}") }))
+        .collect();
+
+    Ok(Some(Value::Array(servers)))
+}
+
+fn base_path_prefix(base_path: Option<Value>) -> Result<String, String> {
+    match base_path {
+        Some(Value::String(base_path)) => Ok(base_path.trim_end_matches('/').to_string()),
+        Some(_) => Err("basePath is not a string".to_string()),
+        None => Ok(String::new()),
+    }
+}
+
+fn map_values(
+    value: Value,
+    field: &str,
+    convert: impl Fn(Value) -> Result<Value, String>,
+) -> Result<Value, String> {
+    match value {
+        Value::Object(items) => items
+            .into_iter()
+            .map(|(name, item)| {
+                let item = convert(item).map_err(|err| format!("{err} (in {field}.{name})"))?;
+                Ok((name, item))
+            })
+            .collect::<Result<Map<_, _>, String>>()
+            .map(Value::Object),
+        _ => Err(format!("{field} is not an object")),
+    }
+}
+
+fn into_object(value: Value, description: &str) -> Result<Map<String, Value>, String> {
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(format!("{description} is not an object")),
+    }
+}
+
+fn convert_path_item(
+    item: Value,
+    consumes: &[String],
+    produces: &[String],
+) -> Result<Value, String> {
+    let mut item = into_object(item, "Path item")?;
+
+    if let Some(parameters) = item.remove("parameters") {
+        item.insert(
+            "parameters".to_string(),
+            convert_parameter_list(parameters, "Path level parameters")?,
+        );
+    }
+
+    for method in HTTP_METHODS {
+        if let Some(operation) = item.remove(*method) {
+            let operation = convert_operation(operation, consumes, produces)
+                .map_err(|err| format!("{err} (in {method} operation)"))?;
+            item.insert(method.to_string(), operation);
+        }
+    }
+
+    Ok(Value::Object(item))
+}
+
+fn convert_operation(
+    operation: Value,
+    consumes: &[String],
+    produces: &[String],
+) -> Result<Value, String> {
+    let mut operation = into_object(operation, "Operation")?;
+
+    if operation.contains_key("schemes") {
+        return Err("Operation level schemes can't be mapped to OpenAPI 3.0".to_string());
+    }
+
+    let consumes = match operation.remove("consumes") {
+        Some(value) => media_types(Some(value), "consumes")?,
+        None => consumes.to_vec(),
+    };
+    let produces = match operation.remove("produces") {
+        Some(value) => media_types(Some(value), "produces")?,
+        None => produces.to_vec(),
+    };
+
+    if let Some(parameters) = operation.remove("parameters") {
+        let Value::Array(parameters) = parameters else {
+            return Err("Operation parameters is not an array".to_string());
+        };
+
+        let mut converted = vec![];
+        for parameter in parameters {
+            if parameter.get("in").and_then(Value::as_str) == Some("body") {
+                let request_body = convert_body_parameter(parameter, &consumes)?;
+                operation.insert("requestBody".to_string(), request_body);
+            } else {
+                converted.push(convert_parameter(parameter)?);
+            }
+        }
+
+        if !converted.is_empty() {
+            operation.insert("parameters".to_string(), Value::Array(converted));
+        }
+    }
+
+    if let Some(responses) = operation.remove("responses") {
+        operation.insert(
+            "responses".to_string(),
+            map_values(responses, "responses", |response| {
+                convert_response(response, &produces)
+            })?,
+        );
+    }
+
+    Ok(Value::Object(operation))
+}
+
+fn convert_parameter_list(parameters: Value, description: &str) -> Result<Value, String> {
+    match parameters {
+        Value::Array(parameters) => parameters
+            .into_iter()
+            .map(convert_parameter)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        _ => Err(format!("{description} is not an array")),
+    }
+}
+
+fn convert_body_parameter(parameter: Value, consumes: &[String]) -> Result<Value, String> {
+    let mut parameter = into_object(parameter, "Parameter")?;
+    let schema = parameter
+        .remove("schema")
+        .ok_or("Body parameter has no schema")?;
+    let schema = convert_schema(schema)?;
+
+    let content: Map<_, _> = consumes
+        .iter()
+        .map(|media_type| (media_type.clone(), json!({ "schema": schema })))
+        .collect();
+
+    let mut request_body = Map::new();
+    request_body.insert("content".to_string(), Value::Object(content));
+    for field in ["description", "required"] {
+        if let Some(value) = parameter.remove(field) {
+            request_body.insert(field.to_string(), value);
+        }
+    }
+
+    Ok(Value::Object(request_body))
+}
+
+fn convert_parameter(parameter: Value) -> Result<Value, String> {
+    let mut parameter = into_object(parameter, "Parameter")?;
+
+    if parameter.contains_key("$ref") {
+        return Ok(Value::Object(parameter));
+    }
+
+    let location = parameter
+        .get("in")
+        .and_then(Value::as_str)
+        .ok_or("Parameter has no location")?
+        .to_string();
+
+    match location.as_str() {
+        "body" => {
+            return Err("Body parameters are only supported on operations".to_string());
+        }
+        "formData" => {
+            return Err("Form data parameters can't be mapped to OpenAPI 3.0".to_string());
+        }
+        _ => {}
+    }
+
+    if let Some(collection_format) = parameter.remove("collectionFormat") {
+        let (style, explode) = match (collection_format.as_str(), location.as_str()) {
+            (Some("csv"), "query") => ("form", false),
+            (Some("csv"), _) => ("simple", false),
+            (Some("multi"), "query") => ("form", true),
+            (Some("ssv"), "query") => ("spaceDelimited", false),
+            (Some("pipes"), "query") => ("pipeDelimited", false),
+            _ => {
+                return Err(format!(
+                    "collectionFormat {collection_format} of a {location} parameter can't be \
+                     mapped to OpenAPI 3.0"
+                ))
+            }
+        };
+        parameter.insert("style".to_string(), json!(style));
+        parameter.insert("explode".to_string(), json!(explode));
+    }
+
+    let schema = take_schema_fields(&mut parameter)?;
+    parameter.insert("schema".to_string(), schema);
+
+    Ok(Value::Object(parameter))
+}
+
+fn convert_response(response: Value, produces: &[String]) -> Result<Value, String> {
+    let mut response = into_object(response, "Response")?;
+
+    if response.contains_key("$ref") {
+        return Ok(Value::Object(response));
+    }
+
+    let mut content = Map::new();
+    if let Some(schema) = response.remove("schema") {
+        let schema = convert_schema(schema)?;
+        for media_type in produces {
+            content.insert(media_type.clone(), json!({ "schema": schema }));
+        }
+    }
+
+    if let Some(examples) = response.remove("examples") {
+        for (media_type, example) in into_object(examples, "Response examples")? {
+            let media_type_object = content.entry(media_type).or_insert_with(|| json!({}));
+            media_type_object["example"] = example;
+        }
+    }
+
+    if !content.is_empty() {
+        response.insert("content".to_string(), Value::Object(content));
+    }
+
+    if let Some(headers) = response.remove("headers") {
+        response.insert(
+            "headers".to_string(),
+            map_values(headers, "headers", |header| {
+                let mut header = into_object(header, "Header")?;
+                let schema = take_schema_fields(&mut header)?;
+                header.insert("schema".to_string(), schema);
+                Ok(Value::Object(header))
+            })?,
+        );
+    }
+
+    Ok(Value::Object(response))
+}
+
+fn take_schema_fields(value: &mut Map<String, Value>) -> Result<Value, String> {
+    let mut schema = Map::new();
+    for field in SCHEMA_FIELDS {
+        if let Some(field_value) = value.remove(*field) {
+            schema.insert(field.to_string(), field_value);
+        }
+    }
+
+    convert_schema(Value::Object(schema))
+}
+
+fn convert_schema(schema: Value) -> Result<Value, String> {
+    let mut schema = into_object(schema, "Schema")?;
+
+    if schema.get("type").and_then(Value::as_str) == Some("file") {
+        return Err("The file type can't be mapped to OpenAPI 3.0".to_string());
+    }
+
+    // The discriminator is the name of the property in Swagger 2.0, and an object in 3.0
+    if let Some(Value::String(property_name)) = schema.remove("discriminator") {
+        schema.insert(
+            "discriminator".to_string(),
+            json!({ "propertyName": property_name }),
+        );
+    }
+
+    // Items of parameters and headers may have a collectionFormat too, which has no
+    // equivalent on schemas
+    schema.remove("collectionFormat");
+
+    if let Some(Value::Object(properties)) = schema.remove("properties") {
+        let properties = properties
+            .into_iter()
+            .map(|(name, property)| Ok((name, convert_schema(property)?)))
+            .collect::<Result<Map<_, _>, String>>()?;
+        schema.insert("properties".to_string(), Value::Object(properties));
+    }
+
+    for field in ["items", "additionalProperties"] {
+        match schema.remove(field) {
+            Some(sub_schema @ Value::Object(_)) => {
+                schema.insert(field.to_string(), convert_schema(sub_schema)?);
+            }
+            Some(value) => {
+                schema.insert(field.to_string(), value);
+            }
+            None => {}
+        }
+    }
+
+    if let Some(Value::Array(all_of)) = schema.remove("allOf") {
+        let all_of = all_of
+            .into_iter()
+            .map(convert_schema)
+            .collect::<Result<Vec<_>, _>>()?;
+        schema.insert("allOf".to_string(), Value::Array(all_of));
+    }
+
+    Ok(Value::Object(schema))
+}
+
+fn convert_security_scheme(scheme: Value) -> Result<Value, String> {
+    let mut scheme = into_object(scheme, "Security definition")?;
+    let scheme_type = scheme
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or("Security definition has no type")?
+        .to_string();
+
+    match scheme_type.as_str() {
+        "apiKey" => {}
+        "basic" => {
+            scheme.insert("type".to_string(), json!("http"));
+            scheme.insert("scheme".to_string(), json!("basic"));
+        }
+        "oauth2" => {
+            let flow = match scheme.remove("flow").as_ref().and_then(Value::as_str) {
+                Some("implicit") => "implicit",
+                Some("password") => "password",
+                Some("application") => "clientCredentials",
+                Some("accessCode") => "authorizationCode",
+                _ => return Err("Unknown OAuth2 flow".to_string()),
+            };
+
+            let mut flow_object = Map::new();
+            for field in ["authorizationUrl", "tokenUrl", "scopes"] {
+                if let Some(value) = scheme.remove(field) {
+                    flow_object.insert(field.to_string(), value);
+                }
+            }
+            let mut flows = Map::new();
+            flows.insert(flow.to_string(), Value::Object(flow_object));
+            scheme.insert("flows".to_string(), Value::Object(flows));
+        }
+        other => return Err(format!("Unknown security definition type {other}")),
+    }
+
+    Ok(Value::Object(scheme))
+}
+
+fn rewrite_references(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                let rewritten = REFERENCE_PREFIXES.iter().find_map(|(from, to)| {
+                    reference
+                        .strip_prefix(from)
+                        .map(|name| format!("{to}{name}"))
+                });
+                if let Some(rewritten) = rewritten {
+                    *reference = rewritten;
+                }
+            }
+
+            map.values_mut().for_each(rewrite_references);
+        }
+        Value::Array(items) => items.iter_mut().for_each(rewrite_references),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use crate::gateway_api_definition::http::{
+        AllPathPatterns, MethodPattern, OpenApiHttpApiDefinitionRequest,
+    };
+
+    fn shopping_cart_swagger() -> Value {
+        json!({
+            "swagger": "2.0",
+            "info": { "title": "Shopping cart", "version": "1.0.0" },
+            "host": "api.example.com",
+            "basePath": "/v1/",
+            "schemes": ["https"],
+            "produces": ["application/json"],
+            "x-golem-api-definition-id": "shopping-cart",
+            "x-golem-api-definition-version": "0.1.0",
+            "paths": {
+                "/{user-id}/cart": {
+                    "post": {
+                        "x-golem-api-gateway-binding": {
+                            "worker-name": "worker-${request.path.user-id}",
+                            "component-id": "2696abdc-df3a-4771-8215-d6af7aa4c408",
+                            "component-version": 0,
+                            "response": "${1}"
+                        },
+                        "parameters": [
+                            {
+                                "name": "user-id",
+                                "in": "path",
+                                "required": true,
+                                "type": "string"
+                            },
+                            {
+                                "name": "item",
+                                "in": "body",
+                                "required": true,
+                                "schema": { "$ref": "#/definitions/CartItem" }
+                            }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "OK",
+                                "schema": { "$ref": "#/definitions/CartItem" }
+                            }
+                        }
+                    }
+                }
+            },
+            "definitions": {
+                "CartItem": {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn swagger_2_is_upgraded_to_open_api_3_0() {
+        let open_api = upgrade_swagger_2(shopping_cart_swagger()).unwrap();
+
+        assert_eq!(open_api["openapi"], json!("3.0.0"));
+        assert_eq!(
+            open_api["servers"],
+            json!([{ "url": "https://api.example.com" }])
+        );
+        assert_eq!(
+            open_api["components"]["schemas"]["CartItem"]["properties"]["name"],
+            json!({ "type": "string" })
+        );
+
+        let operation = &open_api["paths"]["/v1/{user-id}/cart"]["post"];
+        assert_eq!(
+            operation["parameters"][0]["schema"],
+            json!({ "type": "string" })
+        );
+        assert_eq!(
+            operation["requestBody"],
+            json!({
+                "required": true,
+                "content": {
+                    "application/json": {
+                        "schema": { "$ref": "#/components/schemas/CartItem" }
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            operation["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/CartItem" })
+        );
+    }
+
+    #[test]
+    fn swagger_2_routes_include_the_base_path() {
+        let request = OpenApiHttpApiDefinitionRequest::from_json_value(shopping_cart_swagger())
+            .unwrap()
+            .to_http_api_definition_request()
+            .unwrap();
+
+        assert_eq!(request.routes.len(), 1);
+        assert_eq!(request.routes[0].method, MethodPattern::Post);
+        assert_eq!(
+            request.routes[0].path,
+            AllPathPatterns::parse("/v1/{user-id}/cart").unwrap()
+        );
+    }
+
+    #[test]
+    fn unmappable_constructs_are_rejected() {
+        let mut swagger = shopping_cart_swagger();
+        swagger["paths"]["/{user-id}/cart"]["post"]["parameters"][1] = json!({
+            "name": "item",
+            "in": "formData",
+            "type": "string"
+        });
+
+        let result = upgrade_swagger_2(swagger);
+
+        let expected = "Form data parameters can't be mapped to OpenAPI 3.0 (in post operation) \
+                        (in path /{user-id}/cart)";
+        assert_eq!(result, Err(expected.to_string()));
+    }
+
+    #[test]
+    fn security_definitions_become_security_schemes() {
+        let mut swagger = shopping_cart_swagger();
+        swagger["securityDefinitions"] = json!({
+            "basicAuth": { "type": "basic" },
+            "oauth": {
+                "type": "oauth2",
+                "flow": "accessCode",
+                "authorizationUrl": "https://example.com/authorize",
+                "tokenUrl": "https://example.com/token",
+                "scopes": {}
+            }
+        });
+
+        let open_api = upgrade_swagger_2(swagger).unwrap();
+
+        assert_eq!(
+            open_api["components"]["securitySchemes"],
+            json!({
+                "basicAuth": { "type": "http", "scheme": "basic" },
+                "oauth": {
+                    "type": "oauth2",
+                    "flows": {
+                        "authorizationCode": {
+                            "authorizationUrl": "https://example.com/authorize",
+                            "tokenUrl": "https://example.com/token",
+                            "scopes": {}
+                        }
+                    }
+                }
+            })
+        );
+    }
+}
@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 mod api_common;
+pub mod canonical;
 pub mod http;
 
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::fmt::Display;
 
@@ -46,6 +49,28 @@ impl ApiVersion {
     pub fn new(version: &str) -> ApiVersion {
         ApiVersion(version.to_string())
     }
+
+    pub fn parse_semver(&self) -> Result<semver::Version, semver::Error> {
+        semver::Version::parse(&self.0)
+    }
+
+    // The next minor version, without pre-release and build metadata
+    pub fn next_minor(&self) -> Result<ApiVersion, semver::Error> {
+        let version = self.parse_semver()?;
+        let next = semver::Version::new(version.major, version.minor + 1, 0);
+        Ok(ApiVersion(next.to_string()))
+    }
+
+    // Semantic versioning order. Versions which are not valid semver (registered in relaxed mode)
+    // come before all valid ones, and are ordered lexicographically among themselves.
+    pub fn cmp_semver(&self, other: &ApiVersion) -> Ordering {
+        match (self.parse_semver(), other.parse_semver()) {
+            (Ok(this), Ok(other)) => this.cmp(&other),
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Err(_), Err(_)) => self.0.cmp(&other.0),
+        }
+    }
 }
 
 impl From<String> for ApiVersion {
@@ -60,6 +85,55 @@ impl Display for ApiVersion {
     }
 }
 
+// Longest label key or value accepted
+const MAX_LABEL_LENGTH: usize = 63;
+
+// Label keys are non-empty and start with a letter or a digit, values may be empty. Both consist
+// of ASCII letters, digits, `-`, `_` and `.`.
+pub fn validate_labels(labels: &BTreeMap<String, String>) -> Result<(), String> {
+    fn is_label_char(c: char) -> bool {
+        c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'
+    }
+
+    for (key, value) in labels {
+        let valid_key = key.len() <= MAX_LABEL_LENGTH
+            && key.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && key.chars().all(is_label_char);
+        if !valid_key {
+            return Err(format!("Invalid label key: {key:?}"));
+        }
+        if value.len() > MAX_LABEL_LENGTH || !value.chars().all(is_label_char) {
+            return Err(format!("Invalid value of label {key}: {value:?}"));
+        }
+    }
+    Ok(())
+}
+
 pub trait HasGolemBindings {
     fn get_bindings(&self) -> Vec<GatewayBinding>;
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::ApiVersion;
+
+    #[test]
+    fn api_versions_are_sorted_by_semver() {
+        let mut versions: Vec<ApiVersion> = ["1.10.0", "v1", "1.2.0", "1.2.0-beta.1", "latest"]
+            .into_iter()
+            .map(ApiVersion::new)
+            .collect();
+
+        versions.sort_by(|a, b| a.cmp_semver(b));
+
+        assert_eq!(
+            versions,
+            ["latest", "v1", "1.2.0-beta.1", "1.2.0", "1.10.0"]
+                .into_iter()
+                .map(ApiVersion::new)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -115,6 +115,7 @@ mod tests {
     use golem_common::model::ComponentId;
     use golem_service_base::model::VersionedComponentId;
     use rib::Expr;
+    use std::collections::BTreeMap;
 
     fn get_cors_preflight_route() -> Route {
         Route {
@@ -143,6 +144,9 @@ mod tests {
             worker_name: None,
             idempotency_key: None,
             response_mapping: ResponseMapping(Expr::literal("")),
+            timeout_ms: None,
+            component_version: None,
+            param_mappings: BTreeMap::new(),
         };
 
         Route {
@@ -162,6 +166,9 @@ mod tests {
             worker_name: None,
             idempotency_key: None,
             response_mapping: ResponseMapping(Expr::literal("")),
+            timeout_ms: None,
+            component_version: None,
+            param_mappings: BTreeMap::new(),
         };
 
         Route {
@@ -202,6 +209,7 @@ mod tests {
             routes,
             version: ApiVersion::new("v1"),
             draft: false,
+            labels: BTreeMap::new(),
             created_at: chrono::Utc::now(),
         };
 
@@ -236,6 +244,7 @@ mod tests {
             routes,
             version: ApiVersion::new("v1"),
             draft: false,
+            labels: BTreeMap::new(),
             created_at: chrono::Utc::now(),
         };
 
@@ -266,6 +275,7 @@ mod tests {
             routes,
             version: ApiVersion::new("v1"),
             draft: false,
+            labels: BTreeMap::new(),
             created_at: chrono::Utc::now(),
         };
 
@@ -14,12 +14,13 @@
 
 use crate::gateway_binding::StaticBinding;
 use crate::gateway_binding::{
-    GatewayBinding, IdempotencyKeyCompiled, ResponseMappingCompiled, WorkerBinding,
+    GatewayBinding, IdempotencyKeyCompiled, ParamMapping, ResponseMappingCompiled, WorkerBinding,
     WorkerBindingCompiled, WorkerNameCompiled,
 };
 use golem_api_grpc::proto::golem::apidefinition::GatewayBindingType as ProtoGatewayBindingType;
 use golem_common::model::GatewayBindingType;
 use rib::RibOutputTypeInfo;
+use std::collections::HashMap;
 use std::ops::Deref;
 
 // A compiled binding is a binding with all existence of Rib Expr
@@ -114,6 +115,9 @@ impl TryFrom<GatewayBindingCompiled>
                             )?,
                         ),
                         response_rib_output: None,
+                        timeout_ms: None,
+                        component_version: None,
+                        param_mappings: HashMap::new(),
                     },
                 )
             }
@@ -200,6 +204,8 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CompiledGatewayBinding
                         .transpose()?,
                 };
 
+                let param_mappings = ParamMapping::parse_all(value.param_mappings)?;
+
                 let binding_type = value.binding_type.ok_or("Missing binding_type")?;
 
                 if binding_type == 0 {
@@ -208,6 +214,9 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CompiledGatewayBinding
                         worker_name_compiled,
                         idempotency_key_compiled,
                         response_compiled,
+                        timeout_ms: value.timeout_ms,
+                        component_version: value.component_version,
+                        param_mappings,
                     }))
                 } else {
                     Ok(GatewayBindingCompiled::FileServer(WorkerBindingCompiled {
@@ -215,6 +224,9 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CompiledGatewayBinding
                         worker_name_compiled,
                         idempotency_key_compiled,
                         response_compiled,
+                        timeout_ms: value.timeout_ms,
+                        component_version: value.component_version,
+                        param_mappings,
                     }))
                 }
             }
@@ -232,7 +244,7 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CompiledGatewayBinding
 }
 
 mod internal {
-    use crate::gateway_binding::WorkerBindingCompiled;
+    use crate::gateway_binding::{ParamMapping, WorkerBindingCompiled};
 
     use golem_common::model::GatewayBindingType;
 
@@ -308,6 +320,9 @@ mod internal {
                 binding_type: Some(binding_type),
                 static_binding: None,
                 response_rib_output,
+                timeout_ms: worker_binding.timeout_ms,
+                component_version: worker_binding.component_version,
+                param_mappings: ParamMapping::to_strings(&worker_binding.param_mappings),
             },
         )
     }
@@ -16,15 +16,18 @@ pub(crate) use crate::gateway_execution::gateway_binding_resolver::*;
 pub(crate) use crate::gateway_execution::rib_input_value_resolver::*;
 pub(crate) use crate::gateway_request::request_details::*;
 pub(crate) use gateway_binding_compiled::*;
+pub use param_mapping::*;
 use golem_api_grpc::proto::golem::apidefinition::GatewayBindingType;
 use golem_service_base::model::VersionedComponentId;
 use rib::Expr;
 pub use static_binding::*;
+use std::collections::HashMap;
 use std::ops::Deref;
 pub(crate) use worker_binding::*;
 pub(crate) use worker_binding_compiled::*;
 
 mod gateway_binding_compiled;
+mod param_mapping;
 mod static_binding;
 mod worker_binding;
 mod worker_binding_compiled;
@@ -97,6 +100,9 @@ impl TryFrom<GatewayBinding> for golem_api_grpc::proto::golem::apidefinition::Ga
                     response: Some(worker_binding.response_mapping.0.into()),
                     idempotency_key: worker_binding.idempotency_key.map(|x| x.into()),
                     static_binding: None,
+                    timeout_ms: worker_binding.timeout_ms,
+                    component_version: worker_binding.component_version,
+                    param_mappings: ParamMapping::to_strings(&worker_binding.param_mappings),
                 },
             ),
             GatewayBinding::FileServer(worker_binding) => Ok(
@@ -107,6 +113,9 @@ impl TryFrom<GatewayBinding> for golem_api_grpc::proto::golem::apidefinition::Ga
                     response: Some(worker_binding.response_mapping.0.into()),
                     idempotency_key: worker_binding.idempotency_key.map(|x| x.into()),
                     static_binding: None,
+                    timeout_ms: worker_binding.timeout_ms,
+                    component_version: worker_binding.component_version,
+                    param_mappings: ParamMapping::to_strings(&worker_binding.param_mappings),
                 },
             ),
             GatewayBinding::Static(static_binding) => {
@@ -133,6 +142,9 @@ impl TryFrom<GatewayBinding> for golem_api_grpc::proto::golem::apidefinition::Ga
                         response: None,
                         idempotency_key: None,
                         static_binding: Some(static_binding),
+                        timeout_ms: None,
+                        component_version: None,
+                        param_mappings: HashMap::new(),
                     },
                 )
             }
@@ -161,12 +173,21 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::GatewayBinding> for Ga
                 let idempotency_key = value.idempotency_key.map(Expr::try_from).transpose()?;
                 let response_proto = value.response.ok_or("Missing response field")?;
                 let response = Expr::try_from(response_proto)?;
+                let timeout_ms = WorkerBinding::validate_timeout_ms(value.timeout_ms)?;
+                let component_version = WorkerBinding::validate_component_version(
+                    value.component_version,
+                    &worker_name,
+                )?;
+                let param_mappings = ParamMapping::parse_all(value.param_mappings)?;
 
                 Ok(GatewayBinding::Default(WorkerBinding {
                     component_id,
                     worker_name,
                     idempotency_key,
                     response_mapping: ResponseMapping(response),
+                    timeout_ms,
+                    component_version,
+                    param_mappings,
                 }))
             }
             golem_api_grpc::proto::golem::apidefinition::GatewayBindingType::FileServer => {
@@ -177,12 +198,21 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::GatewayBinding> for Ga
                 let idempotency_key = value.idempotency_key.map(Expr::try_from).transpose()?;
                 let response_proto = value.response.ok_or("Missing response field")?;
                 let response = Expr::try_from(response_proto)?;
+                let timeout_ms = WorkerBinding::validate_timeout_ms(value.timeout_ms)?;
+                let component_version = WorkerBinding::validate_component_version(
+                    value.component_version,
+                    &worker_name,
+                )?;
+                let param_mappings = ParamMapping::parse_all(value.param_mappings)?;
 
                 Ok(GatewayBinding::FileServer(WorkerBinding {
                     component_id,
                     worker_name,
                     idempotency_key,
                     response_mapping: ResponseMapping(response),
+                    timeout_ms,
+                    component_version,
+                    param_mappings,
                 }))
             }
             golem_api_grpc::proto::golem::apidefinition::GatewayBindingType::CorsPreflight => {
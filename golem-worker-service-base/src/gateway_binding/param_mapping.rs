@@ -0,0 +1,335 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::gateway_api_definition::http::{AllPathPatterns, PathPattern};
+use crate::gateway_binding::HttpRequestDetails;
+
+// Where the value of a worker binding parameter is taken from in the incoming request.
+// The resolved parameters are available to the Rib expressions of the binding
+// as `request.params.<name>`.
+//
+// In API definitions a mapping is written as `<source>:<argument>`:
+//   path:<name>       a variable of the route's path
+//   query:<name>      a query parameter declared in the route's path
+//   header:<name>     a request header, matched case-insensitively
+//   body:<pointer>    the part of the JSON body at a JSON pointer, `body:` is the whole body
+//   literal:<json>    a constant JSON value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamMapping {
+    PathParam(String),
+    QueryParam(String),
+    Header(String),
+    BodyPointer(String),
+    Literal(Value),
+}
+
+impl ParamMapping {
+    // Checks that the parameters the mapping refers to are declared by the route
+    pub fn validate_for_route(&self, path: &AllPathPatterns) -> Result<(), String> {
+        match self {
+            ParamMapping::PathParam(name) => {
+                let declared = path.path_patterns.iter().any(|pattern| match pattern {
+                    PathPattern::Var(var) | PathPattern::CatchAllVar(var) => &var.key_name == name,
+                    PathPattern::Literal(_) => false,
+                });
+
+                if declared {
+                    Ok(())
+                } else {
+                    Err(format!("Path {} has no variable {}", path, name))
+                }
+            }
+            ParamMapping::QueryParam(name) => {
+                if path.query_params.iter().any(|query| &query.key_name == name) {
+                    Ok(())
+                } else {
+                    Err(format!("Path {} has no query parameter {}", path, name))
+                }
+            }
+            ParamMapping::Header(_) | ParamMapping::BodyPointer(_) | ParamMapping::Literal(_) => {
+                Ok(())
+            }
+        }
+    }
+
+    pub fn resolve(&self, request: &HttpRequestDetails) -> Result<Value, String> {
+        match self {
+            ParamMapping::PathParam(name) => request
+                .request_path_params
+                .get(name)
+                .cloned()
+                .ok_or(format!("Missing path variable {}", name)),
+            ParamMapping::QueryParam(name) => request
+                .request_query_params
+                .get(name)
+                .cloned()
+                .ok_or(format!("Missing query parameter {}", name)),
+            ParamMapping::Header(name) => request
+                .request_headers
+                .get(name)
+                .cloned()
+                .ok_or(format!("Missing header {}", name)),
+            ParamMapping::BodyPointer(pointer) => request
+                .request_body_value
+                .pointer(pointer)
+                .cloned()
+                .ok_or(format!("Missing request body field {}", pointer)),
+            ParamMapping::Literal(value) => Ok(value.clone()),
+        }
+    }
+
+    // Resolves all the mappings of a binding into a JSON object keyed by the parameter names
+    pub fn resolve_all(
+        mappings: &BTreeMap<String, ParamMapping>,
+        request: &HttpRequestDetails,
+    ) -> Result<Value, String> {
+        let mut params = serde_json::Map::new();
+
+        for (name, mapping) in mappings {
+            let value = mapping
+                .resolve(request)
+                .map_err(|err| format!("Cannot resolve parameter {}: {}", name, err))?;
+            params.insert(name.clone(), value);
+        }
+
+        Ok(Value::Object(params))
+    }
+
+    pub fn parse_all(
+        mappings: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<BTreeMap<String, ParamMapping>, String> {
+        mappings
+            .into_iter()
+            .map(|(name, mapping)| {
+                if name.is_empty() {
+                    return Err("Parameter mapping with an empty name".to_string());
+                }
+                let mapping = ParamMapping::from_str(&mapping)
+                    .map_err(|err| format!("Invalid mapping of parameter {}: {}", name, err))?;
+                Ok((name, mapping))
+            })
+            .collect()
+    }
+
+    pub fn to_strings<C: FromIterator<(String, String)>>(
+        mappings: &BTreeMap<String, ParamMapping>,
+    ) -> C {
+        mappings
+            .iter()
+            .map(|(name, mapping)| (name.clone(), mapping.to_string()))
+            .collect()
+    }
+}
+
+impl FromStr for ParamMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (source, argument) = s
+            .split_once(':')
+            .ok_or(format!("Expected <source>:<argument>, got {}", s))?;
+
+        match source {
+            "path" | "query" if argument.is_empty() => Err(format!("Missing name in {}", s)),
+            "path" => Ok(ParamMapping::PathParam(argument.to_string())),
+            "query" => Ok(ParamMapping::QueryParam(argument.to_string())),
+            "header" => {
+                let header_name = http::HeaderName::from_str(argument)
+                    .map_err(|_| format!("Invalid header name {}", argument))?;
+                Ok(ParamMapping::Header(header_name.to_string()))
+            }
+            "body" if argument.is_empty() || argument.starts_with('/') => {
+                Ok(ParamMapping::BodyPointer(argument.to_string()))
+            }
+            "body" => Err(format!(
+                "Invalid JSON pointer {}, it must be empty or start with /",
+                argument
+            )),
+            "literal" => serde_json::from_str(argument)
+                .map(ParamMapping::Literal)
+                .map_err(|err| format!("Invalid JSON literal {}: {}", argument, err)),
+            _ => Err(format!(
+                "Unknown parameter source {}, expected path, query, header, body or literal",
+                source
+            )),
+        }
+    }
+}
+
+impl Display for ParamMapping {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamMapping::PathParam(name) => write!(f, "path:{}", name),
+            ParamMapping::QueryParam(name) => write!(f, "query:{}", name),
+            ParamMapping::Header(name) => write!(f, "header:{}", name),
+            ParamMapping::BodyPointer(pointer) => write!(f, "body:{}", pointer),
+            ParamMapping::Literal(value) => write!(f, "literal:{}", value),
+        }
+    }
+}
+
+impl Serialize for ParamMapping {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ParamMapping {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        ParamMapping::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use crate::gateway_binding::RequestBody;
+    use serde_json::json;
+
+    fn request() -> HttpRequestDetails {
+        let mut request = HttpRequestDetails::empty();
+        request.request_path_params.0.push("id".to_string(), json!(42));
+        request
+            .request_query_params
+            .0
+            .push("limit".to_string(), json!(10));
+        request
+            .request_headers
+            .0
+            .push("x-user".to_string(), json!("alice"));
+        request.request_body_value = RequestBody(json!({"order": {"items": [1, 2]}}));
+        request
+    }
+
+    fn route_path() -> AllPathPatterns {
+        AllPathPatterns::parse("/users/{id}/orders?{limit}").unwrap()
+    }
+
+    #[test]
+    fn path_param() {
+        let mapping = ParamMapping::from_str("path:id").unwrap();
+
+        assert_eq!(mapping, ParamMapping::PathParam("id".to_string()));
+        assert!(mapping.validate_for_route(&route_path()).is_ok());
+        assert!(ParamMapping::from_str("path:user")
+            .unwrap()
+            .validate_for_route(&route_path())
+            .is_err());
+        assert_eq!(mapping.resolve(&request()), Ok(json!(42)));
+    }
+
+    #[test]
+    fn query_param() {
+        let mapping = ParamMapping::from_str("query:limit").unwrap();
+
+        assert_eq!(mapping, ParamMapping::QueryParam("limit".to_string()));
+        assert!(mapping.validate_for_route(&route_path()).is_ok());
+        assert!(ParamMapping::from_str("query:id")
+            .unwrap()
+            .validate_for_route(&route_path())
+            .is_err());
+        assert_eq!(mapping.resolve(&request()), Ok(json!(10)));
+    }
+
+    #[test]
+    fn header() {
+        let mapping = ParamMapping::from_str("header:X-User").unwrap();
+
+        assert_eq!(mapping, ParamMapping::Header("x-user".to_string()));
+        assert!(mapping.validate_for_route(&route_path()).is_ok());
+        assert_eq!(mapping.resolve(&request()), Ok(json!("alice")));
+        assert!(ParamMapping::from_str("header:x user").is_err());
+        assert!(ParamMapping::from_str("header:x-missing")
+            .unwrap()
+            .resolve(&request())
+            .is_err());
+    }
+
+    #[test]
+    fn body_pointer() {
+        let mapping = ParamMapping::from_str("body:/order/items/1").unwrap();
+
+        assert_eq!(
+            mapping,
+            ParamMapping::BodyPointer("/order/items/1".to_string())
+        );
+        assert_eq!(mapping.resolve(&request()), Ok(json!(2)));
+        assert_eq!(
+            ParamMapping::from_str("body:").unwrap().resolve(&request()),
+            Ok(json!({"order": {"items": [1, 2]}}))
+        );
+        assert!(ParamMapping::from_str("body:order").is_err());
+    }
+
+    #[test]
+    fn literal() {
+        let mapping = ParamMapping::from_str(r#"literal:{"a": [true]}"#).unwrap();
+
+        assert_eq!(mapping, ParamMapping::Literal(json!({"a": [true]})));
+        assert_eq!(mapping.resolve(&request()), Ok(json!({"a": [true]})));
+        assert!(ParamMapping::from_str("literal:not json").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_sources() {
+        assert!(ParamMapping::from_str("cookie:session").is_err());
+        assert!(ParamMapping::from_str("id").is_err());
+        assert!(ParamMapping::from_str("path:").is_err());
+    }
+
+    #[test]
+    fn string_form_roundtrips_through_serde() {
+        let mappings = BTreeMap::from_iter([
+            ("id".to_string(), "path:id".to_string()),
+            ("limit".to_string(), "query:limit".to_string()),
+            ("user".to_string(), "header:x-user".to_string()),
+            ("items".to_string(), "body:/order/items".to_string()),
+            ("flag".to_string(), "literal:true".to_string()),
+        ]);
+
+        let parsed = ParamMapping::parse_all(mappings.clone()).unwrap();
+        assert_eq!(
+            ParamMapping::to_strings::<BTreeMap<_, _>>(&parsed),
+            mappings
+        );
+
+        let json = serde_json::to_value(&parsed).unwrap();
+        let deserialized: BTreeMap<String, ParamMapping> = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, parsed);
+    }
+
+    #[test]
+    fn resolves_all_parameters_into_an_object() {
+        let mappings = ParamMapping::parse_all(BTreeMap::from_iter([
+            ("id".to_string(), "path:id".to_string()),
+            ("user".to_string(), "header:x-user".to_string()),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            ParamMapping::resolve_all(&mappings, &request()),
+            Ok(json!({"id": 42, "user": "alice"}))
+        );
+    }
+}
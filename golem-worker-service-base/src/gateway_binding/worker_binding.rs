@@ -13,8 +13,10 @@
 // limitations under the License.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use crate::gateway_binding::WorkerBindingCompiled;
+use crate::gateway_binding::{ParamMapping, WorkerBindingCompiled};
+use golem_common::model::ComponentVersion;
 use golem_service_base::model::VersionedComponentId;
 use rib::Expr;
 
@@ -24,6 +26,44 @@ pub struct WorkerBinding {
     pub worker_name: Option<Expr>,
     pub idempotency_key: Option<Expr>,
     pub response_mapping: ResponseMapping,
+    // Longest time to wait for the worker, if limited
+    pub timeout_ms: Option<u64>,
+    // Version the invoked workers must run, if pinned. The latest one otherwise
+    pub component_version: Option<ComponentVersion>,
+    // Values taken from the request, available to the Rib expressions as `request.params`
+    pub param_mappings: BTreeMap<String, ParamMapping>,
+}
+
+// Longest timeout accepted for a worker binding
+pub const MAX_WORKER_BINDING_TIMEOUT_MS: u64 = 5 * 60 * 1000;
+
+impl WorkerBinding {
+    pub fn validate_timeout_ms(timeout_ms: Option<u64>) -> Result<Option<u64>, String> {
+        match timeout_ms {
+            Some(timeout_ms) if timeout_ms == 0 || timeout_ms > MAX_WORKER_BINDING_TIMEOUT_MS => {
+                Err(format!(
+                    "Worker binding timeout must be between 1 and {} milliseconds, got {}",
+                    MAX_WORKER_BINDING_TIMEOUT_MS, timeout_ms
+                ))
+            }
+            timeout_ms => Ok(timeout_ms),
+        }
+    }
+
+    // Only named workers can be created at a pinned version, ephemeral ones always run the
+    // latest version
+    pub fn validate_component_version(
+        component_version: Option<ComponentVersion>,
+        worker_name: &Option<Expr>,
+    ) -> Result<Option<ComponentVersion>, String> {
+        match component_version {
+            Some(component_version) if worker_name.is_none() => Err(format!(
+                "Worker binding pinned to component version {} must have a worker name",
+                component_version
+            )),
+            component_version => Ok(component_version),
+        }
+    }
 }
 
 // ResponseMapping will consist of actual logic such as invoking worker functions
@@ -45,6 +85,9 @@ impl From<WorkerBindingCompiled> for WorkerBinding {
             response_mapping: ResponseMapping(
                 worker_binding.response_compiled.response_mapping_expr,
             ),
+            timeout_ms: worker_binding.timeout_ms,
+            component_version: worker_binding.component_version,
+            param_mappings: worker_binding.param_mappings,
         }
     }
 }
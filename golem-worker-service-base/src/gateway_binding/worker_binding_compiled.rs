@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::gateway_binding::{ResponseMapping, WorkerBinding};
+use crate::gateway_binding::{ParamMapping, ResponseMapping, WorkerBinding};
 use crate::gateway_rib_compiler::{DefaultWorkerServiceRibCompiler, WorkerServiceRibCompiler};
+use golem_common::model::ComponentVersion;
 use golem_service_base::model::VersionedComponentId;
 use golem_wasm_ast::analysis::AnalysedExport;
 use rib::{Expr, RibByteCode, RibInputTypeInfo, RibOutputTypeInfo, WorkerFunctionsInRib};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct WorkerBindingCompiled {
@@ -24,6 +26,9 @@ pub struct WorkerBindingCompiled {
     pub worker_name_compiled: Option<WorkerNameCompiled>,
     pub idempotency_key_compiled: Option<IdempotencyKeyCompiled>,
     pub response_compiled: ResponseMappingCompiled,
+    pub timeout_ms: Option<u64>,
+    pub component_version: Option<ComponentVersion>,
+    pub param_mappings: BTreeMap<String, ParamMapping>,
 }
 
 impl WorkerBindingCompiled {
@@ -56,6 +61,9 @@ impl WorkerBindingCompiled {
             worker_name_compiled,
             idempotency_key_compiled,
             response_compiled,
+            timeout_ms: gateway_worker_binding.timeout_ms,
+            component_version: gateway_worker_binding.component_version,
+            param_mappings: gateway_worker_binding.param_mappings.clone(),
         })
     }
 }
@@ -90,3 +90,29 @@ impl<AuthCtx, Namespace: HasAccountId + Send + Sync> ApiDefinitionsLookup<InputH
         Ok(http_api_defs)
     }
 }
+
+// Looks up the same API definition for every request, regardless of its host. Used for trying
+// out the routes of an API definition which is not deployed.
+pub struct SingleApiDefinitionLookup<Namespace> {
+    definition: CompiledHttpApiDefinition<Namespace>,
+}
+
+impl<Namespace> SingleApiDefinitionLookup<Namespace> {
+    pub fn new(definition: CompiledHttpApiDefinition<Namespace>) -> Self {
+        Self { definition }
+    }
+}
+
+#[async_trait]
+impl<Namespace: Clone + Send + Sync> ApiDefinitionsLookup<InputHttpRequest>
+    for SingleApiDefinitionLookup<Namespace>
+{
+    type ApiDefinition = CompiledHttpApiDefinition<Namespace>;
+
+    async fn get(
+        &self,
+        _input_http_request: &InputHttpRequest,
+    ) -> Result<Vec<Self::ApiDefinition>, ApiDefinitionLookupError> {
+        Ok(vec![self.definition.clone()])
+    }
+}
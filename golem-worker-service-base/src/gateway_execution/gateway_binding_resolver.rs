@@ -24,7 +24,7 @@ use crate::gateway_middleware::{MiddlewareError, MiddlewareSuccess};
 use crate::gateway_request::http_request::{router, InputHttpRequest};
 use crate::gateway_security::{IdentityProvider, OpenIdClient};
 use async_trait::async_trait;
-use golem_common::model::IdempotencyKey;
+use golem_common::model::{ComponentVersion, IdempotencyKey};
 use golem_common::SafeDisplay;
 use golem_service_base::model::VersionedComponentId;
 use http::StatusCode;
@@ -34,6 +34,7 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 
 // Every type of request (example: InputHttpRequest (which corresponds to a Route)) can have an instance of this resolver,
 // which will resolve the gateway binding equired for that request.
@@ -63,6 +64,10 @@ impl ErrorOrRedirect {
     pub fn rib_input_type_mismatch(err: RibInputTypeMismatch) -> Self {
         ErrorOrRedirect::Error(GatewayBindingResolverError::RibInputTypeMismatch(err))
     }
+
+    pub fn missing_param(err: String) -> Self {
+        ErrorOrRedirect::Error(GatewayBindingResolverError::MissingParam(err))
+    }
 }
 
 #[derive(Debug)]
@@ -71,6 +76,7 @@ pub enum GatewayBindingResolverError {
     Internal(String),
     RouteNotFound,
     MiddlewareError(MiddlewareError),
+    MissingParam(String),
 }
 
 impl SafeDisplay for GatewayBindingResolverError {
@@ -82,6 +88,7 @@ impl SafeDisplay for GatewayBindingResolverError {
             GatewayBindingResolverError::Internal(err) => format!("Internal: {}", err),
             GatewayBindingResolverError::RouteNotFound => "RouteNotFound".to_string(),
             GatewayBindingResolverError::MiddlewareError(err) => err.to_safe_string(),
+            GatewayBindingResolverError::MissingParam(err) => err.clone(),
         }
     }
 }
@@ -103,6 +110,9 @@ impl GatewayBindingResolverError {
                     MiddlewareError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
                     MiddlewareError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
                 }),
+            GatewayBindingResolverError::MissingParam(str) => poem::Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(Body::from_string(str)),
         }
     }
 }
@@ -166,6 +176,8 @@ pub struct ResolvedWorkerBinding<Namespace> {
     pub worker_detail: WorkerDetail,
     pub compiled_response_mapping: ResponseMappingCompiled,
     pub namespace: Namespace,
+    pub timeout: Option<Duration>,
+    pub component_version: Option<ComponentVersion>,
 }
 
 impl<Namespace> ResolvedGatewayBinding<Namespace> {
@@ -305,6 +317,14 @@ impl<Namespace: Clone + Send + Sync + 'static>
             }
         }
 
+        if let GatewayBindingCompiled::Worker(worker_binding)
+        | GatewayBindingCompiled::FileServer(worker_binding) = binding
+        {
+            http_request_details
+                .inject_params(&worker_binding.param_mappings)
+                .map_err(ErrorOrRedirect::missing_param)?;
+        }
+
         match binding {
             GatewayBindingCompiled::FileServer(worker_binding) => internal::get_resolved_binding(
                 worker_binding,
@@ -453,6 +473,8 @@ mod internal {
             worker_detail,
             compiled_response_mapping: binding.response_compiled.clone(),
             namespace: namespace.clone(),
+            timeout: binding.timeout_ms.map(Duration::from_millis),
+            component_version: binding.component_version,
         };
 
         Ok(resolved_binding)
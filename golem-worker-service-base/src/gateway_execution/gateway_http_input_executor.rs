@@ -27,7 +27,7 @@ use crate::gateway_execution::gateway_session::GatewaySessionStore;
 use crate::gateway_execution::to_response::ToHttpResponse;
 use crate::gateway_execution::to_response_failure::ToHttpResponseFromSafeDisplay;
 use crate::gateway_request::http_request::InputHttpRequest;
-use crate::gateway_rib_interpreter::{EvaluationError, WorkerServiceRibInterpreter};
+use crate::gateway_rib_interpreter::WorkerServiceRibInterpreter;
 use crate::gateway_security::{IdentityProvider, SecuritySchemeWithProviderMetadata};
 use async_trait::async_trait;
 use golem_common::SafeDisplay;
@@ -159,28 +159,47 @@ impl<Namespace: Clone> DefaultGatewayInputExecutor<Namespace> {
         ))
     }
 
+    // Gives up with 504 Gateway Timeout if the worker doesn't respond within the timeout of the
+    // binding
     async fn get_rib_result(
         &self,
         request_rib_input: RibInput,
         worker_rib_input: RibInput,
         resolved_worker_binding: &ResolvedWorkerBinding<Namespace>,
-    ) -> Result<RibResult, EvaluationError> {
+    ) -> Result<RibResult, poem::Response> {
         let rib_input = request_rib_input.merge(worker_rib_input);
-        self.evaluator
-            .evaluate(
-                resolved_worker_binding.worker_detail.worker_name.as_deref(),
-                &resolved_worker_binding
-                    .worker_detail
-                    .component_id
-                    .component_id,
-                &resolved_worker_binding.worker_detail.idempotency_key,
-                &resolved_worker_binding
-                    .compiled_response_mapping
-                    .response_mapping_compiled,
-                &rib_input,
-                resolved_worker_binding.namespace.clone(),
-            )
-            .await
+        let evaluation = self.evaluator.evaluate(
+            resolved_worker_binding.worker_detail.worker_name.as_deref(),
+            &resolved_worker_binding
+                .worker_detail
+                .component_id
+                .component_id,
+            resolved_worker_binding.component_version,
+            &resolved_worker_binding.worker_detail.idempotency_key,
+            &resolved_worker_binding
+                .compiled_response_mapping
+                .response_mapping_compiled,
+            &rib_input,
+            resolved_worker_binding.namespace.clone(),
+        );
+
+        let result = match resolved_worker_binding.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, evaluation)
+                .await
+                .map_err(|_| {
+                    poem::Response::builder()
+                        .status(StatusCode::GATEWAY_TIMEOUT)
+                        .body(Body::from_string(format!(
+                            "Worker did not respond within {}ms",
+                            timeout.as_millis()
+                        )))
+                })?,
+            None => evaluation.await,
+        };
+
+        result.map_err(|err| {
+            err.to_response_from_safe_display(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        })
     }
 
     async fn handle_worker_binding(
@@ -203,9 +222,7 @@ impl<Namespace: Clone> DefaultGatewayInputExecutor<Namespace> {
                     .await
                 {
                     Ok(result) => result.to_response(request_details, session_store).await,
-                    Err(err) => {
-                        err.to_response_from_safe_display(|_| StatusCode::INTERNAL_SERVER_ERROR)
-                    }
+                    Err(err_response) => err_response,
                 }
             }
             Err(err_response) => err_response,
@@ -238,9 +255,7 @@ impl<Namespace: Clone> DefaultGatewayInputExecutor<Namespace> {
                             .to_response(request_details, session_store)
                             .await
                     }
-                    Err(err) => {
-                        err.to_response_from_safe_display(|_| StatusCode::INTERNAL_SERVER_ERROR)
-                    }
+                    Err(err_response) => err_response,
                 }
             }
             Err(err_response) => err_response,
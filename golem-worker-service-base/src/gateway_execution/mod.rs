@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use golem_common::model::{ComponentId, IdempotencyKey};
+use golem_common::model::{ComponentId, ComponentVersion, IdempotencyKey};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 
 pub mod api_definition_lookup;
@@ -33,6 +33,8 @@ pub use gateway_worker_request_executor::*;
 #[derive(PartialEq, Debug, Clone)]
 pub struct GatewayResolvedWorkerRequest<Namespace> {
     pub component_id: ComponentId,
+    // Version the worker must run, if the binding is pinned to one
+    pub component_version: Option<ComponentVersion>,
     pub worker_name: Option<String>,
     pub function_name: String,
     pub function_params: Vec<TypeAnnotatedValue>,
@@ -14,7 +14,9 @@
 
 mod core;
 mod pattern;
+mod precedence;
 pub mod tree;
 
 pub use core::*;
 pub use pattern::*;
+pub use precedence::*;
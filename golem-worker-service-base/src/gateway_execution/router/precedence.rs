@@ -0,0 +1,120 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RouterPattern;
+
+/// Precedence of a route, deciding which route serves a request whose path
+/// is matched by more than one route. The route with the greater precedence wins.
+///
+/// The path patterns are compared segment by segment, from left to right, and the
+/// first segment in which they differ decides: a literal beats a variable, and a
+/// variable beats a catch-all. So `/users/me` wins over `/users/{id}`, and
+/// `/users/{id}/posts` wins over `/users/{+rest}`.
+///
+/// Two routes matching the same request can only have the same precedence if they have
+/// the same literals and variables in the same positions. Such routes are ambiguous,
+/// and are rejected when an API definition is registered.
+///
+/// The router tree applies this order at dispatch: it tries the literal child of a node
+/// first, then the variable child, then the catch-all child, and backtracks when a branch
+/// does not lead to a route. The first route found is the one with the greatest precedence.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RoutePrecedence(Vec<SegmentPrecedence>);
+
+// Ordered from the least to the most specific
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SegmentPrecedence {
+    CatchAll,
+    Variable,
+    Literal,
+}
+
+impl RoutePrecedence {
+    pub fn of(path: &[RouterPattern]) -> Self {
+        Self(
+            path.iter()
+                .map(|pattern| match pattern {
+                    RouterPattern::Literal(_) => SegmentPrecedence::Literal,
+                    RouterPattern::Variable => SegmentPrecedence::Variable,
+                    RouterPattern::CatchAll => SegmentPrecedence::CatchAll,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// True if there is a request path matched by both paths.
+/// A catch-all matches one or more segments.
+pub fn paths_overlap(left: &[RouterPattern], right: &[RouterPattern]) -> bool {
+    let mut left = left.iter();
+    let mut right = right.iter();
+
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return true,
+            (Some(RouterPattern::CatchAll), Some(_)) | (Some(_), Some(RouterPattern::CatchAll)) => {
+                return true
+            }
+            (Some(RouterPattern::Literal(l)), Some(RouterPattern::Literal(r))) if l != r => {
+                return false
+            }
+            (Some(_), Some(_)) => {}
+            (Some(_), None) | (None, Some(_)) => return false,
+        }
+    }
+}
+
+/// True if some request path is matched by both paths, and neither of them takes precedence
+pub fn paths_are_ambiguous(left: &[RouterPattern], right: &[RouterPattern]) -> bool {
+    paths_overlap(left, right) && RoutePrecedence::of(left) == RoutePrecedence::of(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+
+    #[test]
+    fn literal_takes_precedence_over_variable() {
+        let me = RouterPattern::parse("/users/me");
+        let id = RouterPattern::parse("/users/{id}");
+
+        assert!(paths_overlap(&me, &id));
+        assert!(RoutePrecedence::of(&me) > RoutePrecedence::of(&id));
+        assert!(!paths_are_ambiguous(&me, &id));
+    }
+
+    #[test]
+    fn leftmost_difference_decides() {
+        let literal_first = RouterPattern::parse("/users/me/{item}");
+        let variable_first = RouterPattern::parse("/users/{id}/posts");
+        let catch_all = RouterPattern::parse("/users/*");
+
+        assert!(RoutePrecedence::of(&literal_first) > RoutePrecedence::of(&variable_first));
+        assert!(RoutePrecedence::of(&variable_first) > RoutePrecedence::of(&catch_all));
+        assert!(paths_overlap(&variable_first, &catch_all));
+    }
+
+    #[test]
+    fn same_shape_is_ambiguous_only_when_overlapping() {
+        let id = RouterPattern::parse("/users/{id}/posts");
+        let name = RouterPattern::parse("/users/{name}/posts");
+        let comments = RouterPattern::parse("/users/{id}/comments");
+
+        assert!(paths_are_ambiguous(&id, &name));
+        assert!(!paths_are_ambiguous(&id, &comments));
+        assert!(!paths_overlap(&id, &RouterPattern::parse("/users/{id}")));
+    }
+}
@@ -53,13 +53,6 @@ impl<T> Default for Children<T> {
 }
 
 impl<T> Children<T> {
-    #[inline]
-    fn get_child_by_str(&self, input: &str) -> Option<&RadixNode<T>> {
-        self.literal_children
-            .get(input)
-            .or_else(|| self.variable_child.as_ref().map(|c| c.as_ref()))
-    }
-
     fn get_child(&self, pattern: &RouterPattern) -> Option<&RadixNode<T>> {
         match pattern {
             RouterPattern::Literal(literal_pattern) => self.literal_children.get(literal_pattern),
@@ -262,41 +255,47 @@ impl<T> RadixNode<T> {
         }
     }
 
-    #[inline]
+    /// Finds the route with the greatest `RoutePrecedence` matching the path.
+    /// Literal children are tried before the variable child, and the variable child before
+    /// the catch-all child, backtracking when a branch does not lead to a route.
     pub fn matches(&self, path: &[&str]) -> Option<&T> {
-        let mut node = self;
-        let mut path_segments = path;
-        let mut last_catch_all: Option<&RadixNode<T>> = None;
-
-        loop {
-            let common_prefix_len =
-                node.interpret_common_prefix_len(path_segments, &mut last_catch_all);
+        let mut remaining_path = path;
 
-            if let Some(child) = node.children.catch_all_child.as_ref() {
-                last_catch_all.replace(child.as_ref());
-            }
-            if common_prefix_len == node.pattern.len() {
-                if common_prefix_len == path_segments.len() {
-                    // The path fully matches the current node's pattern
-                    return node.data.as_ref();
-                } else {
-                    // The path partially matches the current node's pattern
-                    path_segments = &path_segments[common_prefix_len..];
-
-                    if let Some(first_segment) = path_segments.first() {
-                        let next_child = node.children.get_child_by_str(first_segment);
-                        if let Some(child) = next_child {
-                            node = child;
-                            continue;
-                        }
+        for pattern in &self.pattern {
+            let (segment, rest) = remaining_path.split_first()?;
+            match pattern {
+                RouterPattern::Literal(literal) => {
+                    if literal.0 != *segment {
+                        return None;
                     }
                 }
+                RouterPattern::Variable => {}
+                // A catch-all is always the last pattern, and consumes the rest of the path
+                RouterPattern::CatchAll => return self.data.as_ref(),
             }
-
-            break;
+            remaining_path = rest;
         }
 
-        last_catch_all.and_then(|node| node.data.as_ref())
+        match remaining_path.first() {
+            None => self.data.as_ref(),
+            Some(segment) => self
+                .children
+                .literal_children
+                .get(*segment)
+                .and_then(|child| child.matches(remaining_path))
+                .or_else(|| {
+                    self.children
+                        .variable_child
+                        .as_ref()
+                        .and_then(|child| child.matches(remaining_path))
+                })
+                .or_else(|| {
+                    self.children
+                        .catch_all_child
+                        .as_ref()
+                        .and_then(|child| child.matches(remaining_path))
+                }),
+        }
     }
 
     // Stops iterating when it finds a catch all node.
@@ -317,32 +316,6 @@ impl<T> RadixNode<T> {
             .count()
     }
 
-    #[inline(always)]
-    fn interpret_common_prefix_len<'a>(
-        &'a self,
-        path_segments: &[&str],
-        last_catch_all: &mut Option<&'a RadixNode<T>>,
-    ) -> usize {
-        let mut common_prefix_len = 0;
-
-        for (a, b) in self.pattern.iter().zip(path_segments.iter()) {
-            match a {
-                RouterPattern::Literal(s) => {
-                    if s.0 != **b {
-                        break;
-                    }
-                }
-                RouterPattern::Variable => {}
-                RouterPattern::CatchAll => {
-                    *last_catch_all = Some(self);
-                }
-            }
-            common_prefix_len += 1;
-        }
-
-        common_prefix_len
-    }
-
     #[cfg(test)]
     fn matches_str(&self, path: &str) -> Option<&T> {
         let path: Vec<&str> = RouterPattern::split(path).collect();
@@ -654,4 +627,37 @@ mod test {
         assert_eq!(Some(&1), root.matches_str("/api/v4/users/123"));
         assert_eq!(Some(&1), root.matches_str("/api/v5/users/123/profile"));
     }
+
+    #[test]
+    fn test_static_segment_takes_precedence_over_variable() {
+        let mut root = RadixNode::default();
+
+        let path1 = RouterPattern::parse("/users/{id}");
+        root.insert_path(&path1, 1).unwrap();
+
+        let path2 = RouterPattern::parse("/users/me");
+        root.insert_path(&path2, 2).unwrap();
+
+        assert_eq!(Some(&2), root.matches_str("/users/me"));
+        assert_eq!(Some(&1), root.matches_str("/users/123"));
+    }
+
+    #[test]
+    fn test_backtracks_to_less_specific_route() {
+        let mut root = RadixNode::default();
+
+        let path1 = RouterPattern::parse("/users/me/settings");
+        root.insert_path(&path1, 1).unwrap();
+
+        let path2 = RouterPattern::parse("/users/{id}/posts");
+        root.insert_path(&path2, 2).unwrap();
+
+        let path3 = RouterPattern::parse("/users/*");
+        root.insert_path(&path3, 3).unwrap();
+
+        assert_eq!(Some(&1), root.matches_str("/users/me/settings"));
+        assert_eq!(Some(&2), root.matches_str("/users/me/posts"));
+        assert_eq!(Some(&3), root.matches_str("/users/me/comments"));
+        assert_eq!(Some(&3), root.matches_str("/users/me"));
+    }
 }
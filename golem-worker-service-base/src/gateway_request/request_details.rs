@@ -15,6 +15,7 @@
 use crate::gateway_api_definition::http::{QueryInfo, VarInfo};
 
 use crate::gateway_api_deployment::ApiSiteString;
+use crate::gateway_binding::ParamMapping;
 use crate::gateway_execution::gateway_session::{DataKey, GatewaySessionStore, SessionId};
 use crate::gateway_middleware::HttpMiddlewares;
 use crate::gateway_request::http_request::ApiInputPath;
@@ -22,7 +23,7 @@ use golem_common::SafeDisplay;
 use http::uri::Scheme;
 use http::HeaderMap;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use url::Url;
 
 // https://github.com/golemcloud/golem/issues/1069
@@ -100,6 +101,24 @@ impl HttpRequestDetails {
         Ok(())
     }
 
+    // Makes the parameters of the worker binding available as `request.params`
+    pub fn inject_params(
+        &mut self,
+        mappings: &BTreeMap<String, ParamMapping>,
+    ) -> Result<(), String> {
+        if mappings.is_empty() {
+            return Ok(());
+        }
+
+        let params = ParamMapping::resolve_all(mappings, self)?;
+
+        self.request_custom_params
+            .get_or_insert_with(HashMap::new)
+            .insert("params".to_string(), params);
+
+        Ok(())
+    }
+
     pub fn as_json(&self) -> Value {
         let typed_path_values = self.request_path_params.clone().0;
         let typed_query_values = self.request_query_params.clone().0;
@@ -309,6 +328,14 @@ impl RequestPathValues {
 pub struct RequestQueryValues(pub JsonKeyValues);
 
 impl RequestQueryValues {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0
+            .fields
+            .iter()
+            .find(|field| field.name == key)
+            .map(|field| &field.value)
+    }
+
     fn from(
         query_key_values: &HashMap<String, String>,
         query_keys: &[QueryInfo],
@@ -335,8 +362,16 @@ impl RequestQueryValues {
 }
 
 #[derive(Debug, Clone)]
-pub struct RequestHeaderValues(JsonKeyValues);
+pub struct RequestHeaderValues(pub JsonKeyValues);
 impl RequestHeaderValues {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0
+            .fields
+            .iter()
+            .find(|field| field.name.eq_ignore_ascii_case(name))
+            .map(|field| &field.value)
+    }
+
     fn from(headers: &HeaderMap) -> Result<RequestHeaderValues, Vec<String>> {
         let mut headers_map: JsonKeyValues = JsonKeyValues::default();
 
@@ -353,9 +388,13 @@ impl RequestHeaderValues {
 }
 
 #[derive(Debug, Clone)]
-pub struct RequestBody(Value);
+pub struct RequestBody(pub Value);
 
 impl RequestBody {
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        self.0.pointer(pointer)
+    }
+
     fn from(request_body: &Value) -> Result<RequestBody, Vec<String>> {
         Ok(RequestBody(request_body.clone()))
     }
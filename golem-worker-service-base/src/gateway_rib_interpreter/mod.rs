@@ -19,7 +19,7 @@ use std::sync::Arc;
 
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 
-use golem_common::model::{ComponentId, IdempotencyKey};
+use golem_common::model::{ComponentId, ComponentVersion, IdempotencyKey};
 use golem_common::SafeDisplay;
 use golem_wasm_rpc::ValueAndType;
 use rib::{RibByteCode, RibFunctionInvoke, RibInput, RibResult};
@@ -36,6 +36,7 @@ pub trait WorkerServiceRibInterpreter<Namespace> {
         &self,
         worker_name: Option<&str>,
         component_id: &ComponentId,
+        component_version: Option<ComponentVersion>,
         idempotency_key: &Option<IdempotencyKey>,
         rib_byte_code: &RibByteCode,
         rib_input: &RibInput,
@@ -86,6 +87,7 @@ impl<Namespace: Clone + Send + Sync + 'static> WorkerServiceRibInterpreter<Names
         &self,
         worker_name: Option<&str>,
         component_id: &ComponentId,
+        component_version: Option<ComponentVersion>,
         idempotency_key: &Option<IdempotencyKey>,
         expr: &RibByteCode,
         rib_input: &RibInput,
@@ -115,6 +117,7 @@ impl<Namespace: Clone + Send + Sync + 'static> WorkerServiceRibInterpreter<Names
 
                     let worker_request = GatewayResolvedWorkerRequest {
                         component_id,
+                        component_version,
                         worker_name,
                         function_name,
                         function_params,
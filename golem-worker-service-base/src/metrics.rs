@@ -21,3 +21,47 @@ pub fn register_all() -> Registry {
 
     default_registry().clone()
 }
+
+#[cfg(feature = "metrics")]
+pub mod api_definition {
+    use crate::service::gateway::audit::AuditAction;
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref DEFINITIONS_TOTAL: IntGauge = register_int_gauge!(
+            "api_definitions_total",
+            "Number of registered API definitions"
+        )
+        .unwrap();
+        static ref CHANGE_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "api_definition_change_total",
+            "Number of successful changes of API definitions",
+            &["action"]
+        )
+        .unwrap();
+        static ref REGISTER_ERRORS_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "api_definition_register_errors_total",
+            "Number of failed registrations of API definitions",
+            &["kind"]
+        )
+        .unwrap();
+    }
+
+    pub fn record_definition_count(count: u64) {
+        DEFINITIONS_TOTAL.set(count as i64);
+    }
+
+    pub fn record_change(action: AuditAction) {
+        let action = match action {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        };
+        CHANGE_TOTAL.with_label_values(&[action]).inc();
+    }
+
+    pub fn record_register_error(kind: &'static str) {
+        REGISTER_ERRORS_TOTAL.with_label_values(&[kind]).inc();
+    }
+}
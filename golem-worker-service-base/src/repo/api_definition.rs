@@ -17,6 +17,7 @@ use async_trait::async_trait;
 use conditional_trait_gen::{trait_gen, when};
 use golem_service_base::repo::RepoError;
 use sqlx::{Database, Pool, Row};
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -28,6 +29,8 @@ pub struct ApiDefinitionRecord {
     pub id: String,
     pub version: String,
     pub draft: bool,
+    // JSON object of the labels
+    pub labels: String,
     pub data: Vec<u8>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
@@ -38,11 +41,14 @@ impl ApiDefinitionRecord {
         created_at: chrono::DateTime<chrono::Utc>,
     ) -> Result<Self, String> {
         let data = record_data_serde::serialize(&definition.routes)?;
+        let labels = serde_json::to_string(&definition.labels)
+            .map_err(|e| format!("Failed to serialize labels: {e}"))?;
         Ok(Self {
             namespace: definition.namespace.to_string(),
             id: definition.id.0,
             version: definition.version.0,
             draft: definition.draft,
+            labels,
             data: data.into(),
             created_at,
         })
@@ -56,16 +62,21 @@ where
 {
     type Error = String;
     fn try_from(value: ApiDefinitionRecord) -> Result<Self, Self::Error> {
-        let routes = record_data_serde::deserialize(&value.data)?;
+        let routes = record_data_serde::deserialize_with_migrations(
+            &value.data,
+            &record_data_serde::MIGRATIONS,
+        )?;
 
         let namespace = Namespace::try_from(value.namespace)
             .map_err(|e| format!("Failed to convert namespace: {e}"))?;
+        let labels = deserialize_labels(&value.labels)?;
 
         Ok(Self {
             id: value.id.into(),
             version: value.version.into(),
             routes,
             draft: value.draft,
+            labels,
             created_at: value.created_at,
             namespace,
         })
@@ -75,23 +86,32 @@ where
 impl TryFrom<ApiDefinitionRecord> for HttpApiDefinition {
     type Error = String;
     fn try_from(value: ApiDefinitionRecord) -> Result<Self, Self::Error> {
-        let routes = record_data_serde::deserialize(&value.data)?;
+        let routes = record_data_serde::deserialize_with_migrations(
+            &value.data,
+            &record_data_serde::MIGRATIONS,
+        )?;
 
         let routes = routes
             .into_iter()
             .map(crate::gateway_api_definition::http::Route::from)
             .collect();
+        let labels = deserialize_labels(&value.labels)?;
 
         Ok(Self {
             id: value.id.into(),
             version: value.version.into(),
             routes,
             draft: value.draft,
+            labels,
             created_at: value.created_at,
         })
     }
 }
 
+fn deserialize_labels(labels: &str) -> Result<BTreeMap<String, String>, String> {
+    serde_json::from_str(labels).map_err(|e| format!("Failed to deserialize labels: {e}"))
+}
+
 #[async_trait]
 pub trait ApiDefinitionRepo {
     async fn create(&self, definition: &ApiDefinitionRecord) -> Result<(), RepoError>;
@@ -129,6 +149,21 @@ pub trait ApiDefinitionRepo {
         namespace: &str,
         id: &str,
     ) -> Result<Vec<ApiDefinitionRecord>, RepoError>;
+
+    /// Gets at most `limit` definitions ordered by id and version, starting after the given
+    /// id and version, so all definitions can be iterated without loading them at once
+    async fn get_page(
+        &self,
+        namespace: &str,
+        after: Option<(&str, &str)>,
+        limit: u32,
+    ) -> Result<Vec<ApiDefinitionRecord>, RepoError>;
+
+    /// Counts all definitions, or all versions of the definition with the given id
+    async fn count(&self, namespace: &str, id: Option<&str>) -> Result<u64, RepoError>;
+
+    /// Checks that the backing store is reachable
+    async fn ping(&self) -> Result<(), RepoError>;
 }
 
 pub struct LoggedApiDefinitionRepo<Repo: ApiDefinitionRepo> {
@@ -225,6 +260,29 @@ impl<Repo: ApiDefinitionRepo + Sync> ApiDefinitionRepo for LoggedApiDefinitionRe
         let result = self.repo.get_all_versions(namespace, id).await;
         Self::logged_with_id("get_all_versions", namespace, id, result)
     }
+
+    async fn get_page(
+        &self,
+        namespace: &str,
+        after: Option<(&str, &str)>,
+        limit: u32,
+    ) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
+        let result = self.repo.get_page(namespace, after, limit).await;
+        Self::logged_with_id("get_page", namespace, "*", result)
+    }
+
+    async fn count(&self, namespace: &str, id: Option<&str>) -> Result<u64, RepoError> {
+        let result = self.repo.count(namespace, id).await;
+        Self::logged_with_id("count", namespace, id.unwrap_or("*"), result)
+    }
+
+    async fn ping(&self) -> Result<(), RepoError> {
+        let result = self.repo.ping().await;
+        if let Err(error) = &result {
+            error!(error = error.to_string(), "ping");
+        }
+        result
+    }
 }
 
 pub struct DbApiDefinitionRepo<DB: Database> {
@@ -244,9 +302,9 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
         sqlx::query(
             r#"
               INSERT INTO api_definitions
-                (namespace, id, version, draft, data, created_at)
+                (namespace, id, version, draft, data, created_at, labels)
               VALUES
-                ($1, $2, $3, $4, $5, $6)
+                ($1, $2, $3, $4, $5, $6, $7)
                "#,
         )
         .bind(definition.namespace.clone())
@@ -255,6 +313,7 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
         .bind(definition.draft)
         .bind(definition.data.clone())
         .bind(definition.created_at)
+        .bind(definition.labels.clone())
         .execute(self.db_pool.deref())
         .await?;
 
@@ -265,7 +324,7 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
         sqlx::query(
             r#"
               UPDATE api_definitions
-              SET draft = $4, data = $5
+              SET draft = $4, data = $5, labels = $6
               WHERE namespace = $1 AND id = $2 AND version = $3
                "#,
         )
@@ -274,7 +333,7 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
         .bind(definition.version.clone())
         .bind(definition.draft)
         .bind(definition.data.clone())
-        .bind(definition.created_at)
+        .bind(definition.labels.clone())
         .execute(self.db_pool.deref())
         .await?;
 
@@ -312,7 +371,7 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
         id: &str,
         version: &str,
     ) -> Result<Option<ApiDefinitionRecord>, RepoError> {
-        sqlx::query_as::<_, ApiDefinitionRecord>("SELECT namespace, id, version, draft, data, created_at::timestamptz FROM api_definitions WHERE namespace = $1 AND id = $2 AND version = $3")
+        sqlx::query_as::<_, ApiDefinitionRecord>("SELECT namespace, id, version, draft, labels, data, created_at::timestamptz FROM api_definitions WHERE namespace = $1 AND id = $2 AND version = $3")
             .bind(namespace)
             .bind(id)
             .bind(version)
@@ -328,7 +387,7 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
         id: &str,
         version: &str,
     ) -> Result<Option<ApiDefinitionRecord>, RepoError> {
-        sqlx::query_as::<_, ApiDefinitionRecord>("SELECT namespace, id, version, draft, data, created_at FROM api_definitions WHERE namespace = $1 AND id = $2 AND version = $3")
+        sqlx::query_as::<_, ApiDefinitionRecord>("SELECT namespace, id, version, draft, labels, data, created_at FROM api_definitions WHERE namespace = $1 AND id = $2 AND version = $3")
             .bind(namespace)
             .bind(id)
             .bind(version)
@@ -375,7 +434,7 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
         namespace: &str,
     ) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
         sqlx::query_as::<_, ApiDefinitionRecord>(
-            "SELECT namespace, id, version, draft, data, created_at::timestamptz FROM api_definitions WHERE namespace = $1",
+            "SELECT namespace, id, version, draft, labels, data, created_at::timestamptz FROM api_definitions WHERE namespace = $1",
         )
         .bind(namespace)
         .fetch_all(self.db_pool.deref())
@@ -386,7 +445,7 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
     #[when(sqlx::Sqlite -> get_all)]
     async fn get_all_sqlite(&self, namespace: &str) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
         sqlx::query_as::<_, ApiDefinitionRecord>(
-            "SELECT namespace, id, version, draft, data, created_at FROM api_definitions WHERE namespace = $1",
+            "SELECT namespace, id, version, draft, labels, data, created_at FROM api_definitions WHERE namespace = $1",
         )
             .bind(namespace)
             .fetch_all(self.db_pool.deref())
@@ -400,7 +459,7 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
         namespace: &str,
         id: &str,
     ) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
-        sqlx::query_as::<_, ApiDefinitionRecord>("SELECT namespace, id, version, draft, data, created_at::timestamptz FROM api_definitions WHERE namespace = $1 AND id = $2")
+        sqlx::query_as::<_, ApiDefinitionRecord>("SELECT namespace, id, version, draft, labels, data, created_at::timestamptz FROM api_definitions WHERE namespace = $1 AND id = $2")
             .bind(namespace)
             .bind(id)
             .fetch_all(self.db_pool.deref())
@@ -414,13 +473,98 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
         namespace: &str,
         id: &str,
     ) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
-        sqlx::query_as::<_, ApiDefinitionRecord>("SELECT namespace, id, version, draft, data, created_at FROM api_definitions WHERE namespace = $1 AND id = $2")
+        sqlx::query_as::<_, ApiDefinitionRecord>("SELECT namespace, id, version, draft, labels, data, created_at FROM api_definitions WHERE namespace = $1 AND id = $2")
             .bind(namespace)
             .bind(id)
             .fetch_all(self.db_pool.deref())
             .await
             .map_err(|e| e.into())
     }
+
+    #[when(sqlx::Postgres -> get_page)]
+    async fn get_page_postgres(
+        &self,
+        namespace: &str,
+        after: Option<(&str, &str)>,
+        limit: u32,
+    ) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
+        // Ids are never empty, so the empty string is before all of them
+        let (after_id, after_version) = after.unwrap_or(("", ""));
+        sqlx::query_as::<_, ApiDefinitionRecord>(
+            r#"
+              SELECT namespace, id, version, draft, labels, data, created_at::timestamptz
+              FROM api_definitions
+              WHERE namespace = $1 AND (id > $2 OR (id = $3 AND version > $4))
+              ORDER BY id, version
+              LIMIT $5
+               "#,
+        )
+        .bind(namespace)
+        .bind(after_id)
+        .bind(after_id)
+        .bind(after_version)
+        .bind(limit as i64)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_page)]
+    async fn get_page_sqlite(
+        &self,
+        namespace: &str,
+        after: Option<(&str, &str)>,
+        limit: u32,
+    ) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
+        // Ids are never empty, so the empty string is before all of them
+        let (after_id, after_version) = after.unwrap_or(("", ""));
+        sqlx::query_as::<_, ApiDefinitionRecord>(
+            r#"
+              SELECT namespace, id, version, draft, labels, data, created_at
+              FROM api_definitions
+              WHERE namespace = $1 AND (id > $2 OR (id = $3 AND version > $4))
+              ORDER BY id, version
+              LIMIT $5
+               "#,
+        )
+        .bind(namespace)
+        .bind(after_id)
+        .bind(after_id)
+        .bind(after_version)
+        .bind(limit as i64)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn count(&self, namespace: &str, id: Option<&str>) -> Result<u64, RepoError> {
+        let count: i64 = match id {
+            Some(id) => {
+                sqlx::query_scalar(
+                    "SELECT COUNT(*) FROM api_definitions WHERE namespace = $1 AND id = $2",
+                )
+                .bind(namespace)
+                .bind(id)
+                .fetch_one(self.db_pool.deref())
+                .await?
+            }
+            None => {
+                sqlx::query_scalar("SELECT COUNT(*) FROM api_definitions WHERE namespace = $1")
+                    .bind(namespace)
+                    .fetch_one(self.db_pool.deref())
+                    .await?
+            }
+        };
+
+        Ok(count as u64)
+    }
+
+    async fn ping(&self) -> Result<(), RepoError> {
+        sqlx::query("SELECT 1")
+            .execute(self.db_pool.deref())
+            .await?;
+        Ok(())
+    }
 }
 
 pub mod record_data_serde {
@@ -429,10 +573,75 @@ pub mod record_data_serde {
     use golem_api_grpc::proto::golem::apidefinition::{
         CompiledHttpApiDefinition, CompiledHttpRoute,
     };
+    use lazy_static::lazy_static;
     use prost::Message;
+    use std::borrow::Cow;
+    use std::collections::HashMap;
 
     pub const SERIALIZATION_VERSION_V1: u8 = 1u8;
 
+    // The version new records are serialized with
+    pub const CURRENT_SERIALIZATION_VERSION: u8 = SERIALIZATION_VERSION_V1;
+
+    /// Upgrades the data of a record serialized with some version to the next version. The
+    /// migration of the current version is the identity, which keeps the data as it is.
+    pub type Migration = for<'a> fn(Cow<'a, [u8]>) -> Result<Cow<'a, [u8]>, String>;
+
+    lazy_static! {
+        // The migrations of the records loaded from the repo
+        pub static ref MIGRATIONS: Migrations = Migrations::new();
+    }
+
+    /// Migrations of the records, keyed by the version they upgrade from. Records are upgraded
+    /// one version at a time when they are loaded, ending with the identity migration of the
+    /// current version, so a change of the serialized form only needs a new version and a
+    /// migration to it.
+    #[derive(Clone)]
+    pub struct Migrations {
+        migrations: HashMap<u8, Migration>,
+    }
+
+    impl Default for Migrations {
+        fn default() -> Self {
+            Self {
+                migrations: HashMap::from([(CURRENT_SERIALIZATION_VERSION, identity as Migration)]),
+            }
+        }
+    }
+
+    impl Migrations {
+        // Version 1 is the first one, so only the identity migration is registered yet
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn with_migration(mut self, from_version: u8, migration: Migration) -> Self {
+            self.migrations.insert(from_version, migration);
+            self
+        }
+
+        /// Brings the data to the current version. Data of the current version is returned
+        /// as it is.
+        pub fn migrate<'a>(&self, version: u8, data: &'a [u8]) -> Result<Cow<'a, [u8]>, String> {
+            if version > CURRENT_SERIALIZATION_VERSION {
+                return Err(format!("Unsupported serialization version {version}"));
+            }
+
+            let mut data = Cow::Borrowed(data);
+            for version in version..=CURRENT_SERIALIZATION_VERSION {
+                let migration = self.migrations.get(&version).ok_or_else(|| {
+                    format!("No migration from serialization version {version}")
+                })?;
+                data = migration(data)?;
+            }
+            Ok(data)
+        }
+    }
+
+    fn identity(data: Cow<'_, [u8]>) -> Result<Cow<'_, [u8]>, String> {
+        Ok(data)
+    }
+
     pub fn serialize(value: &[CompiledRoute]) -> Result<Bytes, String> {
         let routes: Vec<CompiledHttpRoute> = value
             .iter()
@@ -443,28 +652,79 @@ pub mod record_data_serde {
         let proto_value: CompiledHttpApiDefinition = CompiledHttpApiDefinition { routes };
 
         let mut bytes = BytesMut::new();
-        bytes.put_u8(SERIALIZATION_VERSION_V1);
+        bytes.put_u8(CURRENT_SERIALIZATION_VERSION);
         bytes.extend_from_slice(&proto_value.encode_to_vec());
         Ok(bytes.freeze())
     }
 
-    pub fn deserialize(bytes: &[u8]) -> Result<Vec<CompiledRoute>, String> {
-        let (version, data) = bytes.split_at(1);
+    pub fn deserialize_with_migrations(
+        bytes: &[u8],
+        migrations: &Migrations,
+    ) -> Result<Vec<CompiledRoute>, String> {
+        let (version, data) = bytes
+            .split_first()
+            .ok_or_else(|| "Missing serialization version".to_string())?;
+        let data = migrations.migrate(*version, data)?;
 
-        match version[0] {
-            SERIALIZATION_VERSION_V1 => {
-                let proto_value: CompiledHttpApiDefinition = Message::decode(data)
-                    .map_err(|e| format!("Failed to deserialize value: {e}"))?;
+        let proto_value: CompiledHttpApiDefinition = Message::decode(data.as_ref())
+            .map_err(|e| format!("Failed to deserialize value: {e}"))?;
 
-                let value = proto_value
-                    .routes
-                    .into_iter()
-                    .map(CompiledRoute::try_from)
-                    .collect::<Result<Vec<CompiledRoute>, String>>()?;
+        proto_value
+            .routes
+            .into_iter()
+            .map(CompiledRoute::try_from)
+            .collect::<Result<Vec<CompiledRoute>, String>>()
+    }
 
-                Ok(value)
-            }
-            _ => Err("Unsupported serialization version".to_string()),
+    #[cfg(test)]
+    mod tests {
+        use test_r::test;
+
+        use super::*;
+
+        const SERIALIZATION_VERSION_V0: u8 = 0u8;
+
+        // A made up older version, which had the definition encoded with a length prefix
+        fn migrate_v0(data: Cow<'_, [u8]>) -> Result<Cow<'_, [u8]>, String> {
+            CompiledHttpApiDefinition::decode_length_delimited(data.as_ref())
+                .map(|definition| Cow::Owned(definition.encode_to_vec()))
+                .map_err(|e| format!("Failed to decode v0 data: {e}"))
+        }
+
+        #[test]
+        fn older_versions_are_migrated() {
+            let definition = CompiledHttpApiDefinition { routes: vec![] };
+            let mut fixture = vec![SERIALIZATION_VERSION_V0];
+            fixture.extend(definition.encode_length_delimited_to_vec());
+
+            let migrations = Migrations::new()
+                .with_migration(SERIALIZATION_VERSION_V0, migrate_v0);
+            let routes = deserialize_with_migrations(&fixture, &migrations).unwrap();
+            assert!(routes.is_empty());
+
+            let error = deserialize_with_migrations(&fixture, &MIGRATIONS).unwrap_err();
+            assert_eq!(error, "No migration from serialization version 0");
+        }
+
+        #[test]
+        fn current_version_is_not_migrated() {
+            let data = serialize(&[]).unwrap();
+            assert_eq!(data[0], CURRENT_SERIALIZATION_VERSION);
+
+            let migrated = MIGRATIONS.migrate(data[0], &data[1..]).unwrap();
+            assert!(matches!(migrated, Cow::Borrowed(_)));
+            assert!(deserialize_with_migrations(&data, &MIGRATIONS)
+                .unwrap()
+                .is_empty());
+        }
+
+        #[test]
+        fn unknown_versions_are_rejected() {
+            assert!(deserialize_with_migrations(&[], &MIGRATIONS).is_err());
+            assert!(
+                deserialize_with_migrations(&[CURRENT_SERIALIZATION_VERSION + 1], &MIGRATIONS)
+                    .is_err()
+            );
         }
     }
 }
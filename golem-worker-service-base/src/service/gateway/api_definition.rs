@@ -15,12 +15,15 @@
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::api::HttpApiDefinitionResponseData;
 use crate::gateway_api_definition::http::{
     CompiledHttpApiDefinition, ComponentMetadataDictionary, HttpApiDefinition,
     HttpApiDefinitionRequest, RouteCompilationErrors,
 };
 use crate::gateway_api_definition::{ApiDefinitionId, ApiVersion, HasGolemBindings};
+use crate::gateway_binding::GatewayBindingCompiled;
 use crate::gateway_security::IdentityProviderError;
 use crate::repo::api_definition::ApiDefinitionRecord;
 use crate::repo::api_definition::ApiDefinitionRepo;
@@ -29,12 +32,16 @@ use crate::service::component::ComponentService;
 use crate::service::gateway::api_definition_validator::{
     ApiDefinitionValidatorService, ValidationErrors,
 };
+use crate::service::gateway::keyed_lock::KeyedLock;
+use crate::service::gateway::reservation::{ReservationStore, ReservationToken};
 use crate::service::gateway::security_scheme::{SecuritySchemeService, SecuritySchemeServiceError};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use golem_common::model::ComponentId;
 use golem_common::SafeDisplay;
 use golem_service_base::model::{Component, VersionedComponentId};
 use golem_service_base::repo::RepoError;
+use tokio::sync::OwnedMutexGuard;
 use tracing::{error, info};
 
 pub type ApiResult<T> = Result<T, ApiDefinitionError>;
@@ -67,6 +74,16 @@ pub enum ApiDefinitionError {
     ApiDefinitionAlreadyExists(ApiDefinitionId),
     #[error("API definition deployed: {0}")]
     ApiDefinitionDeployed(String),
+    #[error("API definition limit exceeded: at most {0} API definitions can be registered")]
+    ApiDefinitionLimitExceeded(u64),
+    #[error("API definition version is reserved: {0}/{1}")]
+    ApiDefinitionReserved(ApiDefinitionId, ApiVersion),
+    #[error("API definition has been modified: {0}/{1}")]
+    ApiDefinitionModified(ApiDefinitionId, ApiVersion),
+    #[error("API definition reservation not found, it may have expired")]
+    ReservationNotFound,
+    #[error("API definition reservation is for another API definition: {0}/{1}")]
+    ReservationMismatch(ApiDefinitionId, ApiVersion),
     #[error("Internal repository error: {0}")]
     InternalRepoError(RepoError),
     #[error("Internal error: {0}")]
@@ -92,6 +109,11 @@ impl SafeDisplay for ApiDefinitionError {
             ApiDefinitionError::ApiDefinitionAlreadyExists(_) => self.to_string(),
             ApiDefinitionError::IdentityProviderError(inner) => inner.to_safe_string(),
             ApiDefinitionError::ApiDefinitionDeployed(_) => self.to_string(),
+            ApiDefinitionError::ApiDefinitionLimitExceeded(_) => self.to_string(),
+            ApiDefinitionError::ApiDefinitionReserved(_, _) => self.to_string(),
+            ApiDefinitionError::ApiDefinitionModified(_, _) => self.to_string(),
+            ApiDefinitionError::ReservationNotFound => self.to_string(),
+            ApiDefinitionError::ReservationMismatch(_, _) => self.to_string(),
             ApiDefinitionError::InternalRepoError(inner) => inner.to_safe_string(),
             ApiDefinitionError::Internal(_) => self.to_string(),
             ApiDefinitionError::SecuritySchemeError(inner) => inner.to_safe_string(),
@@ -115,6 +137,16 @@ impl From<RouteCompilationErrors> for ApiDefinitionError {
     }
 }
 
+// The id and version of an API definition reserved until `expires_at`, which only the holder of
+// the token can create
+#[derive(Debug, Clone)]
+pub struct ApiDefinitionReservation {
+    pub id: ApiDefinitionId,
+    pub version: ApiVersion,
+    pub token: ReservationToken,
+    pub expires_at: DateTime<Utc>,
+}
+
 // A namespace here can be example: (account, project) etc.
 // Ideally a repo service and its implementation with a different service impl that takes care of
 // validations, authorisations etc is the right approach. However we are keeping it simple for now.
@@ -127,9 +159,22 @@ pub trait ApiDefinitionService<AuthCtx, Namespace> {
         auth_ctx: &AuthCtx,
     ) -> ApiResult<CompiledHttpApiDefinition<Namespace>>;
 
+    /// Same as `create`, but fails with `ApiDefinitionAlreadyExists` if any version of the API
+    /// definition exists, not only the given one
+    async fn create_if_absent(
+        &self,
+        definition: &HttpApiDefinitionRequest,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<CompiledHttpApiDefinition<Namespace>>;
+
+    /// With `if_match`, the API definition is only updated if the entity tag of its current
+    /// content is one of the given ones, otherwise it fails with `ApiDefinitionModified`. The
+    /// check is done while no other change of the API definition can happen.
     async fn update(
         &self,
         definition: &HttpApiDefinitionRequest,
+        if_match: Option<&[String]>,
         namespace: &Namespace,
         auth_ctx: &AuthCtx,
     ) -> ApiResult<CompiledHttpApiDefinition<Namespace>>;
@@ -142,13 +187,24 @@ pub trait ApiDefinitionService<AuthCtx, Namespace> {
         auth_ctx: &AuthCtx,
     ) -> ApiResult<Option<CompiledHttpApiDefinition<Namespace>>>;
 
+    /// Returns whether the API definition existed, and was deleted. A deployed API definition
+    /// is not deleted.
     async fn delete(
         &self,
         id: &ApiDefinitionId,
         version: &ApiVersion,
         namespace: &Namespace,
         auth_ctx: &AuthCtx,
-    ) -> ApiResult<()>;
+    ) -> ApiResult<bool>;
+
+    /// Deletes all versions of the API definition with the given id, and returns the deleted ones.
+    /// Nothing is deleted if any of the versions is deployed.
+    async fn delete_all_versions(
+        &self,
+        id: &ApiDefinitionId,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<Vec<ApiVersion>>;
 
     async fn get_all(
         &self,
@@ -156,14 +212,74 @@ pub trait ApiDefinitionService<AuthCtx, Namespace> {
         auth_ctx: &AuthCtx,
     ) -> ApiResult<Vec<CompiledHttpApiDefinition<Namespace>>>;
 
+    /// Gets all API definitions, or only the ones with an id starting with `id_prefix`
+    async fn get_all_filtered(
+        &self,
+        id_prefix: Option<&str>,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<Vec<CompiledHttpApiDefinition<Namespace>>>;
+
     async fn get_all_versions(
         &self,
         id: &ApiDefinitionId,
         namespace: &Namespace,
         auth_ctx: &AuthCtx,
     ) -> ApiResult<Vec<CompiledHttpApiDefinition<Namespace>>>;
+
+    /// Gets all API definitions with at least one route bound to the given component
+    async fn find_by_component(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<Vec<CompiledHttpApiDefinition<Namespace>>>;
+
+    /// Gets at most `limit` API definitions, ordered by id and version, which come after `after`
+    async fn get_page(
+        &self,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+        after: Option<(ApiDefinitionId, ApiVersion)>,
+        limit: u32,
+    ) -> ApiResult<Vec<CompiledHttpApiDefinition<Namespace>>>;
+
+    /// Counts all API definitions, or all versions of the one with the given id
+    async fn count(
+        &self,
+        id: Option<&ApiDefinitionId>,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<u64>;
+
+    /// Reserves a version of an API definition, which can then only be created by `commit`
+    /// with the returned token, until the reservation expires. Fails with
+    /// `ApiDefinitionAlreadyExists` if the version exists, or `ApiDefinitionReserved` if it is
+    /// already reserved.
+    async fn reserve(
+        &self,
+        id: &ApiDefinitionId,
+        version: &ApiVersion,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<ApiDefinitionReservation>;
+
+    /// Creates the API definition version reserved with `token`, and releases the reservation
+    async fn commit(
+        &self,
+        token: &ReservationToken,
+        definition: &HttpApiDefinitionRequest,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<CompiledHttpApiDefinition<Namespace>>;
+
+    /// Checks that the service can reach its backing store
+    async fn ping(&self) -> ApiResult<()>;
 }
 
+// How long a version of an API definition stays reserved, unless configured otherwise
+const DEFAULT_RESERVATION_TTL: Duration = Duration::from_secs(600);
+
 pub struct ApiDefinitionServiceDefault<AuthCtx, Namespace> {
     pub component_service: Arc<dyn ComponentService<AuthCtx> + Send + Sync>,
     pub definition_repo: Arc<dyn ApiDefinitionRepo + Sync + Send>,
@@ -171,6 +287,34 @@ pub struct ApiDefinitionServiceDefault<AuthCtx, Namespace> {
     pub security_scheme_service: Arc<dyn SecuritySchemeService<Namespace> + Sync + Send>,
     pub api_definition_validator:
         Arc<dyn ApiDefinitionValidatorService<HttpApiDefinition> + Sync + Send>,
+    // Serializes the changes of the same API definition version in a namespace, so that the
+    // existence and draft checks can't interleave with a concurrent change
+    definition_locks: KeyedLock<DefinitionLockKey>,
+    // The maximum number of API definition versions in a namespace, unlimited if not set
+    max_definitions: Option<u64>,
+    // Reserved API definition versions by namespace, id and version
+    reservations: ReservationStore<(String, ApiDefinitionId, ApiVersion)>,
+    reservation_ttl: Duration,
+}
+
+// The scopes of the definition locks are distinct keys, so that no id or version, not even an
+// empty one, can make a lock collide with the lock of a broader scope
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum DefinitionLockKey {
+    Namespace(String),
+    Id(String, ApiDefinitionId),
+    Version(String, ApiDefinitionId, ApiVersion),
+}
+
+// The repository relies on ids being non-empty when paging, as the empty id sorts first
+fn validate_id(id: &ApiDefinitionId) -> ApiResult<()> {
+    if id.0.is_empty() {
+        Err(ApiDefinitionError::ValidationError(ValidationErrors {
+            errors: vec!["API definition id must not be empty".to_string()],
+        }))
+    } else {
+        Ok(())
+    }
 }
 
 impl<AuthCtx, Namespace> ApiDefinitionServiceDefault<AuthCtx, Namespace> {
@@ -182,6 +326,7 @@ impl<AuthCtx, Namespace> ApiDefinitionServiceDefault<AuthCtx, Namespace> {
         api_definition_validator: Arc<
             dyn ApiDefinitionValidatorService<HttpApiDefinition> + Sync + Send,
         >,
+        max_definitions: Option<u64>,
     ) -> Self {
         Self {
             component_service,
@@ -189,9 +334,55 @@ impl<AuthCtx, Namespace> ApiDefinitionServiceDefault<AuthCtx, Namespace> {
             security_scheme_service,
             deployment_repo,
             api_definition_validator,
+            definition_locks: KeyedLock::new(),
+            max_definitions,
+            reservations: ReservationStore::new(),
+            reservation_ttl: DEFAULT_RESERVATION_TTL,
         }
     }
 
+    /// Sets how long a reserved API definition version can be committed
+    pub fn with_reservation_ttl(mut self, reservation_ttl: Duration) -> Self {
+        self.reservation_ttl = reservation_ttl;
+        self
+    }
+
+    async fn lock_definition(
+        &self,
+        namespace: &impl Display,
+        id: &ApiDefinitionId,
+        version: &ApiVersion,
+    ) -> OwnedMutexGuard<()> {
+        self.definition_locks
+            .lock(DefinitionLockKey::Version(
+                namespace.to_string(),
+                id.clone(),
+                version.clone(),
+            ))
+            .await
+    }
+
+    // Locks the whole id. Every creation and every change of multiple versions takes it before
+    // the lock of a version, so they are serialized per id.
+    async fn lock_definition_id(
+        &self,
+        namespace: &impl Display,
+        id: &ApiDefinitionId,
+    ) -> OwnedMutexGuard<()> {
+        self.definition_locks
+            .lock(DefinitionLockKey::Id(namespace.to_string(), id.clone()))
+            .await
+    }
+
+    // Locks the whole namespace, so that the definitions in it can be counted and created
+    // without another creation in between. It is always taken after the locks of the id and
+    // the version, and no other lock is taken while holding it.
+    async fn lock_namespace(&self, namespace: &impl Display) -> OwnedMutexGuard<()> {
+        self.definition_locks
+            .lock(DefinitionLockKey::Namespace(namespace.to_string()))
+            .await
+    }
+
     async fn get_all_components(
         &self,
         definition: &HttpApiDefinition,
@@ -202,8 +393,17 @@ impl<AuthCtx, Namespace> ApiDefinitionServiceDefault<AuthCtx, Namespace> {
             .iter()
             .cloned()
             .filter_map(|binding| binding.get_worker_binding())
-            .map(|binding| async move {
-                let id = &binding.component_id;
+            .flat_map(|binding| {
+                // Pinned versions are fetched too, so that unknown ones are rejected
+                let pinned = binding
+                    .component_version
+                    .map(|version| VersionedComponentId {
+                        component_id: binding.component_id.component_id.clone(),
+                        version,
+                    });
+                std::iter::once(binding.component_id).chain(pinned)
+            })
+            .map(|id| async move {
                 self.component_service
                     .get_by_version(&id.component_id, id.version, auth_ctx)
                     .await
@@ -238,21 +438,22 @@ impl<AuthCtx, Namespace> ApiDefinitionServiceDefault<AuthCtx, Namespace> {
     }
 }
 
-#[async_trait]
-impl<AuthCtx, Namespace> ApiDefinitionService<AuthCtx, Namespace>
-    for ApiDefinitionServiceDefault<AuthCtx, Namespace>
+impl<AuthCtx, Namespace> ApiDefinitionServiceDefault<AuthCtx, Namespace>
 where
     AuthCtx: Send + Sync,
     Namespace: Display + Clone + Send + Sync + TryFrom<String>,
     <Namespace as TryFrom<String>>::Error: Display,
 {
-    async fn create(
+    // Creates the API definition, while the caller holds the lock of its version. A reserved
+    // version can only be created with the token of its reservation.
+    async fn create_locked(
         &self,
         definition: &HttpApiDefinitionRequest,
         namespace: &Namespace,
         auth_ctx: &AuthCtx,
+        token: Option<&ReservationToken>,
     ) -> ApiResult<CompiledHttpApiDefinition<Namespace>> {
-        info!(namespace = %namespace, "Create API definition");
+        validate_id(&definition.id)?;
         let created_at = Utc::now();
 
         let exists = self
@@ -270,6 +471,36 @@ where
             ));
         }
 
+        let reservation_key = (
+            namespace.to_string(),
+            definition.id.clone(),
+            definition.version.clone(),
+        );
+        if self
+            .reservations
+            .is_reserved_by_other(&reservation_key, token)
+        {
+            return Err(ApiDefinitionError::ApiDefinitionReserved(
+                definition.id.clone(),
+                definition.version.clone(),
+            ));
+        }
+
+        let _namespace_lock = match self.max_definitions {
+            Some(_) => Some(self.lock_namespace(namespace).await),
+            None => None,
+        };
+        if let Some(max_definitions) = self.max_definitions {
+            let count = self
+                .definition_repo
+                .count(namespace.to_string().as_str(), None)
+                .await?;
+
+            if count >= max_definitions {
+                return Err(ApiDefinitionError::ApiDefinitionLimitExceeded(max_definitions));
+            }
+        }
+
         let definition = HttpApiDefinition::from_http_api_definition_request::<Namespace>(
             namespace,
             definition.clone(),
@@ -301,14 +532,71 @@ where
 
         Ok(compiled_http_api_definition)
     }
+}
+
+#[async_trait]
+impl<AuthCtx, Namespace> ApiDefinitionService<AuthCtx, Namespace>
+    for ApiDefinitionServiceDefault<AuthCtx, Namespace>
+where
+    AuthCtx: Send + Sync,
+    Namespace: Display + Clone + Send + Sync + TryFrom<String>,
+    <Namespace as TryFrom<String>>::Error: Display,
+{
+    async fn create(
+        &self,
+        definition: &HttpApiDefinitionRequest,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<CompiledHttpApiDefinition<Namespace>> {
+        info!(namespace = %namespace, "Create API definition");
+        let _id_lock = self.lock_definition_id(namespace, &definition.id).await;
+        let _lock = self
+            .lock_definition(namespace, &definition.id, &definition.version)
+            .await;
+
+        self.create_locked(definition, namespace, auth_ctx, None)
+            .await
+    }
+
+    async fn create_if_absent(
+        &self,
+        definition: &HttpApiDefinitionRequest,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<CompiledHttpApiDefinition<Namespace>> {
+        info!(namespace = %namespace, "Create API definition if absent");
+        // The id lock keeps other versions from being created while the versions are checked
+        let _id_lock = self.lock_definition_id(namespace, &definition.id).await;
+        let _lock = self
+            .lock_definition(namespace, &definition.id, &definition.version)
+            .await;
+
+        let versions = self
+            .definition_repo
+            .get_all_versions(&namespace.to_string(), definition.id.0.as_str())
+            .await?;
+
+        if !versions.is_empty() {
+            return Err(ApiDefinitionError::ApiDefinitionAlreadyExists(
+                definition.id.clone(),
+            ));
+        }
+
+        self.create_locked(definition, namespace, auth_ctx, None)
+            .await
+    }
 
     async fn update(
         &self,
         definition: &HttpApiDefinitionRequest,
+        if_match: Option<&[String]>,
         namespace: &Namespace,
         auth_ctx: &AuthCtx,
     ) -> ApiResult<CompiledHttpApiDefinition<Namespace>> {
         info!(namespace = %namespace, "Update API definition");
+        let _lock = self
+            .lock_definition(namespace, &definition.id, &definition.version)
+            .await;
 
         let existing_record = self
             .definition_repo
@@ -319,15 +607,26 @@ where
             )
             .await?;
 
-        let created_at = match existing_record {
+        let existing_record = match existing_record {
             None => Err(ApiDefinitionError::ApiDefinitionNotFound(
                 definition.id.clone(),
             )),
             Some(record) if !record.draft => Err(ApiDefinitionError::ApiDefinitionNotDraft(
                 definition.id.clone(),
             )),
-            Some(record) => Ok(record.created_at),
+            Some(record) => Ok(record),
         }?;
+        let created_at = existing_record.created_at;
+
+        if let Some(if_match) = if_match {
+            let current_etag = entity_tag::<Namespace>(existing_record)?;
+            if !if_match.contains(&current_etag) {
+                return Err(ApiDefinitionError::ApiDefinitionModified(
+                    definition.id.clone(),
+                    definition.version.clone(),
+                ));
+            }
+        }
         let definition = HttpApiDefinition::from_http_api_definition_request(
             namespace,
             definition.clone(),
@@ -392,8 +691,9 @@ where
         version: &ApiVersion,
         namespace: &Namespace,
         _auth_ctx: &AuthCtx,
-    ) -> ApiResult<()> {
+    ) -> ApiResult<bool> {
         info!(namespace = %namespace, "Delete API definition");
+        let _lock = self.lock_definition(namespace, id, version).await;
 
         let deployments = self
             .deployment_repo
@@ -406,11 +706,7 @@ where
                 .delete(&namespace.to_string(), id.0.as_str(), version.0.as_str())
                 .await?;
 
-            if deleted {
-                Ok(())
-            } else {
-                Err(ApiDefinitionError::ApiDefinitionNotFound(id.clone()))
-            }
+            Ok(deleted)
         } else {
             Err(ApiDefinitionError::ApiDefinitionDeployed(
                 deployments
@@ -422,6 +718,60 @@ where
         }
     }
 
+    async fn delete_all_versions(
+        &self,
+        id: &ApiDefinitionId,
+        namespace: &Namespace,
+        _auth_ctx: &AuthCtx,
+    ) -> ApiResult<Vec<ApiVersion>> {
+        info!(namespace = %namespace, "Delete all API definition versions");
+        // No version can be created between listing the versions and deleting them
+        let _id_lock = self.lock_definition_id(namespace, id).await;
+
+        let versions = self
+            .definition_repo
+            .get_all_versions(&namespace.to_string(), id.0.as_str())
+            .await?;
+
+        if versions.is_empty() {
+            return Err(ApiDefinitionError::ApiDefinitionNotFound(id.clone()));
+        }
+
+        let mut deployments = vec![];
+        for record in &versions {
+            let version_deployments = self
+                .deployment_repo
+                .get_by_id_and_version(&namespace.to_string(), id.0.as_str(), &record.version)
+                .await?;
+            deployments.extend(version_deployments);
+        }
+
+        if !deployments.is_empty() {
+            return Err(ApiDefinitionError::ApiDefinitionDeployed(
+                deployments
+                    .into_iter()
+                    .map(|d| d.site)
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ));
+        }
+
+        let mut deleted = vec![];
+        for record in versions {
+            let version = ApiVersion(record.version);
+            let _lock = self.lock_definition(namespace, id, &version).await;
+            if self
+                .definition_repo
+                .delete(&namespace.to_string(), id.0.as_str(), version.0.as_str())
+                .await?
+            {
+                deleted.push(version);
+            }
+        }
+
+        Ok(deleted)
+    }
+
     async fn get_all(
         &self,
         namespace: &Namespace,
@@ -430,7 +780,7 @@ where
         info!(namespace = %namespace, "Get all API definitions");
         let records = self.definition_repo.get_all(&namespace.to_string()).await?;
 
-        let values: Vec<CompiledHttpApiDefinition<Namespace>> = records
+        let mut values: Vec<CompiledHttpApiDefinition<Namespace>> = records
             .iter()
             .map(|d| d.clone().try_into())
             .collect::<Result<Vec<CompiledHttpApiDefinition<Namespace>>, _>>()
@@ -440,9 +790,57 @@ where
                 ))
             })?;
 
+        // The repo doesn't order the records
+        values.sort_by(|a, b| {
+            a.id.0
+                .cmp(&b.id.0)
+                .then_with(|| a.version.cmp_semver(&b.version))
+        });
+
         Ok(values)
     }
 
+    async fn get_all_filtered(
+        &self,
+        id_prefix: Option<&str>,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<Vec<CompiledHttpApiDefinition<Namespace>>> {
+        let definitions = self.get_all(namespace, auth_ctx).await?;
+
+        Ok(match id_prefix {
+            Some(id_prefix) => definitions
+                .into_iter()
+                .filter(|definition| definition.id.0.starts_with(id_prefix))
+                .collect(),
+            None => definitions,
+        })
+    }
+
+    async fn find_by_component(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<Vec<CompiledHttpApiDefinition<Namespace>>> {
+        info!(namespace = %namespace, "Find API definitions by component");
+
+        let definitions = self.get_all(namespace, auth_ctx).await?;
+
+        Ok(definitions
+            .into_iter()
+            .filter(|definition| {
+                definition.routes.iter().any(|route| match &route.binding {
+                    GatewayBindingCompiled::Worker(binding)
+                    | GatewayBindingCompiled::FileServer(binding) => {
+                        &binding.component_id.component_id == component_id
+                    }
+                    GatewayBindingCompiled::Static(_) => false,
+                })
+            })
+            .collect())
+    }
+
     async fn get_all_versions(
         &self,
         id: &ApiDefinitionId,
@@ -456,7 +854,7 @@ where
             .get_all_versions(&namespace.to_string(), id.0.as_str())
             .await?;
 
-        let values: Vec<CompiledHttpApiDefinition<Namespace>> = records
+        let mut values: Vec<CompiledHttpApiDefinition<Namespace>> = records
             .iter()
             .map(|d| d.clone().try_into())
             .collect::<Result<Vec<CompiledHttpApiDefinition<Namespace>>, _>>()
@@ -466,8 +864,147 @@ where
                 ))
             })?;
 
+        values.sort_by(|a, b| a.version.cmp_semver(&b.version));
+
         Ok(values)
     }
+
+    async fn get_page(
+        &self,
+        namespace: &Namespace,
+        _auth_ctx: &AuthCtx,
+        after: Option<(ApiDefinitionId, ApiVersion)>,
+        limit: u32,
+    ) -> ApiResult<Vec<CompiledHttpApiDefinition<Namespace>>> {
+        let records = self
+            .definition_repo
+            .get_page(
+                &namespace.to_string(),
+                after
+                    .as_ref()
+                    .map(|(id, version)| (id.0.as_str(), version.0.as_str())),
+                limit,
+            )
+            .await?;
+
+        records
+            .into_iter()
+            .map(|record| record.try_into())
+            .collect::<Result<Vec<CompiledHttpApiDefinition<Namespace>>, _>>()
+            .map_err(|e| {
+                ApiDefinitionError::Internal(format!(
+                    "Failed to convert API definition record: {e}"
+                ))
+            })
+    }
+
+    async fn count(
+        &self,
+        id: Option<&ApiDefinitionId>,
+        namespace: &Namespace,
+        _auth_ctx: &AuthCtx,
+    ) -> ApiResult<u64> {
+        let count = self
+            .definition_repo
+            .count(&namespace.to_string(), id.map(|id| id.0.as_str()))
+            .await?;
+        Ok(count)
+    }
+
+    async fn reserve(
+        &self,
+        id: &ApiDefinitionId,
+        version: &ApiVersion,
+        namespace: &Namespace,
+        _auth_ctx: &AuthCtx,
+    ) -> ApiResult<ApiDefinitionReservation> {
+        info!(namespace = %namespace, "Reserve API definition");
+        validate_id(id)?;
+        let _lock = self.lock_definition(namespace, id, version).await;
+
+        let exists = self
+            .definition_repo
+            .get_draft(&namespace.to_string(), id.0.as_str(), version.0.as_str())
+            .await?;
+
+        if exists.is_some() {
+            return Err(ApiDefinitionError::ApiDefinitionAlreadyExists(id.clone()));
+        }
+
+        let key = (namespace.to_string(), id.clone(), version.clone());
+        let token = self
+            .reservations
+            .reserve(key, self.reservation_ttl)
+            .ok_or(ApiDefinitionError::ApiDefinitionReserved(
+                id.clone(),
+                version.clone(),
+            ))?;
+
+        let expires_at = chrono::Duration::from_std(self.reservation_ttl)
+            .ok()
+            .and_then(|ttl| Utc::now().checked_add_signed(ttl))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+        Ok(ApiDefinitionReservation {
+            id: id.clone(),
+            version: version.clone(),
+            token,
+            expires_at,
+        })
+    }
+
+    async fn commit(
+        &self,
+        token: &ReservationToken,
+        definition: &HttpApiDefinitionRequest,
+        namespace: &Namespace,
+        auth_ctx: &AuthCtx,
+    ) -> ApiResult<CompiledHttpApiDefinition<Namespace>> {
+        info!(namespace = %namespace, "Commit API definition reservation");
+        let (reserved_namespace, id, version) = self
+            .reservations
+            .find(token)
+            .ok_or(ApiDefinitionError::ReservationNotFound)?;
+
+        if reserved_namespace != namespace.to_string() {
+            return Err(ApiDefinitionError::ReservationNotFound);
+        }
+        if id != definition.id || version != definition.version {
+            return Err(ApiDefinitionError::ReservationMismatch(id, version));
+        }
+
+        let _id_lock = self.lock_definition_id(namespace, &definition.id).await;
+        let _lock = self
+            .lock_definition(namespace, &definition.id, &definition.version)
+            .await;
+
+        let created = self
+            .create_locked(definition, namespace, auth_ctx, Some(token))
+            .await?;
+        self.reservations.release(token);
+
+        Ok(created)
+    }
+
+    async fn ping(&self) -> ApiResult<()> {
+        self.definition_repo.ping().await?;
+        Ok(())
+    }
+}
+
+// The entity tag of a stored API definition, as returned to the clients which read it
+fn entity_tag<Namespace>(record: ApiDefinitionRecord) -> ApiResult<String>
+where
+    Namespace: TryFrom<String>,
+    <Namespace as TryFrom<String>>::Error: Display,
+{
+    let definition: CompiledHttpApiDefinition<Namespace> = record.try_into().map_err(|e| {
+        ApiDefinitionError::Internal(format!("Failed to convert API definition record: {e}"))
+    })?;
+
+    HttpApiDefinitionResponseData::try_from(definition)
+        .and_then(|definition| definition.entity_tag())
+        .map_err(|e| ApiDefinitionError::Internal(format!("Failed to hash API definition: {e}")))
 }
 
 #[cfg(test)]
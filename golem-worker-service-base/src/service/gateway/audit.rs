@@ -0,0 +1,168 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::gateway_api_definition::{ApiDefinitionId, ApiVersion};
+use std::fmt::{Display, Formatter};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditAction::Create => write!(f, "create"),
+            AuditAction::Update => write!(f, "update"),
+            AuditAction::Delete => write!(f, "delete"),
+        }
+    }
+}
+
+// A successful change of an API definition, and the subject of the auth context which made it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub namespace: String,
+    pub id: ApiDefinitionId,
+    pub version: ApiVersion,
+    pub action: AuditAction,
+    pub subject: String,
+}
+
+impl AuditEvent {
+    pub fn new(
+        namespace: &impl Display,
+        id: &ApiDefinitionId,
+        version: &ApiVersion,
+        action: AuditAction,
+        auth_ctx: &impl Display,
+    ) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            id: id.clone(),
+            version: version.clone(),
+            action,
+            subject: auth_ctx.to_string(),
+        }
+    }
+}
+
+pub trait AuditSink {
+    fn record(&self, event: AuditEvent);
+
+    /// Subscribes to the events recorded from now on, if the sink publishes them
+    fn subscribe(&self) -> Option<broadcast::Receiver<AuditEvent>> {
+        None
+    }
+}
+
+// Writes audit events as structured log entries with the `audit` target
+#[derive(Debug, Clone, Default)]
+pub struct TracingAuditSink;
+
+impl AuditSink for TracingAuditSink {
+    fn record(&self, event: AuditEvent) {
+        info!(
+            target: "audit",
+            namespace = event.namespace,
+            api_definition_id = event.id.to_string(),
+            version = event.version.to_string(),
+            action = event.action.to_string(),
+            subject = event.subject,
+            "API definition changed"
+        );
+    }
+}
+
+// Keeps all recorded events in memory, to be inspected by tests
+#[derive(Debug, Default)]
+pub struct CollectingAuditSink {
+    events: Mutex<Vec<AuditEvent>>,
+}
+
+impl CollectingAuditSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn events(&self) -> Vec<AuditEvent> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl AuditSink for CollectingAuditSink {
+    fn record(&self, event: AuditEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+// Events are dropped for subscribers lagging behind by more than this
+const BROADCAST_CAPACITY: usize = 1024;
+
+// Records the events with the inner sink, and also publishes them to the subscribers
+pub struct BroadcastAuditSink<S> {
+    inner: S,
+    sender: broadcast::Sender<AuditEvent>,
+}
+
+impl<S: AuditSink> BroadcastAuditSink<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            sender: broadcast::channel(BROADCAST_CAPACITY).0,
+        }
+    }
+}
+
+impl<S: AuditSink> AuditSink for BroadcastAuditSink<S> {
+    fn record(&self, event: AuditEvent) {
+        self.inner.record(event.clone());
+        // Fails only if there are no subscribers
+        let _ = self.sender.send(event);
+    }
+
+    fn subscribe(&self) -> Option<broadcast::Receiver<AuditEvent>> {
+        Some(self.sender.subscribe())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+
+    #[test]
+    fn broadcast_sink_publishes_recorded_events() {
+        let sink = BroadcastAuditSink::new(CollectingAuditSink::new());
+        let mut receiver = sink.subscribe().unwrap();
+
+        let event = AuditEvent::new(
+            &"namespace",
+            &ApiDefinitionId("test".to_string()),
+            &ApiVersion("1.0.0".to_string()),
+            AuditAction::Create,
+            &"subject",
+        );
+        sink.record(event.clone());
+
+        assert_eq!(sink.inner.events(), vec![event.clone()]);
+        assert_eq!(receiver.try_recv().unwrap(), event);
+    }
+}
@@ -20,7 +20,7 @@ use golem_service_base::model::{Component, VersionedComponentId};
 use serde::{Deserialize, Serialize};
 
 use crate::gateway_api_definition::http::{HttpApiDefinition, MethodPattern, Route};
-use crate::gateway_execution::router::{Router, RouterPattern};
+use crate::gateway_execution::router::{paths_are_ambiguous, RouterPattern};
 use crate::service::gateway::api_definition_validator::{
     ApiDefinitionValidatorService, ValidationErrors,
 };
@@ -65,7 +65,8 @@ impl ApiDefinitionValidatorService<HttpApiDefinition> for HttpApiDefinitionValid
         api: &HttpApiDefinition,
         _components: &[Component],
     ) -> Result<(), ValidationErrors> {
-        let errors = unique_routes(api.routes.as_slice());
+        let mut errors = unique_routes(api.routes.as_slice());
+        errors.extend(invalid_param_mappings(api.routes.as_slice()));
         let errors_string = errors.iter().map(|x| x.to_string()).collect::<Vec<_>>();
 
         if errors_string.is_empty() {
@@ -78,24 +79,34 @@ impl ApiDefinitionValidatorService<HttpApiDefinition> for HttpApiDefinitionValid
     }
 }
 
+// Routes matching the same requests must differ in precedence (see `RoutePrecedence`),
+// otherwise there is no way to decide which of them serves a request
 fn unique_routes(routes: &[Route]) -> Vec<RouteValidationError> {
-    let mut router = Router::<&Route>::new();
+    let paths: Vec<Vec<RouterPattern>> = routes
+        .iter()
+        .map(|route| {
+            route
+                .path
+                .path_patterns
+                .clone()
+                .into_iter()
+                .map(|p| p.into())
+                .collect()
+        })
+        .collect();
 
     let mut errors = vec![];
 
-    for route in routes {
-        let method: hyper::Method = route.method.clone().into();
-        let path: Vec<RouterPattern> = route
-            .path
-            .path_patterns
-            .clone()
-            .into_iter()
-            .map(|p| p.into())
-            .collect();
-
-        if !router.add_route(method.clone(), path.clone(), route) {
-            let current_route = router.get_route(&method, &path).unwrap();
+    for (index, (route, path)) in routes.iter().zip(&paths).enumerate() {
+        let ambiguous_with = routes
+            .iter()
+            .zip(&paths)
+            .take(index)
+            .find(|(other, other_path)| {
+                other.method == route.method && paths_are_ambiguous(other_path, path)
+            });
 
+        if let Some((current_route, _)) = ambiguous_with {
             let detail = format!("Duplicate route with path: {}", current_route.path);
 
             errors.push(RouteValidationError {
@@ -110,36 +121,65 @@ fn unique_routes(routes: &[Route]) -> Vec<RouteValidationError> {
     errors
 }
 
+// Parameter mappings can only refer to the variables and query parameters of their own route
+fn invalid_param_mappings(routes: &[Route]) -> Vec<RouteValidationError> {
+    let mut errors = vec![];
+
+    for route in routes {
+        if let Some(worker_binding) = route.binding.get_worker_binding() {
+            for (name, mapping) in &worker_binding.param_mappings {
+                if let Err(err) = mapping.validate_for_route(&route.path) {
+                    errors.push(RouteValidationError {
+                        method: route.method.clone(),
+                        path: route.path.to_string(),
+                        component: Some(worker_binding.component_id.clone()),
+                        detail: format!("Invalid mapping of parameter {}: {}", name, err),
+                    });
+                }
+            }
+        }
+    }
+
+    errors
+}
+
 #[cfg(test)]
 mod tests {
     use test_r::test;
 
     use crate::gateway_api_definition::http::{MethodPattern, Route};
     use crate::gateway_binding::{GatewayBinding, ResponseMapping};
-    use crate::service::gateway::http_api_definition_validator::unique_routes;
+    use crate::gateway_binding::ParamMapping;
+    use crate::service::gateway::http_api_definition_validator::{
+        invalid_param_mappings, unique_routes,
+    };
     use golem_common::model::ComponentId;
     use golem_service_base::model::VersionedComponentId;
     use rib::Expr;
+    use std::collections::BTreeMap;
+
+    fn make_route(method: MethodPattern, path: &str) -> Route {
+        Route {
+            method,
+            path: crate::gateway_api_definition::http::AllPathPatterns::parse(path).unwrap(),
+            binding: GatewayBinding::Default(crate::gateway_binding::WorkerBinding {
+                component_id: VersionedComponentId {
+                    component_id: ComponentId::new_v4(),
+                    version: 1,
+                },
+                worker_name: Some(Expr::identifier("request")),
+                idempotency_key: None,
+                response_mapping: ResponseMapping(Expr::literal("sample")),
+                timeout_ms: None,
+                component_version: None,
+                param_mappings: BTreeMap::new(),
+            }),
+            middlewares: None,
+        }
+    }
 
     #[test]
     fn test_unique_routes() {
-        fn make_route(method: MethodPattern, path: &str) -> Route {
-            Route {
-                method,
-                path: crate::gateway_api_definition::http::AllPathPatterns::parse(path).unwrap(),
-                binding: GatewayBinding::Default(crate::gateway_binding::WorkerBinding {
-                    component_id: VersionedComponentId {
-                        component_id: ComponentId::new_v4(),
-                        version: 1,
-                    },
-                    worker_name: Some(Expr::identifier("request")),
-                    idempotency_key: None,
-                    response_mapping: ResponseMapping(Expr::literal("sample")),
-                }),
-                middlewares: None,
-            }
-        }
-
         let paths = &[
             "/users/{id}/posts/{post_id}",
             "/users/{id}/posts/{post_id}/comments/{comment_id}",
@@ -169,4 +209,41 @@ mod tests {
         assert!(errors.len() == 1);
         assert!(errors[0].detail.contains(paths[0]), "Received: {errors:?}");
     }
+
+    #[test]
+    fn test_static_segment_is_not_ambiguous_with_variable() {
+        let me = make_route(MethodPattern::Get, "/users/me");
+        let id = make_route(MethodPattern::Get, "/users/{id}");
+        let name = make_route(MethodPattern::Get, "/users/{name}");
+
+        let errors = unique_routes(&[me.clone(), id.clone()]);
+        assert!(errors.is_empty(), "Received: {errors:?}");
+
+        let errors = unique_routes(&[me, id, name]);
+        assert!(errors.len() == 1);
+        assert!(errors[0].detail.contains("/users/{id}"), "Received: {errors:?}");
+    }
+
+    #[test]
+    fn test_param_mappings_refer_to_the_route() {
+        let mut route = make_route(MethodPattern::Get, "/users/{id}?{limit}");
+        let worker_binding = route.binding.get_worker_binding_mut().unwrap();
+        for (name, mapping) in [("id", "path:id"), ("limit", "query:limit"), ("all", "body:")] {
+            worker_binding
+                .param_mappings
+                .insert(name.to_string(), mapping.parse::<ParamMapping>().unwrap());
+        }
+
+        assert!(invalid_param_mappings(&[route.clone()]).is_empty());
+
+        let worker_binding = route.binding.get_worker_binding_mut().unwrap();
+        worker_binding.param_mappings.insert(
+            "user".to_string(),
+            ParamMapping::PathParam("user".to_string()),
+        );
+
+        let errors = invalid_param_mappings(&[route]);
+        assert!(errors.len() == 1);
+        assert!(errors[0].detail.contains("user"), "Received: {errors:?}");
+    }
 }
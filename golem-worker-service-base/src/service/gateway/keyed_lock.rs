@@ -0,0 +1,105 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::OwnedMutexGuard;
+
+// One async mutex per key, so holders of the same key are serialized while different keys
+// proceed concurrently. The locks are only held in memory, so they are not shared between
+// instances. A key's mutex is dropped together with its last guard.
+pub struct KeyedLock<K> {
+    locks: Mutex<HashMap<K, Weak<tokio::sync::Mutex<()>>>>,
+}
+
+impl<K: Eq + Hash> KeyedLock<K> {
+    pub fn new() -> Self {
+        Self {
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn lock(&self, key: K) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            locks.retain(|_, lock| lock.strong_count() > 0);
+
+            match locks.get(&key).and_then(Weak::upgrade) {
+                Some(lock) => lock,
+                None => {
+                    let lock = Arc::new(tokio::sync::Mutex::new(()));
+                    locks.insert(key, Arc::downgrade(&lock));
+                    lock
+                }
+            }
+        };
+
+        lock.lock_owned().await
+    }
+}
+
+impl<K: Eq + Hash> Default for KeyedLock<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+
+    #[test]
+    async fn same_key_is_serialized() {
+        let keyed_lock = Arc::new(KeyedLock::new());
+        let counter = Arc::new(Mutex::new(0));
+
+        let writers = (0..20).map(|_| {
+            let keyed_lock = keyed_lock.clone();
+            let counter = counter.clone();
+            tokio::spawn(async move {
+                let _guard = keyed_lock.lock("key").await;
+                let current = *counter.lock().unwrap();
+                tokio::task::yield_now().await;
+                *counter.lock().unwrap() = current + 1;
+            })
+        });
+
+        for writer in writers.collect::<Vec<_>>() {
+            writer.await.unwrap();
+        }
+
+        assert_eq!(*counter.lock().unwrap(), 20);
+    }
+
+    #[test]
+    async fn different_keys_are_independent() {
+        let keyed_lock = KeyedLock::new();
+
+        let _first = keyed_lock.lock("first").await;
+        let _second = keyed_lock.lock("second").await;
+    }
+
+    #[test]
+    async fn unused_locks_are_dropped() {
+        let keyed_lock = KeyedLock::new();
+
+        drop(keyed_lock.lock("first").await);
+        let _second = keyed_lock.lock("second").await;
+
+        assert_eq!(keyed_lock.locks.lock().unwrap().len(), 1);
+    }
+}
@@ -15,5 +15,9 @@
 pub mod api_definition;
 pub mod api_definition_validator;
 pub mod api_deployment;
+pub mod audit;
 pub mod http_api_definition_validator;
+pub mod keyed_lock;
+pub mod rate_limit;
+pub mod reservation;
 pub mod security_scheme;
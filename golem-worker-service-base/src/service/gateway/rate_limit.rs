@@ -0,0 +1,165 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::app_config::RateLimitConfig;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[async_trait]
+pub trait RateLimiter {
+    // Takes a token for the given key, or returns how long to wait until one is available
+    async fn try_acquire(&self, key: &str) -> Result<(), Duration>;
+}
+
+pub struct UnlimitedRateLimiter;
+
+#[async_trait]
+impl RateLimiter for UnlimitedRateLimiter {
+    async fn try_acquire(&self, _key: &str) -> Result<(), Duration> {
+        Ok(())
+    }
+}
+
+// Keeps one token bucket per key in memory, so the limits are not shared between instances
+pub struct InMemoryRateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl InMemoryRateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn try_acquire_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key.to_string()).or_insert(TokenBucket {
+            tokens: self.config.burst as f64,
+            last_refill: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.config.rate_per_second)
+            .min(self.config.burst as f64);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if self.config.rate_per_second > 0.0 {
+            let wait = (1.0 - bucket.tokens) / self.config.rate_per_second;
+            Err(Duration::try_from_secs_f64(wait).unwrap_or(Duration::MAX))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimiter for InMemoryRateLimiter {
+    async fn try_acquire(&self, key: &str) -> Result<(), Duration> {
+        self.try_acquire_at(key, Instant::now())
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+
+    fn limiter() -> InMemoryRateLimiter {
+        InMemoryRateLimiter::new(RateLimitConfig {
+            rate_per_second: 2.0,
+            burst: 3,
+        })
+    }
+
+    #[test]
+    fn burst_is_allowed_then_limited() {
+        let limiter = limiter();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at("ns", now).is_ok());
+        }
+
+        assert_eq!(
+            limiter.try_acquire_at("ns", now),
+            Err(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn tokens_are_refilled_over_time() {
+        let limiter = limiter();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at("ns", now).is_ok());
+        }
+
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.try_acquire_at("ns", later).is_ok());
+        assert!(limiter.try_acquire_at("ns", later).is_err());
+    }
+
+    #[test]
+    fn keys_are_limited_independently() {
+        let limiter = limiter();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at("first", now).is_ok());
+        }
+
+        assert!(limiter.try_acquire_at("first", now).is_err());
+        assert!(limiter.try_acquire_at("second", now).is_ok());
+    }
+
+    #[test]
+    fn tiny_rates_wait_at_most_the_longest_duration() {
+        for rate_per_second in [0.0, f64::MIN_POSITIVE] {
+            let limiter = InMemoryRateLimiter::new(RateLimitConfig {
+                rate_per_second,
+                burst: 1,
+            });
+            let now = Instant::now();
+
+            assert!(limiter.try_acquire_at("ns", now).is_ok());
+            assert_eq!(limiter.try_acquire_at("ns", now), Err(Duration::MAX));
+        }
+    }
+
+    #[test]
+    fn invalid_rates_are_rejected() {
+        let config =
+            serde_json::from_str::<RateLimitConfig>(r#"{ "rate_per_second": -1.0, "burst": 1 }"#);
+        assert!(config.is_err());
+
+        let config =
+            serde_json::from_str::<RateLimitConfig>(r#"{ "rate_per_second": 0.5, "burst": 1 }"#);
+        assert!(config.is_ok());
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2024-2025 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReservationToken(pub Uuid);
+
+impl ReservationToken {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for ReservationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for ReservationToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// Reservations of keys which expire after a TTL unless released before. Only the holder of a
+// reservation's token can use the reserved key. The reservations are only held in memory, so
+// they are not shared between instances.
+pub struct ReservationStore<K> {
+    reservations: Mutex<HashMap<K, Reservation>>,
+}
+
+struct Reservation {
+    token: ReservationToken,
+    expires_at: Instant,
+}
+
+impl<K: Eq + Hash + Clone> ReservationStore<K> {
+    pub fn new() -> Self {
+        Self {
+            reservations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Reserves the key for `ttl`, or returns None if it is already reserved
+    pub fn reserve(&self, key: K, ttl: Duration) -> Option<ReservationToken> {
+        self.reserve_at(key, ttl, Instant::now())
+    }
+
+    // True if the key is reserved with another token than the given one
+    pub fn is_reserved_by_other(&self, key: &K, token: Option<&ReservationToken>) -> bool {
+        self.is_reserved_by_other_at(key, token, Instant::now())
+    }
+
+    // The key reserved with the token, if the reservation has not expired
+    pub fn find(&self, token: &ReservationToken) -> Option<K> {
+        self.find_at(token, Instant::now())
+    }
+
+    pub fn release(&self, token: &ReservationToken) {
+        let mut reservations = self.reservations.lock().unwrap();
+        reservations.retain(|_, reservation| reservation.token != *token);
+    }
+
+    fn reserve_at(&self, key: K, ttl: Duration, now: Instant) -> Option<ReservationToken> {
+        let mut reservations = self.live_reservations(now);
+        if reservations.contains_key(&key) {
+            return None;
+        }
+
+        let token = ReservationToken::new();
+        reservations.insert(
+            key,
+            Reservation {
+                token,
+                expires_at: now + ttl,
+            },
+        );
+        Some(token)
+    }
+
+    fn is_reserved_by_other_at(
+        &self,
+        key: &K,
+        token: Option<&ReservationToken>,
+        now: Instant,
+    ) -> bool {
+        self.live_reservations(now)
+            .get(key)
+            .is_some_and(|reservation| Some(&reservation.token) != token)
+    }
+
+    fn find_at(&self, token: &ReservationToken, now: Instant) -> Option<K> {
+        self.live_reservations(now)
+            .iter()
+            .find(|(_, reservation)| reservation.token == *token)
+            .map(|(key, _)| key.clone())
+    }
+
+    // Locks the reservations, dropping the expired ones
+    fn live_reservations(&self, now: Instant) -> MutexGuard<'_, HashMap<K, Reservation>> {
+        let mut reservations = self.reservations.lock().unwrap();
+        reservations.retain(|_, reservation| reservation.expires_at > now);
+        reservations
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for ReservationStore<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn key_can_only_be_reserved_once() {
+        let store = ReservationStore::new();
+        let now = Instant::now();
+
+        let token = store.reserve_at("api@1.0", TTL, now).unwrap();
+
+        assert!(store.reserve_at("api@1.0", TTL, now).is_none());
+        assert!(store.reserve_at("api@2.0", TTL, now).is_some());
+        assert_eq!(store.find_at(&token, now), Some("api@1.0"));
+    }
+
+    #[test]
+    fn only_the_holder_can_use_the_key() {
+        let store = ReservationStore::new();
+        let now = Instant::now();
+
+        let token = store.reserve_at("api@1.0", TTL, now).unwrap();
+
+        assert!(!store.is_reserved_by_other_at(&"api@1.0", Some(&token), now));
+        assert!(store.is_reserved_by_other_at(&"api@1.0", None, now));
+        assert!(store.is_reserved_by_other_at(&"api@1.0", Some(&ReservationToken::new()), now));
+        assert!(!store.is_reserved_by_other_at(&"api@2.0", None, now));
+    }
+
+    #[test]
+    fn reservations_expire() {
+        let store = ReservationStore::new();
+        let now = Instant::now();
+
+        let token = store.reserve_at("api@1.0", TTL, now).unwrap();
+
+        let later = now + TTL;
+        assert_eq!(store.find_at(&token, later), None);
+        assert!(!store.is_reserved_by_other_at(&"api@1.0", None, later));
+        assert!(store.reserve_at("api@1.0", TTL, later).is_some());
+    }
+
+    #[test]
+    fn released_keys_can_be_reserved_again() {
+        let store = ReservationStore::new();
+        let now = Instant::now();
+
+        let token = store.reserve_at("api@1.0", TTL, now).unwrap();
+        store.release(&token);
+
+        assert_eq!(store.find_at(&token, now), None);
+        assert!(store.reserve_at("api@1.0", TTL, now).is_some());
+    }
+}
@@ -478,6 +478,7 @@ async fn test_services(
         api_deployment_repo.clone(),
         security_scheme_service.clone(),
         api_definition_validator_service.clone(),
+        None,
     ));
 
     let deployment_service: Arc<
@@ -957,7 +958,7 @@ async fn test_definition_crud(
                 &EmptyAuthCtx::default(),
             )
             .await
-            .is_ok(),
+            .is_ok_and(|deleted| deleted),
         "Failed to delete definition"
     );
     assert!(
@@ -969,7 +970,7 @@ async fn test_definition_crud(
                 &EmptyAuthCtx::default(),
             )
             .await
-            .is_ok(),
+            .is_ok_and(|deleted| deleted),
         "Failed to delete definition"
     );
 
@@ -996,7 +997,7 @@ async fn test_delete_non_existing(
         )
         .await;
 
-    assert!(delete_result.is_err(), "definition should not exist");
+    assert!(matches!(delete_result, Ok(false)), "definition should not exist");
 }
 
 fn get_api_deployment(
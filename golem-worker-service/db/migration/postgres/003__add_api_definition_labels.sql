@@ -0,0 +1,2 @@
+ALTER TABLE api_definitions
+    ADD COLUMN IF NOT EXISTS labels text NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+ALTER TABLE api_definitions
+    ADD COLUMN labels text NOT NULL DEFAULT '{}';
@@ -1,24 +1,63 @@
+use futures::stream::BoxStream;
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
 use golem_common::json_yaml::JsonOrYaml;
-use golem_common::{recorded_http_api_request, safe};
+#[cfg(feature = "metrics")]
+use golem_common::metrics::api::TraceErrorKind;
+use golem_common::model::{ComponentId, RetryConfig};
+use golem_common::retries::RetryState;
+use golem_common::{recorded_http_api_request, safe, SafeDisplay};
 use golem_service_base::api_tags::ApiTags;
 use golem_service_base::auth::{DefaultNamespace, EmptyAuthCtx};
-use golem_worker_service_base::api::ApiEndpointError;
-use golem_worker_service_base::api::HttpApiDefinitionRequest;
-use golem_worker_service_base::api::HttpApiDefinitionResponseData;
-use golem_worker_service_base::gateway_api_definition::http::CompiledHttpApiDefinition;
-use golem_worker_service_base::gateway_api_definition::http::HttpApiDefinitionRequest as CoreHttpApiDefinitionRequest;
-use golem_worker_service_base::gateway_api_definition::http::OpenApiHttpApiDefinitionRequest;
+use golem_worker_service_base::api::{
+    api_definition_json_schema, to_open_api_document, ApiDefinitionInfo,
+    ApiDefinitionReservationResponse, ApiDefinitionSnapshot, ApiDefinitionSummary,
+    ApiDefinitionWatchEvent, ApiEndpointError, CustomHttpRequestApi, HttpApiDefinitionRequest,
+    HttpApiDefinitionResponseData, OpenApiUrlImportRequest, RouteTestRequest, RouteTestResponse,
+    RoutingTableEntry,
+};
+use golem_worker_service_base::gateway_api_definition::http::{
+    AllPathPatterns, CompiledHttpApiDefinition, HttpApiDefinition,
+    HttpApiDefinitionRequest as CoreHttpApiDefinitionRequest, MethodPattern,
+    OpenApiHttpApiDefinitionRequest,
+};
 use golem_worker_service_base::gateway_api_definition::{ApiDefinitionId, ApiVersion};
-use golem_worker_service_base::service::gateway::api_definition::ApiDefinitionService;
-use poem_openapi::param::{Path, Query};
-use poem_openapi::payload::Json;
+use golem_worker_service_base::gateway_execution::api_definition_lookup::SingleApiDefinitionLookup;
+use golem_worker_service_base::gateway_execution::file_server_binding_handler::FileServerBindingHandler;
+use golem_worker_service_base::gateway_execution::gateway_session::GatewaySession;
+use golem_worker_service_base::gateway_execution::GatewayWorkerRequestExecutor;
+#[cfg(feature = "metrics")]
+use golem_worker_service_base::metrics;
+use golem_worker_service_base::service::gateway::api_definition::{
+    ApiDefinitionError, ApiDefinitionService,
+};
+use golem_worker_service_base::service::gateway::audit::{AuditAction, AuditEvent, AuditSink};
+use golem_worker_service_base::service::gateway::rate_limit::RateLimiter;
+use golem_worker_service_base::service::gateway::reservation::ReservationToken;
+use poem::Body;
+use poem_openapi::param::{Header, Path, Query};
+use poem_openapi::payload::{Binary, EventStream, Json};
 use poem_openapi::*;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::io;
 use std::result::Result;
 use std::sync::Arc;
-use tracing::{error, Instrument};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::{error, warn, Instrument};
+use url::Url;
+use uuid::Uuid;
 
 pub struct RegisterApiDefinitionApi {
     definition_service: Arc<dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace> + Sync + Send>,
+    audit_sink: Arc<dyn AuditSink + Sync + Send>,
+    registration_rate_limiter: Arc<dyn RateLimiter + Sync + Send>,
+    registration_retries: Option<RetryConfig>,
+    worker_request_executor: Arc<dyn GatewayWorkerRequestExecutor<DefaultNamespace> + Sync + Send>,
+    fileserver_binding_handler: Arc<dyn FileServerBindingHandler<DefaultNamespace> + Sync + Send>,
+    gateway_session_store: Arc<dyn GatewaySession + Sync + Send>,
+    namespace: DefaultNamespace,
+    allow_insecure_open_api_urls: bool,
 }
 
 #[OpenApi(prefix_path = "/v1/api/definitions", tag = ApiTags::ApiDefinition)]
@@ -27,8 +66,40 @@ impl RegisterApiDefinitionApi {
         definition_service: Arc<
             dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace> + Sync + Send,
         >,
+        audit_sink: Arc<dyn AuditSink + Sync + Send>,
+        registration_rate_limiter: Arc<dyn RateLimiter + Sync + Send>,
+        registration_retries: Option<RetryConfig>,
+        worker_request_executor: Arc<
+            dyn GatewayWorkerRequestExecutor<DefaultNamespace> + Sync + Send,
+        >,
+        fileserver_binding_handler: Arc<
+            dyn FileServerBindingHandler<DefaultNamespace> + Sync + Send,
+        >,
+        gateway_session_store: Arc<dyn GatewaySession + Sync + Send>,
     ) -> Self {
-        Self { definition_service }
+        Self {
+            definition_service,
+            audit_sink,
+            registration_rate_limiter,
+            registration_retries,
+            worker_request_executor,
+            fileserver_binding_handler,
+            gateway_session_store,
+            namespace: DefaultNamespace::default(),
+            allow_insecure_open_api_urls: false,
+        }
+    }
+
+    /// Sets the namespace all the requests are served in, which is `DefaultNamespace` otherwise
+    pub fn with_namespace(mut self, namespace: DefaultNamespace) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    /// Allows importing OpenAPI documents from plain HTTP URLs, not only from HTTPS ones
+    pub fn with_insecure_open_api_urls(mut self, allow: bool) -> Self {
+        self.allow_insecure_open_api_urls = allow;
+        self
     }
 
     /// Upload an OpenAPI definition
@@ -39,26 +110,62 @@ impl RegisterApiDefinitionApi {
     async fn create_or_update_open_api(
         &self,
         payload: JsonOrYaml<OpenApiHttpApiDefinitionRequest>,
-    ) -> Result<Json<HttpApiDefinitionResponseData>, ApiEndpointError> {
+        /// Accept a version which is not a valid semantic version
+        #[oai(name = "relaxed_version")]
+        relaxed_version: Query<Option<bool>>,
+    ) -> Result<RegisterDefinitionResponse, ApiEndpointError> {
         let record = recorded_http_api_request!("import_open_api",);
 
         let response = {
             let definition = payload.0.to_http_api_definition_request().map_err(|e| {
                 error!("Invalid Spec {}", e);
-                ApiEndpointError::bad_request(safe(e))
+                ApiEndpointError::bad_request("api_definition.invalid_open_api", safe(e))
             })?;
+            validate_version(&definition.version, relaxed_version.0.unwrap_or(false))?;
+
+            RegisterDefinitionResponse::timed(
+                self.create_api(&definition, CreateMode::Unconditional)
+                    .instrument(record.span.clone()),
+            )
+            .await
+        };
+
+        record.result(response)
+    }
+
+    /// Import an OpenAPI definition from a URL
+    ///
+    /// Fetches an OpenAPI JSON or YAML document and creates a new Golem API definition using it.
+    /// Only HTTPS URLs are accepted, unless plain HTTP is allowed in the configuration.
+    #[oai(
+        path = "/oas/from-url",
+        method = "post",
+        operation_id = "import_open_api_from_url"
+    )]
+    async fn import_open_api_from_url(
+        &self,
+        payload: Json<OpenApiUrlImportRequest>,
+        /// Accept a version which is not a valid semantic version
+        #[oai(name = "relaxed_version")]
+        relaxed_version: Query<Option<bool>>,
+    ) -> Result<RegisterDefinitionResponse, ApiEndpointError> {
+        let record = recorded_http_api_request!("import_open_api_from_url", url = payload.0.url);
 
-            let result = self
-                .create_api(&definition)
+        let response = {
+            let open_api = fetch_open_api(&payload.0.url, self.allow_insecure_open_api_urls)
                 .instrument(record.span.clone())
                 .await?;
+            let definition = open_api.to_http_api_definition_request().map_err(|e| {
+                error!("Invalid Spec {}", e);
+                ApiEndpointError::bad_request("api_definition.invalid_open_api", safe(e))
+            })?;
+            validate_version(&definition.version, relaxed_version.0.unwrap_or(false))?;
 
-            let result = HttpApiDefinitionResponseData::try_from(result).map_err(|e| {
-                error!("Failed to convert to response data {}", e);
-                ApiEndpointError::internal(safe(e))
-            });
-
-            result.map(Json)
+            RegisterDefinitionResponse::timed(
+                self.create_api(&definition, CreateMode::Unconditional)
+                    .instrument(record.span.clone()),
+            )
+            .await
         };
 
         record.result(response)
@@ -72,7 +179,16 @@ impl RegisterApiDefinitionApi {
     async fn create(
         &self,
         payload: JsonOrYaml<HttpApiDefinitionRequest>,
-    ) -> Result<Json<HttpApiDefinitionResponseData>, ApiEndpointError> {
+        /// Accept a version which is not a valid semantic version
+        #[oai(name = "relaxed_version")]
+        relaxed_version: Query<Option<bool>>,
+        /// Keep the first of the routes with the same method and path, instead of rejecting them
+        #[oai(name = "allow_duplicate_routes")]
+        allow_duplicate_routes: Query<Option<bool>>,
+        /// Only create the API definition if no version of it exists yet
+        #[oai(name = "if_version_absent")]
+        if_version_absent: Query<Option<bool>>,
+    ) -> Result<RegisterDefinitionResponse, ApiEndpointError> {
         let record = recorded_http_api_request!(
             "create_definition",
             api_definition_id = payload.0.id.to_string(),
@@ -81,23 +197,132 @@ impl RegisterApiDefinitionApi {
         );
 
         let response = {
-            let definition: CoreHttpApiDefinitionRequest = payload
-                .0
-                .try_into()
-                .map_err(|err| ApiEndpointError::bad_request(safe(err)))?;
+            let definition = to_core_request(payload.0, allow_duplicate_routes.0)?;
+            validate_version(&definition.version, relaxed_version.0.unwrap_or(false))?;
+
+            let mode = if if_version_absent.0.unwrap_or(false) {
+                CreateMode::IfVersionAbsent
+            } else {
+                CreateMode::Unconditional
+            };
 
-            let compiled_definition = self
-                .create_api(&definition)
+            RegisterDefinitionResponse::timed(
+                self.create_api(&definition, mode)
+                    .instrument(record.span.clone()),
+            )
+            .await
+        };
+
+        record.result(response)
+    }
+
+    /// Create the next version of an API definition
+    ///
+    /// Creates the API definition under the next minor version after its latest registered
+    /// version, or under `1.0.0` if no version of it exists yet. The version of the payload is
+    /// ignored. The assigned version is part of the response. Fails with 409 if another version
+    /// was registered concurrently, in which case the request can be retried.
+    #[oai(path = "/next", method = "post", operation_id = "create_next_definition")]
+    async fn create_next(
+        &self,
+        payload: JsonOrYaml<HttpApiDefinitionRequest>,
+        /// Keep the first of the routes with the same method and path, instead of rejecting them
+        #[oai(name = "allow_duplicate_routes")]
+        allow_duplicate_routes: Query<Option<bool>>,
+    ) -> Result<RegisterDefinitionResponse, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "create_next_definition",
+            api_definition_id = payload.0.id.to_string(),
+            draft = payload.0.draft.to_string()
+        );
+
+        let response = {
+            let mut payload = payload.0;
+            payload.version = self
+                .next_version(&payload.id)
                 .instrument(record.span.clone())
                 .await?;
 
-            let result =
-                HttpApiDefinitionResponseData::try_from(compiled_definition).map_err(|e| {
-                    error!("Failed to convert to response data {}", e);
-                    ApiEndpointError::internal(safe(e))
-                });
+            let definition = to_core_request(payload, allow_duplicate_routes.0)?;
+
+            RegisterDefinitionResponse::timed(
+                self.create_api(&definition, CreateMode::Unconditional)
+                    .instrument(record.span.clone()),
+            )
+            .await
+        };
+
+        record.result(response)
+    }
+
+    /// Reserve a version of an API definition
+    ///
+    /// Reserves the version for a limited time, during which it can only be created by committing
+    /// the reservation with the returned token. Fails with 409 if the version already exists or
+    /// is reserved. Reservations which are not committed expire.
+    #[oai(path = "/reserve", method = "post", operation_id = "reserve_definition")]
+    async fn reserve(
+        &self,
+        payload: Json<ApiDefinitionInfo>,
+        /// Accept a version which is not a valid semantic version
+        #[oai(name = "relaxed_version")]
+        relaxed_version: Query<Option<bool>>,
+    ) -> Result<Json<ApiDefinitionReservationResponse>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "reserve_definition",
+            api_definition_id = payload.0.id.to_string(),
+            version = payload.0.version.to_string()
+        );
+
+        let response = {
+            validate_version(&payload.0.version, relaxed_version.0.unwrap_or(false))?;
+
+            self.definition_service
+                .reserve(
+                    &payload.0.id,
+                    &payload.0.version,
+                    &self.namespace,
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await
+                .map(|reservation| Json(reservation.into()))
+                .map_err(ApiEndpointError::from)
+        };
+
+        record.result(response)
+    }
+
+    /// Commit a reservation of an API definition version
+    ///
+    /// Creates the API definition version reserved with the token, and releases the reservation.
+    /// The API definition must have the reserved id and version. Fails with 404 if the
+    /// reservation does not exist or expired.
+    #[oai(path = "/commit", method = "put", operation_id = "commit_definition")]
+    async fn commit(
+        &self,
+        payload: JsonOrYaml<HttpApiDefinitionRequest>,
+        /// Token of the reservation
+        token: Query<Uuid>,
+        /// Keep the first of the routes with the same method and path, instead of rejecting them
+        #[oai(name = "allow_duplicate_routes")]
+        allow_duplicate_routes: Query<Option<bool>>,
+    ) -> Result<RegisterDefinitionResponse, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "commit_definition",
+            api_definition_id = payload.0.id.to_string(),
+            version = payload.0.version.to_string(),
+            draft = payload.0.draft.to_string()
+        );
+
+        let response = {
+            let definition = to_core_request(payload.0, allow_duplicate_routes.0)?;
 
-            result.map(Json)
+            RegisterDefinitionResponse::timed(
+                self.create_api(&definition, CreateMode::Reserved(ReservationToken(token.0)))
+                    .instrument(record.span.clone()),
+            )
+            .await
         };
 
         record.result(response)
@@ -106,6 +331,8 @@ impl RegisterApiDefinitionApi {
     /// Update an existing API definition.
     ///
     /// Only draft API definitions can be updated.
+    /// If the `If-Match` header is set, the update is only performed if it matches the `ETag`
+    /// of the current API definition.
     #[oai(
         path = "/:id/:version",
         method = "put",
@@ -116,7 +343,14 @@ impl RegisterApiDefinitionApi {
         id: Path<ApiDefinitionId>,
         version: Path<ApiVersion>,
         payload: JsonOrYaml<HttpApiDefinitionRequest>,
-    ) -> Result<Json<HttpApiDefinitionResponseData>, ApiEndpointError> {
+        /// Accept a version which is not a valid semantic version
+        #[oai(name = "relaxed_version")]
+        relaxed_version: Query<Option<bool>>,
+        /// Keep the first of the routes with the same method and path, instead of rejecting them
+        #[oai(name = "allow_duplicate_routes")]
+        allow_duplicate_routes: Query<Option<bool>>,
+        #[oai(name = "If-Match")] if_match: Header<Option<String>>,
+    ) -> Result<RegisterDefinitionResponse, ApiEndpointError> {
         let record = recorded_http_api_request!(
             "update_definition",
             api_definition_id = id.0.to_string(),
@@ -125,37 +359,41 @@ impl RegisterApiDefinitionApi {
         );
 
         let response = {
-            let definition: CoreHttpApiDefinitionRequest = payload
-                .0
-                .try_into()
-                .map_err(|err| ApiEndpointError::bad_request(safe(err)))?;
+            let definition = to_core_request(payload.0, allow_duplicate_routes.0)?;
+            validate_version(&definition.version, relaxed_version.0.unwrap_or(false))?;
 
             if id.0 != definition.id {
-                Err(ApiEndpointError::bad_request(safe(
-                    "Unmatched url and body ids.".to_string(),
-                )))
+                Err(ApiEndpointError::bad_request(
+                    "api_definition.id_mismatch",
+                    safe("Unmatched url and body ids.".to_string()),
+                ))
             } else if version.0 != definition.version {
-                Err(ApiEndpointError::bad_request(safe(
-                    "Unmatched url and body versions.".to_string(),
-                )))
+                Err(ApiEndpointError::bad_request(
+                    "api_definition.version_mismatch",
+                    safe("Unmatched url and body versions.".to_string()),
+                ))
             } else {
-                let compiled_definition = self
-                    .definition_service
-                    .update(
-                        &definition,
-                        &DefaultNamespace::default(),
-                        &EmptyAuthCtx::default(),
-                    )
-                    .instrument(record.span.clone())
-                    .await?;
-
-                let result =
-                    HttpApiDefinitionResponseData::try_from(compiled_definition).map_err(|e| {
-                        error!("Failed to convert to response data {}", e);
-                        ApiEndpointError::internal(safe(e))
-                    });
-
-                result.map(Json)
+                let if_match = if_match.0.as_deref().and_then(parse_if_match);
+
+                RegisterDefinitionResponse::timed(async {
+                    self.limit_registration().await?;
+                    let compiled_definition = self
+                        .definition_service
+                        .update(
+                            &definition,
+                            if_match.as_deref(),
+                            &self.namespace,
+                            &EmptyAuthCtx::default(),
+                        )
+                        .instrument(record.span.clone())
+                        .await
+                        .map_err(ApiEndpointError::from)
+                        .inspect_err(record_register_error)?;
+
+                    self.audit(&definition.id, &definition.version, AuditAction::Update);
+                    Ok(compiled_definition)
+                })
+                .await
             }
         };
 
@@ -165,6 +403,9 @@ impl RegisterApiDefinitionApi {
     /// Get an API definition
     ///
     /// An API definition is selected by its API definition ID and version.
+    /// The version `latest` selects the highest registered version.
+    /// The `ETag` response header identifies the content of the API definition.
+    /// If the `If-None-Match` header matches it, 304 is returned without a body.
     #[oai(
         path = "/:id/:version",
         method = "get",
@@ -174,7 +415,8 @@ impl RegisterApiDefinitionApi {
         &self,
         id: Path<ApiDefinitionId>,
         version: Path<ApiVersion>,
-    ) -> Result<Json<HttpApiDefinitionResponseData>, ApiEndpointError> {
+        #[oai(name = "If-None-Match")] if_none_match: Header<Option<String>>,
+    ) -> Result<GetDefinitionResponse, ApiEndpointError> {
         let record = recorded_http_api_request!(
             "get_definition",
             api_definition_id = id.0.to_string(),
@@ -184,30 +426,49 @@ impl RegisterApiDefinitionApi {
         let response = {
             let api_definition_id = id.0;
 
-            let api_version = version.0;
+            let api_version = self
+                .resolve_version(&api_definition_id, version.0)
+                .instrument(record.span.clone())
+                .await?;
 
             let data = self
                 .definition_service
                 .get(
                     &api_definition_id,
                     &api_version,
-                    &DefaultNamespace::default(),
+                    &self.namespace,
                     &EmptyAuthCtx::default(),
                 )
                 .instrument(record.span.clone())
                 .await?;
 
-            let compiled_definition = data.ok_or(ApiEndpointError::not_found(safe(format!(
-                "Can't find api definition with id {api_definition_id}, and version {api_version}"
-            ))))?;
+            let compiled_definition = data.ok_or(ApiEndpointError::not_found(
+                "api_definition.not_found",
+                safe(format!(
+                    "Can't find api definition with id {api_definition_id}, and version {api_version}"
+                )),
+            ))?;
 
-            let result =
+            let definition =
                 HttpApiDefinitionResponseData::try_from(compiled_definition).map_err(|e| {
                     error!("Failed to convert to response data {}", e);
-                    ApiEndpointError::internal(safe(e))
-                });
+                    ApiEndpointError::internal("api_definition.internal", safe(e))
+                })?;
+            let etag = etag(&definition)?;
+
+            // Weak comparison, as required for If-None-Match
+            let not_modified = if_none_match.0.as_ref().is_some_and(|if_none_match| {
+                if_none_match
+                    .split(',')
+                    .map(|tag| tag.trim().trim_start_matches("W/"))
+                    .any(|tag| tag == "*" || tag == etag)
+            });
 
-            result.map(Json)
+            if not_modified {
+                Ok(GetDefinitionResponse::NotModified(etag))
+            } else {
+                Ok(GetDefinitionResponse::Ok(Json(definition), etag))
+            }
         };
 
         record.result(response)
@@ -216,6 +477,7 @@ impl RegisterApiDefinitionApi {
     /// Delete an API definition
     ///
     /// Deletes an API definition by its API definition ID and version.
+    /// The version `latest` selects the highest registered version.
     #[oai(
         path = "/:id/:version",
         method = "delete",
@@ -225,6 +487,9 @@ impl RegisterApiDefinitionApi {
         &self,
         id: Path<ApiDefinitionId>,
         version: Path<ApiVersion>,
+        /// Succeed without deleting anything if the API definition does not exist
+        #[oai(name = "if_exists")]
+        if_exists: Query<Option<bool>>,
     ) -> Result<Json<String>, ApiEndpointError> {
         let record = recorded_http_api_request!(
             "delete_definition",
@@ -233,297 +498,2446 @@ impl RegisterApiDefinitionApi {
         );
 
         let response = {
-            let api_definition_id = id.0;
-            let api_definition_version = version.0;
+            let deleted = self
+                .delete_version(&id.0, version.0)
+                .instrument(record.span.clone())
+                .await;
 
-            self.definition_service
-                .delete(
+            match deleted {
+                Ok(()) => Ok(Json("API definition deleted".to_string())),
+                Err(ApiEndpointError::NotFound(_)) if if_exists.0.unwrap_or(false) => {
+                    Ok(Json("API definition not found, nothing to delete".to_string()))
+                }
+                Err(error) => Err(error),
+            }
+        };
+        record.result(response)
+    }
+
+    /// Delete all versions of an API definition
+    ///
+    /// Deletes all versions of the API definition with the given ID, and returns their number.
+    /// Nothing is deleted if any of the versions is deployed.
+    #[oai(
+        path = "/by-id",
+        method = "delete",
+        operation_id = "delete_definition_versions"
+    )]
+    async fn delete_all_versions(
+        &self,
+        #[oai(name = "api-definition-id")] api_definition_id: Query<ApiDefinitionId>,
+    ) -> Result<Json<u64>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "delete_definition_versions",
+            api_definition_id = api_definition_id.0.to_string()
+        );
+
+        let response = {
+            let api_definition_id = api_definition_id.0;
+            let deleted_versions = self
+                .definition_service
+                .delete_all_versions(
+                    &api_definition_id,
+                    &self.namespace,
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?;
+
+            for version in &deleted_versions {
+                self.audit(&api_definition_id, version, AuditAction::Delete);
+            }
+            self.refresh_definition_count()
+                .instrument(record.span.clone())
+                .await;
+
+            Ok(Json(deleted_versions.len() as u64))
+        };
+
+        record.result(response)
+    }
+
+    /// Delete a route of an API definition
+    ///
+    /// Removes the route with the given method and path from a draft API definition, and keeps
+    /// all its other routes. The version `latest` selects the highest registered version.
+    #[oai(
+        path = "/route",
+        method = "delete",
+        operation_id = "delete_definition_route"
+    )]
+    async fn delete_route(
+        &self,
+        #[oai(name = "api-definition-id")] api_definition_id: Query<ApiDefinitionId>,
+        version: Query<ApiVersion>,
+        method: Query<MethodPattern>,
+        path: Query<String>,
+    ) -> Result<Json<HttpApiDefinitionResponseData>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "delete_definition_route",
+            api_definition_id = api_definition_id.0.to_string(),
+            version = version.0.to_string(),
+            method = method.0.to_string(),
+            path = path.0.to_string()
+        );
+
+        let response = {
+            let api_definition_id = api_definition_id.0;
+            let api_version = self
+                .resolve_version(&api_definition_id, version.0)
+                .instrument(record.span.clone())
+                .await?;
+            let route_path = AllPathPatterns::parse(&path.0).map_err(|e| {
+                ApiEndpointError::bad_request("api_definition.invalid_path", safe(e))
+            })?;
+
+            let data = self
+                .definition_service
+                .get(
                     &api_definition_id,
-                    &api_definition_version,
-                    &DefaultNamespace::default(),
+                    &api_version,
+                    &self.namespace,
                     &EmptyAuthCtx::default(),
                 )
                 .instrument(record.span.clone())
+                .await?
+                .ok_or_else(|| {
+                    ApiEndpointError::not_found(
+                        "api_definition.not_found",
+                        safe(format!(
+                            "Can't find api definition with id {api_definition_id}, and version {api_version}"
+                        )),
+                    )
+                })?;
+
+            let mut definition = CoreHttpApiDefinitionRequest::from(HttpApiDefinition::from(data));
+            let route_count = definition.routes.len();
+            definition
+                .routes
+                .retain(|route| route.method != method.0 || route.path != route_path);
+
+            if definition.routes.len() == route_count {
+                Err(ApiEndpointError::not_found(
+                    "api_definition.route_not_found",
+                    safe(format!(
+                        "Can't find route {} {} in api definition with id {api_definition_id}, and version {api_version}",
+                        method.0, path.0
+                    )),
+                ))
+            } else {
+                let compiled_definition = self
+                    .definition_service
+                    .update(&definition, None, &self.namespace, &EmptyAuthCtx::default())
+                    .instrument(record.span.clone())
+                    .await
+                    .map_err(ApiEndpointError::from)
+                    .inspect_err(record_register_error)?;
+
+                self.audit(&definition.id, &definition.version, AuditAction::Update);
+
+                HttpApiDefinitionResponseData::try_from(compiled_definition)
+                    .map(Json)
+                    .map_err(|e| {
+                        error!("Failed to convert to response data {}", e);
+                        ApiEndpointError::internal("api_definition.internal", safe(e))
+                    })
+            }
+        };
+
+        record.result(response)
+    }
+
+    /// Test the routes of an API definition
+    ///
+    /// Routes the sample request to the routes of the API definition as if it was deployed, and
+    /// returns the response. Worker bindings invoke their workers, so testing is not free of side
+    /// effects. The version `latest` selects the highest registered version.
+    #[oai(
+        path = "/routes/test",
+        method = "post",
+        operation_id = "test_definition_route"
+    )]
+    async fn test_route(
+        &self,
+        #[oai(name = "api-definition-id")] api_definition_id: Query<ApiDefinitionId>,
+        version: Query<ApiVersion>,
+        payload: Json<RouteTestRequest>,
+    ) -> Result<Json<RouteTestResponse>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "test_definition_route",
+            api_definition_id = api_definition_id.0.to_string(),
+            version = version.0.to_string(),
+            method = payload.0.method.to_string(),
+            path = payload.0.path.to_string()
+        );
+
+        let response = {
+            let api_definition_id = api_definition_id.0;
+            let api_version = self
+                .resolve_version(&api_definition_id, version.0)
+                .instrument(record.span.clone())
                 .await?;
+            let request = payload.0.to_http_request().map_err(|e| {
+                ApiEndpointError::bad_request("api_definition.invalid_test_request", safe(e))
+            })?;
+
+            let definition = self
+                .definition_service
+                .get(
+                    &api_definition_id,
+                    &api_version,
+                    &self.namespace,
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?
+                .ok_or_else(|| {
+                    ApiEndpointError::not_found(
+                        "api_definition.not_found",
+                        safe(format!(
+                            "Can't find api definition with id {api_definition_id}, and version {api_version}"
+                        )),
+                    )
+                })?;
 
-            Ok(Json("API definition deleted".to_string()))
+            let executor = CustomHttpRequestApi::new(
+                self.worker_request_executor.clone(),
+                Arc::new(SingleApiDefinitionLookup::new(definition)),
+                self.fileserver_binding_handler.clone(),
+                self.gateway_session_store.clone(),
+            );
+            let response = executor
+                .execute(request)
+                .instrument(record.span.clone())
+                .await;
+
+            RouteTestResponse::from_http_response(response)
+                .await
+                .map(Json)
+                .map_err(|e| {
+                    error!("Failed to read the response of the tested route {}", e);
+                    ApiEndpointError::internal("api_definition.internal", safe(e))
+                })
         };
+
         record.result(response)
     }
 
     /// Get or list API definitions
     ///
     /// If `api_definition_id` is specified, returns a single API definition.
-    /// Otherwise lists all API definitions.
+    /// Otherwise lists all API definitions, or the ones with an id starting with `id-prefix`.
+    /// With `summary=true` only an overview of each API definition is returned, without the
+    /// routes, as `application/vnd.golem.summary+json`.
+    /// With `Accept: application/x-ndjson` all API definitions are streamed as newline-delimited
+    /// JSON instead, without loading all of them at once.
     #[oai(path = "/", method = "get", operation_id = "list_definitions")]
     async fn list(
         &self,
         #[oai(name = "api-definition-id")] api_definition_id_query: Query<Option<ApiDefinitionId>>,
-    ) -> Result<Json<Vec<HttpApiDefinitionResponseData>>, ApiEndpointError> {
+        /// Only list the API definitions with an id starting with this prefix
+        #[oai(name = "id-prefix")]
+        id_prefix: Query<Option<String>>,
+        /// Return an overview of each API definition instead of the full API definitions
+        summary: Query<Option<bool>>,
+        /// Only list the API definitions with this label, given as `key=value`. Can be repeated,
+        /// in which case the API definitions must have all the given labels.
+        label: Query<Option<Vec<String>>>,
+        request: &poem::Request,
+    ) -> Result<ListDefinitionsResponse, ApiEndpointError> {
         let record = recorded_http_api_request!(
             "list_definitions",
             api_definition_id = api_definition_id_query.0.as_ref().map(|id| id.to_string()),
+            id_prefix = id_prefix.0.clone(),
         );
 
+        let labels = match parse_label_filter(&label.0.unwrap_or_default()) {
+            Ok(labels) => labels,
+            Err(error) => return record.result(Err(error)),
+        };
+
+        let accepts_ndjson = request
+            .header(poem::http::header::ACCEPT)
+            .is_some_and(|accept| accept.contains(NDJSON_CONTENT_TYPE));
+        if accepts_ndjson && api_definition_id_query.0.is_none() {
+            let body = ndjson_stream(
+                self.definition_service.clone(),
+                self.namespace.clone(),
+                id_prefix.0,
+                labels,
+            );
+            return record.result(Ok(ListDefinitionsResponse::Ok(
+                ListDefinitionsContent::Ndjson(Binary(body)),
+            )));
+        }
+
         let response = {
             let data = if let Some(id) = api_definition_id_query.0 {
                 self.definition_service
-                    .get_all_versions(&id, &DefaultNamespace::default(), &EmptyAuthCtx::default())
+                    .get_all_versions(&id, &self.namespace, &EmptyAuthCtx::default())
                     .instrument(record.span.clone())
                     .await?
             } else {
                 self.definition_service
-                    .get_all(&DefaultNamespace::default(), &EmptyAuthCtx::default())
+                    .get_all_filtered(
+                        id_prefix.0.as_deref(),
+                        &self.namespace,
+                        &EmptyAuthCtx::default(),
+                    )
                     .instrument(record.span.clone())
                     .await?
             };
-
-            let values = data
+            let data = data
                 .into_iter()
-                .map(HttpApiDefinitionResponseData::try_from)
-                .collect::<Result<Vec<_>, String>>()
-                .map_err(|e| {
-                    error!("Failed to convert to response data {}", e);
-                    ApiEndpointError::internal(safe(e))
-                })?;
+                .filter(|definition| definition.has_labels(&labels))
+                .collect::<Vec<_>>();
+
+            if summary.0.unwrap_or(false) {
+                let summaries = data.into_iter().map(ApiDefinitionSummary::from).collect();
+                Ok(ListDefinitionsResponse::Ok(ListDefinitionsContent::Summary(Json(summaries))))
+            } else {
+                let values = data
+                    .into_iter()
+                    .map(HttpApiDefinitionResponseData::try_from)
+                    .collect::<Result<Vec<_>, String>>()
+                    .map_err(|e| {
+                        error!("Failed to convert to response data {}", e);
+                        ApiEndpointError::internal("api_definition.internal", safe(e))
+                    })?;
 
-            Ok(Json(values))
+                Ok(ListDefinitionsResponse::Ok(ListDefinitionsContent::Json(Json(values))))
+            }
         };
         record.result(response)
     }
-}
 
-impl RegisterApiDefinitionApi {
-    async fn create_api(
+    /// Check whether an API definition exists
+    ///
+    /// Returns 200 without a body if the API definition exists, 404 otherwise.
+    /// The version `latest` selects the highest registered version.
+    #[oai(path = "/", method = "head", operation_id = "head_definition")]
+    async fn head(
         &self,
-        definition: &CoreHttpApiDefinitionRequest,
-    ) -> Result<CompiledHttpApiDefinition<DefaultNamespace>, ApiEndpointError> {
-        let result = self
-            .definition_service
-            .create(
-                definition,
-                &DefaultNamespace::default(),
-                &EmptyAuthCtx::default(),
-            )
-            .await
-            .map_err(|e| {
-                error!(
-                    "API definition ID: {} - register error: {e:?}",
-                    definition.id
-                );
-                e
-            })?;
+        #[oai(name = "api-definition-id")] api_definition_id: Query<ApiDefinitionId>,
+        version: Query<ApiVersion>,
+    ) -> Result<HeadDefinitionResponse, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "head_definition",
+            api_definition_id = api_definition_id.0.to_string(),
+            version = version.0.to_string()
+        );
 
-        Ok(result)
-    }
-}
+        let response = {
+            let api_definition_id = api_definition_id.0;
+            let api_version = self
+                .resolve_version(&api_definition_id, version.0)
+                .instrument(record.span.clone())
+                .await?;
 
-#[cfg(test)]
-mod test {
-    use golem_service_base::migration::{Migrations, MigrationsDir};
-    use test_r::test;
+            let data = self
+                .definition_service
+                .get(
+                    &api_definition_id,
+                    &api_version,
+                    &self.namespace,
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?;
 
-    use super::*;
-    use crate::service::component::ComponentService;
-    use async_trait::async_trait;
-    use golem_common::config::DbSqliteConfig;
-    use golem_common::model::component_constraint::FunctionConstraintCollection;
-    use golem_common::model::ComponentId;
-    use golem_service_base::db;
-    use golem_service_base::model::Component;
-    use golem_worker_service_base::gateway_security::DefaultIdentityProvider;
-    use golem_worker_service_base::repo::api_definition::{
-        ApiDefinitionRepo, DbApiDefinitionRepo, LoggedApiDefinitionRepo,
-    };
-    use golem_worker_service_base::repo::api_deployment;
-    use golem_worker_service_base::repo::security_scheme::{
-        DbSecuritySchemeRepo, LoggedSecuritySchemeRepo, SecuritySchemeRepo,
-    };
-    use golem_worker_service_base::service::component::ComponentResult;
-    use golem_worker_service_base::service::gateway::api_definition::ApiDefinitionServiceDefault;
-    use golem_worker_service_base::service::gateway::http_api_definition_validator::HttpApiDefinitionValidator;
-    use golem_worker_service_base::service::gateway::security_scheme::DefaultSecuritySchemeService;
-    use http::StatusCode;
-    use poem::test::TestClient;
-    use std::marker::PhantomData;
+            match data {
+                Some(_) => Ok(HeadDefinitionResponse::Found),
+                None => Err(ApiEndpointError::not_found(
+                    "api_definition.not_found",
+                    safe(format!(
+                        "Can't find api definition with id {api_definition_id}, and version {api_version}"
+                    )),
+                )),
+            }
+        };
 
-    struct SqliteDb<'c> {
-        db_path: String,
-        lifetime: PhantomData<&'c ()>,
+        record.result(response)
     }
 
-    impl Default for SqliteDb<'_> {
-        fn default() -> Self {
-            Self {
-                db_path: format!("/tmp/golem-worker-{}.db", uuid::Uuid::new_v4()),
-                lifetime: PhantomData,
+    /// List API definitions using a component
+    ///
+    /// Lists the API definitions with at least one route bound to the given component.
+    #[oai(
+        path = "/by-component",
+        method = "get",
+        operation_id = "list_definitions_by_component"
+    )]
+    async fn list_by_component(
+        &self,
+        #[oai(name = "component-id")] component_id: Query<ComponentId>,
+    ) -> Result<Json<Vec<HttpApiDefinitionResponseData>>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "list_definitions_by_component",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = {
+            let data = self
+                .definition_service
+                .find_by_component(&component_id.0, &self.namespace, &EmptyAuthCtx::default())
+                .instrument(record.span.clone())
+                .await?;
+
+            data.into_iter()
+                .map(HttpApiDefinitionResponseData::try_from)
+                .collect::<Result<Vec<_>, String>>()
+                .map(Json)
+                .map_err(|e| {
+                    error!("Failed to convert to response data {}", e);
+                    ApiEndpointError::internal("api_definition.internal", safe(e))
+                })
+        };
+
+        record.result(response)
+    }
+
+    /// Get the routing table
+    ///
+    /// Returns the routes of the latest version of every API definition, flattened into a single
+    /// list sorted by path and method, along with the API definition each route comes from.
+    /// Routes claimed by more than one API definition with the same method and path are flagged
+    /// as conflicting.
+    #[oai(path = "/routing-table", method = "get", operation_id = "get_routing_table")]
+    async fn routing_table(&self) -> Result<Json<Vec<RoutingTableEntry>>, ApiEndpointError> {
+        let record = recorded_http_api_request!("get_routing_table",);
+
+        let response = {
+            let mut latest: HashMap<ApiDefinitionId, CompiledHttpApiDefinition<DefaultNamespace>> =
+                HashMap::new();
+            let definitions = self
+                .definition_service
+                .get_all(&self.namespace, &EmptyAuthCtx::default())
+                .instrument(record.span.clone())
+                .await?;
+            for definition in definitions {
+                match latest.get(&definition.id) {
+                    Some(current) if current.version.cmp_semver(&definition.version).is_ge() => {}
+                    _ => {
+                        latest.insert(definition.id.clone(), definition);
+                    }
+                }
             }
-        }
+
+            Ok(Json(RoutingTableEntry::from_definitions(
+                latest.into_values().collect(),
+            )))
+        };
+
+        record.result(response)
     }
 
-    impl Drop for SqliteDb<'_> {
-        fn drop(&mut self) {
-            std::fs::remove_file(&self.db_path).unwrap();
+    /// Count API definitions
+    ///
+    /// If `api_definition_id` is specified, counts the versions of that API definition.
+    /// Otherwise counts all API definitions.
+    #[oai(path = "/count", method = "get", operation_id = "count_definitions")]
+    async fn count(
+        &self,
+        #[oai(name = "api-definition-id")] api_definition_id_query: Query<Option<ApiDefinitionId>>,
+    ) -> Result<Json<u64>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "count_definitions",
+            api_definition_id = api_definition_id_query.0.as_ref().map(|id| id.to_string()),
+        );
+
+        let response = self
+            .definition_service
+            .count(
+                api_definition_id_query.0.as_ref(),
+                &self.namespace,
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map(Json)
+            .map_err(|e| e.into());
+
+        record.result(response)
+    }
+
+    /// Export an API definition as an OpenAPI document
+    ///
+    /// Returns an OpenAPI 3.0 document describing the routes of the API definition, with a
+    /// `Content-Disposition` header, to be consumed by client generators such as
+    /// openapi-typescript. The version `latest` selects the highest registered version.
+    #[oai(path = "/oas/client", method = "get", operation_id = "export_open_api_client")]
+    async fn open_api_client(
+        &self,
+        #[oai(name = "api-definition-id")] api_definition_id: Query<ApiDefinitionId>,
+        version: Query<ApiVersion>,
+    ) -> Result<OpenApiClientResponse, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "export_open_api_client",
+            api_definition_id = api_definition_id.0.to_string(),
+            version = version.0.to_string()
+        );
+
+        let response = {
+            let api_definition_id = api_definition_id.0;
+            let api_version = self
+                .resolve_version(&api_definition_id, version.0)
+                .instrument(record.span.clone())
+                .await?;
+
+            let data = self
+                .definition_service
+                .get(
+                    &api_definition_id,
+                    &api_version,
+                    &self.namespace,
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?
+                .ok_or_else(|| {
+                    ApiEndpointError::not_found(
+                        "api_definition.not_found",
+                        safe(format!(
+                            "Can't find api definition with id {api_definition_id}, and version {api_version}"
+                        )),
+                    )
+                })?;
+
+            let document = HttpApiDefinitionResponseData::try_from(data)
+                .and_then(|definition| to_open_api_document(&definition))
+                .map_err(|e| {
+                    error!("Failed to export API definition as OpenAPI {}", e);
+                    ApiEndpointError::internal("api_definition.internal", safe(e))
+                })?;
+
+            Ok(OpenApiClientResponse::Ok(
+                Json(document),
+                format!(
+                    "attachment; filename=\"{}-{}.openapi.json\"",
+                    api_definition_id, api_version
+                ),
+            ))
+        };
+
+        record.result(response)
+    }
+
+    /// Get the JSON Schema of API definitions
+    ///
+    /// Returns the JSON Schema of the API definition documents accepted when creating or updating
+    /// an API definition, for editors and tools validating them before they are uploaded.
+    #[oai(path = "/schema", method = "get", operation_id = "get_definition_schema")]
+    async fn schema(&self) -> Json<serde_json::Value> {
+        Json(api_definition_json_schema())
+    }
+
+    /// Watch the API definitions for changes
+    ///
+    /// Streams server-sent events, starting with a snapshot of all API definitions, followed by
+    /// an event for each API definition created, updated or deleted afterwards. The stream ends
+    /// after at most ten minutes, or when the client falls behind, after which the client is
+    /// expected to reconnect and start over from a new snapshot.
+    #[oai(path = "/watch", method = "get", operation_id = "watch_definitions")]
+    async fn watch(
+        &self,
+    ) -> Result<EventStream<BoxStream<'static, ApiDefinitionWatchEvent>>, ApiEndpointError> {
+        let record = recorded_http_api_request!("watch_definitions",);
+
+        let response = {
+            // Subscribing before taking the snapshot, so no change is missed in between
+            let receiver = self.audit_sink.subscribe().ok_or_else(|| {
+                ApiEndpointError::internal(
+                    "api_definition.watch_unavailable",
+                    safe("Watching API definitions is not supported".to_string()),
+                )
+            })?;
+
+            let definitions = self
+                .definition_service
+                .get_all(&self.namespace, &EmptyAuthCtx::default())
+                .instrument(record.span.clone())
+                .await?
+                .into_iter()
+                .map(|definition| ApiDefinitionInfo {
+                    id: definition.id,
+                    version: definition.version,
+                })
+                .collect();
+
+            let snapshot = ApiDefinitionWatchEvent::Snapshot(ApiDefinitionSnapshot { definitions });
+            let events = stream::once(future::ready(snapshot))
+                .chain(watch_events(receiver, self.namespace.to_string()))
+                .take_until(tokio::time::sleep(WATCH_MAX_DURATION))
+                .boxed();
+
+            Ok(EventStream::new(events).keep_alive(WATCH_KEEP_ALIVE_INTERVAL))
+        };
+
+        record.result(response)
+    }
+
+    /// Check the health of the API definition service
+    ///
+    /// Returns 200 if the API definition service can reach its backing store, 503 otherwise.
+    #[oai(path = "/health", method = "get", operation_id = "definitions_health")]
+    async fn health(&self) -> HealthResponse {
+        match self.definition_service.ping().await {
+            Ok(()) => {
+                HealthResponse::Healthy(Json("API definition service is healthy".to_string()))
+            }
+            Err(error) => {
+                error!("API definition service health check failed: {error}");
+                HealthResponse::Unavailable(Json(error.to_safe_string()))
+            }
         }
     }
+}
 
-    struct TestComponentService;
+#[derive(ApiResponse, Debug, Clone)]
+enum HeadDefinitionResponse {
+    #[oai(status = 200)]
+    Found,
+}
 
-    #[async_trait]
-    impl golem_worker_service_base::service::component::ComponentService<EmptyAuthCtx>
-        for TestComponentService
-    {
-        async fn get_by_version(
-            &self,
-            _component_id: &ComponentId,
-            _version: u64,
-            _auth_ctx: &EmptyAuthCtx,
-        ) -> ComponentResult<Component> {
-            unimplemented!()
+#[derive(ApiResponse, Debug, Clone)]
+enum OpenApiClientResponse {
+    #[oai(status = 200)]
+    Ok(
+        Json<serde_json::Value>,
+        #[oai(header = "Content-Disposition")] String,
+    ),
+}
+
+#[derive(ApiResponse, Debug, Clone)]
+enum HealthResponse {
+    #[oai(status = 200)]
+    Healthy(Json<String>),
+    #[oai(status = 503)]
+    Unavailable(Json<String>),
+}
+
+impl RegisterApiDefinitionApi {
+    // With `if_version_absent`, creating fails if any version of the definition exists
+    // Creations and updates share the registration limit of the namespace
+    async fn limit_registration(&self) -> Result<(), ApiEndpointError> {
+        let namespace = &self.namespace;
+        self.registration_rate_limiter
+            .try_acquire(&namespace.to_string())
+            .await
+            .map_err(|retry_after| {
+                ApiEndpointError::too_many_requests(
+                    "api_definition.rate_limited",
+                    safe(format!("Too many API definition changes in namespace {namespace}")),
+                    retry_after,
+                )
+            })
+            .inspect_err(record_register_error)
+    }
+
+    async fn create_api(
+        &self,
+        definition: &CoreHttpApiDefinitionRequest,
+        mode: CreateMode,
+    ) -> Result<CompiledHttpApiDefinition<DefaultNamespace>, ApiEndpointError> {
+        self.limit_registration().await?;
+
+        let auth_ctx = EmptyAuthCtx::default();
+        let result = retry_internal_errors(&self.registration_retries, || async {
+            match &mode {
+                CreateMode::Unconditional => {
+                    self.definition_service
+                        .create(definition, namespace, &auth_ctx)
+                        .await
+                }
+                CreateMode::IfVersionAbsent => {
+                    self.definition_service
+                        .create_if_absent(definition, namespace, &auth_ctx)
+                        .await
+                }
+                CreateMode::Reserved(token) => {
+                    self.definition_service
+                        .commit(token, definition, namespace, &auth_ctx)
+                        .await
+                }
+            }
+        })
+        .await
+        .map_err(|e| {
+            error!(
+                "API definition ID: {} - register error: {e:?}",
+                definition.id
+            );
+            ApiEndpointError::from(e)
+        })
+        .inspect_err(record_register_error)?;
+
+        self.audit(&definition.id, &definition.version, AuditAction::Create);
+        self.refresh_definition_count().await;
+
+        Ok(result)
+    }
+
+    async fn delete_version(
+        &self,
+        id: &ApiDefinitionId,
+        version: ApiVersion,
+    ) -> Result<(), ApiEndpointError> {
+        let version = self.resolve_version(id, version).await?;
+
+        // Checked by the deletion itself, so a concurrent deletion can't slip in between
+        let deleted = self
+            .definition_service
+            .delete(id, &version, &self.namespace, &EmptyAuthCtx::default())
+            .await?;
+        if !deleted {
+            return Err(ApiEndpointError::not_found(
+                "api_definition.not_found",
+                safe(format!("Can't find api definition with id {id}, and version {version}")),
+            ));
         }
 
-        async fn get_latest(
-            &self,
-            _component_id: &ComponentId,
-            _auth_ctx: &EmptyAuthCtx,
-        ) -> ComponentResult<Component> {
-            unimplemented!()
+        self.audit(id, &version, AuditAction::Delete);
+        self.refresh_definition_count().await;
+
+        Ok(())
+    }
+
+    // Also counts the change in the metrics
+    fn audit(&self, id: &ApiDefinitionId, version: &ApiVersion, action: AuditAction) {
+        #[cfg(feature = "metrics")]
+        metrics::api_definition::record_change(action);
+        self.audit_sink.record(AuditEvent::new(
+            &self.namespace,
+            id,
+            version,
+            action,
+            &EmptyAuthCtx::default(),
+        ));
+    }
+
+    // Failing to count the definitions must not fail the change which triggered it
+    #[cfg(feature = "metrics")]
+    async fn refresh_definition_count(&self) {
+        let count = self
+            .definition_service
+            .count(None, &self.namespace, &EmptyAuthCtx::default())
+            .await;
+
+        match count {
+            Ok(count) => metrics::api_definition::record_definition_count(count),
+            Err(error) => warn!("Failed to count API definitions: {error}"),
         }
+    }
 
-        async fn create_or_update_constraints(
-            &self,
-            _component_id: &ComponentId,
-            _constraints: FunctionConstraintCollection,
-            _auth_ctx: &EmptyAuthCtx,
-        ) -> ComponentResult<FunctionConstraintCollection> {
-            unimplemented!()
+    #[cfg(not(feature = "metrics"))]
+    async fn refresh_definition_count(&self) {}
+
+    // Resolves the `latest` alias to the highest registered version, in semver order
+    async fn resolve_version(
+        &self,
+        id: &ApiDefinitionId,
+        version: ApiVersion,
+    ) -> Result<ApiVersion, ApiEndpointError> {
+        if version.0 != LATEST_VERSION {
+            return Ok(version);
         }
+
+        let versions = self
+            .definition_service
+            .get_all_versions(id, &self.namespace, &EmptyAuthCtx::default())
+            .await?;
+
+        versions
+            .into_iter()
+            .map(|definition| definition.version)
+            .max_by(|a, b| a.cmp_semver(b))
+            .ok_or(ApiEndpointError::not_found(
+                "api_definition.not_found",
+                safe(format!("Can't find any version of api definition with id {id}")),
+            ))
     }
 
-    async fn make_route<'c>() -> (poem::Route, SqliteDb<'c>) {
-        let db = SqliteDb::default();
-        let db_config = DbSqliteConfig {
-            database: db.db_path.to_string(),
-            max_connections: 10,
+    async fn next_version(&self, id: &ApiDefinitionId) -> Result<ApiVersion, ApiEndpointError> {
+        let latest = match self.resolve_version(id, ApiVersion::new(LATEST_VERSION)).await {
+            Ok(latest) => latest,
+            Err(ApiEndpointError::NotFound(_)) => return Ok(ApiVersion::new(FIRST_VERSION)),
+            Err(error) => return Err(error),
         };
 
-        db::sqlite_migrate(
-            &db_config,
-            MigrationsDir::new("./db/migration".into()).sqlite_migrations(),
-        )
-        .await
-        .unwrap();
+        latest.next_minor().map_err(|_| {
+            ApiEndpointError::bad_request(
+                "api_definition.invalid_version",
+                safe(format!(
+                    "Can't assign the next version of api definition {id}, its latest version {latest} is not a valid semantic version"
+                )),
+            )
+        })
+    }
+}
 
-        let db_pool = db::create_sqlite_pool(&db_config).await.unwrap();
+const LATEST_VERSION: &str = "latest";
 
-        let api_definition_repo: Arc<dyn ApiDefinitionRepo + Sync + Send> = Arc::new(
-            LoggedApiDefinitionRepo::new(DbApiDefinitionRepo::new(db_pool.clone().into())),
-        );
-        let api_deployment_repo: Arc<dyn api_deployment::ApiDeploymentRepo + Sync + Send> =
-            Arc::new(api_deployment::LoggedDeploymentRepo::new(
-                api_deployment::DbApiDeploymentRepo::new(db_pool.clone().into()),
-            ));
+// Version assigned by `create_next` to the first version of an API definition
+const FIRST_VERSION: &str = "1.0.0";
 
-        let security_scheme_repo: Arc<dyn SecuritySchemeRepo + Sync + Send> = Arc::new(
-            LoggedSecuritySchemeRepo::new(DbSecuritySchemeRepo::new(db_pool.clone().into())),
-        );
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+// Number of API definitions loaded from the store at once while streaming
+const NDJSON_PAGE_SIZE: u32 = 100;
+
+// Watch streams are closed after this long, so connections are not held open indefinitely
+const WATCH_MAX_DURATION: Duration = Duration::from_secs(10 * 60);
+
+const WATCH_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+const OPEN_API_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Largest OpenAPI document accepted when importing from a URL
+const OPEN_API_FETCH_MAX_SIZE: usize = 5 * 1024 * 1024;
+
+const OPEN_API_FETCH_MAX_REDIRECTS: usize = 10;
+
+#[derive(ApiResponse)]
+enum ListDefinitionsResponse {
+    #[oai(status = 200)]
+    Ok(ListDefinitionsContent),
+}
+
+#[derive(ResponseContent)]
+enum ListDefinitionsContent {
+    Json(Json<Vec<HttpApiDefinitionResponseData>>),
+    #[oai(content_type = "application/vnd.golem.summary+json")]
+    Summary(Json<Vec<ApiDefinitionSummary>>),
+    #[oai(content_type = "application/x-ndjson")]
+    Ndjson(Binary<Body>),
+}
+
+// Streams all API definitions, or the ones with an id starting with `id_prefix`, one page at a
+// time, each API definition as a single line of JSON.
+// Errors after the response has started can only be reported by aborting the body.
+fn ndjson_stream(
+    definition_service: Arc<
+        dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace> + Sync + Send,
+    >,
+    namespace: DefaultNamespace,
+    id_prefix: Option<String>,
+    labels: BTreeMap<String, String>,
+) -> Body {
+    // `None` after the last page, otherwise the id and version to continue after
+    let first_page: Option<Option<(ApiDefinitionId, ApiVersion)>> = Some(None);
+
+    let pages = stream::try_unfold(first_page, move |cursor| {
+        let definition_service = definition_service.clone();
+        let namespace = namespace.clone();
+        async move {
+            let Some(after) = cursor else {
+                return Ok(None);
+            };
+
+            let page = definition_service
+                .get_page(
+                    &namespace,
+                    &EmptyAuthCtx::default(),
+                    after,
+                    NDJSON_PAGE_SIZE,
+                )
+                .await
+                .map_err(|e| io::Error::other(e.to_safe_string()))?;
+
+            let next = if page.len() < NDJSON_PAGE_SIZE as usize {
+                None
+            } else {
+                page.last()
+                    .map(|definition| Some((definition.id.clone(), definition.version.clone())))
+            };
+
+            let definitions = stream::iter(page.into_iter().map(Ok::<_, io::Error>));
+            Ok::<_, io::Error>(Some((definitions, next)))
+        }
+    });
+
+    let matching = pages.try_flatten().try_filter(move |definition| {
+        let matches = match &id_prefix {
+            Some(id_prefix) => definition.id.0.starts_with(id_prefix.as_str()),
+            None => true,
+        };
+        future::ready(matches && definition.has_labels(&labels))
+    });
+
+    let lines = matching.and_then(|definition| async move {
+        let definition =
+            HttpApiDefinitionResponseData::try_from(definition).map_err(io::Error::other)?;
+        let mut line = serde_json::to_vec(&definition)?;
+        line.push(b'\n');
+        Ok(line)
+    });
+
+    Body::from_bytes_stream(lines)
+}
+
+// Changes of the API definitions in the given namespace, ending the stream if the subscriber
+// lags behind, as the missed changes could only be recovered from a new snapshot
+fn watch_events(
+    receiver: broadcast::Receiver<AuditEvent>,
+    namespace: String,
+) -> impl Stream<Item = ApiDefinitionWatchEvent> {
+    stream::unfold(receiver, move |mut receiver| {
+        let namespace = namespace.clone();
+        async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.namespace == namespace => {
+                        let change = ApiDefinitionWatchEvent::Change(event.into());
+                        return Some((change, receiver));
+                    }
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("API definition watcher lagged behind by {skipped} changes");
+                        return None;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }
+    })
+}
+
+#[derive(ApiResponse, Debug, Clone)]
+enum RegisterDefinitionResponse {
+    #[oai(status = 200)]
+    Ok(
+        Json<HttpApiDefinitionResponseData>,
+        #[oai(header = "X-Processing-Time-Ms")] u64,
+    ),
+}
+
+impl RegisterDefinitionResponse {
+    // Responds with the API definition resulting from the service call. The processing time
+    // covers the service call, but not parsing and converting the payloads.
+    async fn timed<F>(call: F) -> Result<Self, ApiEndpointError>
+    where
+        F: Future<Output = Result<CompiledHttpApiDefinition<DefaultNamespace>, ApiEndpointError>>,
+    {
+        let start = Instant::now();
+        let compiled_definition = call.await?;
+        let processing_time = start.elapsed();
+
+        let definition =
+            HttpApiDefinitionResponseData::try_from(compiled_definition).map_err(|e| {
+                error!("Failed to convert to response data {}", e);
+                ApiEndpointError::internal("api_definition.internal", safe(e))
+            })?;
+
+        Ok(Self::Ok(Json(definition), processing_time.as_millis() as u64))
+    }
+}
+
+// Converts the payload of a write request, keeping the first of the routes with the same method
+// and path if duplicate routes are allowed
+fn to_core_request(
+    payload: HttpApiDefinitionRequest,
+    allow_duplicate_routes: Option<bool>,
+) -> Result<CoreHttpApiDefinitionRequest, ApiEndpointError> {
+    payload
+        .into_core_request(allow_duplicate_routes.unwrap_or(false))
+        .map_err(|err| ApiEndpointError::bad_request("api_definition.invalid", safe(err)))
+}
+
+#[derive(ApiResponse, Debug, Clone)]
+enum GetDefinitionResponse {
+    #[oai(status = 200)]
+    Ok(
+        Json<HttpApiDefinitionResponseData>,
+        #[oai(header = "ETag")] String,
+    ),
+    #[oai(status = 304)]
+    NotModified(#[oai(header = "ETag")] String),
+}
+
+// Labels to filter by, each given as `key=value`
+fn parse_label_filter(labels: &[String]) -> Result<BTreeMap<String, String>, ApiEndpointError> {
+    labels
+        .iter()
+        .map(|label| match label.split_once('=') {
+            Some((key, value)) => Ok((key.to_string(), value.to_string())),
+            None => Err(ApiEndpointError::bad_request(
+                "api_definition.invalid_label",
+                safe(format!("Invalid label filter {label:?}, expected key=value")),
+            )),
+        })
+        .collect()
+}
+
+// Strong entity tag of the canonical form of the definition
+fn etag(definition: &HttpApiDefinitionResponseData) -> Result<String, ApiEndpointError> {
+    definition.entity_tag().map_err(|e| {
+        error!("Failed to hash API definition {}", e);
+        ApiEndpointError::internal("api_definition.internal", safe(e))
+    })
+}
+
+// The entity tags listed in an If-Match header, or None if any of them is accepted
+fn parse_if_match(if_match: &str) -> Option<Vec<String>> {
+    let tags: Vec<String> = if_match
+        .split(',')
+        .map(|tag| tag.trim().to_string())
+        .collect();
+
+    if tags.iter().any(|tag| tag == "*") {
+        None
+    } else {
+        Some(tags)
+    }
+}
+
+// How `create_api` treats the existing and reserved versions of the API definition
+#[derive(Debug, Clone, Copy)]
+enum CreateMode {
+    Unconditional,
+    IfVersionAbsent,
+    Reserved(ReservationToken),
+}
+
+// Versions have to be valid semantic versions, so they can be ordered, unless the client
+// explicitly asks for the relaxed mode
+fn validate_version(version: &ApiVersion, relaxed: bool) -> Result<(), ApiEndpointError> {
+    if relaxed {
+        return Ok(());
+    }
+
+    version.parse_semver().map(|_| ()).map_err(|err| {
+        ApiEndpointError::bad_request(
+            "api_definition.invalid_version",
+            safe(format!(
+                "API definition version {version} is not a valid semantic version: {err}. \
+                 Use relaxed_version=true to register it anyway"
+            )),
+        )
+    })
+}
+
+// Retries the action with exponential backoff while it fails with an internal error, which may
+// be caused by transient contention in the repository. Without a config it is attempted once.
+async fn retry_internal_errors<T, F, Fut>(
+    config: &Option<RetryConfig>,
+    action: F,
+) -> Result<T, ApiDefinitionError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, ApiDefinitionError>>,
+{
+    let Some(config) = config else {
+        return action().await;
+    };
+
+    let mut retry_state = RetryState::new(config);
+    loop {
+        retry_state.start_attempt();
+        match action().await {
+            Err(error) if is_internal(&error) => {
+                warn!("Registering API definition failed with an internal error: {error}");
+                if !retry_state.failed_attempt().await {
+                    break Err(error);
+                }
+            }
+            result => break result,
+        }
+    }
+}
+
+// Fetching and parsing are reported with different error codes, and both keep the whole chain
+// of the underlying errors
+async fn fetch_open_api(
+    url: &str,
+    allow_insecure: bool,
+) -> Result<OpenApiHttpApiDefinitionRequest, ApiEndpointError> {
+    let url = Url::parse(url).map_err(|err| {
+        ApiEndpointError::bad_request(
+            "api_definition.invalid_open_api_url",
+            safe(format!("Invalid OpenAPI document URL {url}: {err}")),
+        )
+    })?;
+    if !is_allowed_open_api_url(&url, allow_insecure) {
+        return Err(ApiEndpointError::bad_request(
+            "api_definition.invalid_open_api_url",
+            safe(format!(
+                "OpenAPI document URL {url} is not allowed, only HTTPS URLs are accepted"
+            )),
+        ));
+    }
+
+    let document = download_open_api(&url, allow_insecure)
+        .await
+        .map_err(|err| {
+            ApiEndpointError::bad_request(
+                "api_definition.open_api_fetch_failed",
+                safe(format!("Failed to fetch OpenAPI document from {url}: {err}")),
+            )
+        })?;
+
+    // YAML is a superset of JSON, so this parses both formats
+    serde_yaml::from_slice::<serde_json::Value>(&document)
+        .map_err(|err| error_chain(&err))
+        .and_then(OpenApiHttpApiDefinitionRequest::from_json_value)
+        .map_err(|err| {
+            ApiEndpointError::bad_request(
+                "api_definition.invalid_open_api",
+                safe(format!(
+                    "Fetched OpenAPI document from {url}, but failed to parse it: {err}"
+                )),
+            )
+        })
+}
+
+fn is_allowed_open_api_url(url: &Url, allow_insecure: bool) -> bool {
+    match url.scheme() {
+        "https" => true,
+        "http" => allow_insecure,
+        _ => false,
+    }
+}
+
+// Redirects are checked too, so an HTTPS URL can't lead to a plain HTTP one. The body is read
+// in chunks to reject an oversized document without buffering all of it.
+async fn download_open_api(url: &Url, allow_insecure: bool) -> Result<Vec<u8>, String> {
+    let redirect_policy = reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= OPEN_API_FETCH_MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if !is_allowed_open_api_url(attempt.url(), allow_insecure) {
+            attempt.error("redirected to a URL which is not HTTPS")
+        } else {
+            attempt.follow()
+        }
+    });
+    let client = reqwest::Client::builder()
+        .timeout(OPEN_API_FETCH_TIMEOUT)
+        .redirect(redirect_policy)
+        .build()
+        .map_err(|err| error_chain(&err))?;
+
+    let mut response = client
+        .get(url.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| error_chain(&err))?;
+
+    let too_large = format!("the document is larger than {OPEN_API_FETCH_MAX_SIZE} bytes");
+    if response
+        .content_length()
+        .is_some_and(|length| length > OPEN_API_FETCH_MAX_SIZE as u64)
+    {
+        return Err(too_large);
+    }
+
+    let mut document = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|err| error_chain(&err))? {
+        if document.len() + chunk.len() > OPEN_API_FETCH_MAX_SIZE {
+            return Err(too_large);
+        }
+        document.extend_from_slice(&chunk);
+    }
+
+    Ok(document)
+}
+
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        message.push_str(&format!(": {error}"));
+        source = error.source();
+    }
+    message
+}
+
+fn is_internal(error: &ApiDefinitionError) -> bool {
+    matches!(
+        error,
+        ApiDefinitionError::InternalRepoError(_) | ApiDefinitionError::Internal(_)
+    )
+}
+
+#[cfg(feature = "metrics")]
+fn record_register_error(error: &ApiEndpointError) {
+    metrics::api_definition::record_register_error(error.trace_error_kind());
+}
+
+#[cfg(not(feature = "metrics"))]
+fn record_register_error(_error: &ApiEndpointError) {}
+
+#[cfg(test)]
+mod test {
+    use golem_service_base::migration::{Migrations, MigrationsDir};
+    use test_r::test;
+
+    use super::*;
+    use crate::service::component::ComponentService;
+    use async_trait::async_trait;
+    use golem_common::config::DbSqliteConfig;
+    use golem_common::model::component_constraint::FunctionConstraintCollection;
+    use golem_common::model::component_metadata::ComponentMetadata;
+    use golem_service_base::db;
+    use golem_service_base::model::{Component, ComponentName, VersionedComponentId};
+    use golem_worker_service_base::gateway_execution::file_server_binding_handler::FileServerBindingResult;
+    use golem_worker_service_base::gateway_execution::gateway_binding_resolver::WorkerDetail;
+    use golem_worker_service_base::gateway_execution::gateway_session::{
+        DataKey, DataValue, GatewaySessionError, SessionId,
+    };
+    use golem_worker_service_base::gateway_execution::{
+        GatewayResolvedWorkerRequest, WorkerRequestExecutorError, WorkerResponse,
+    };
+    use golem_worker_service_base::gateway_security::DefaultIdentityProvider;
+    use golem_worker_service_base::repo::api_definition::{
+        ApiDefinitionRepo, DbApiDefinitionRepo, LoggedApiDefinitionRepo,
+    };
+    use golem_worker_service_base::repo::api_deployment;
+    use golem_worker_service_base::repo::security_scheme::{
+        DbSecuritySchemeRepo, LoggedSecuritySchemeRepo, SecuritySchemeRepo,
+    };
+    use golem_worker_service_base::service::component::{ComponentResult, ComponentServiceError};
+    use golem_worker_service_base::service::gateway::api_definition::ApiDefinitionServiceDefault;
+    use golem_worker_service_base::app_config::RateLimitConfig;
+    use golem_worker_service_base::service::gateway::audit::{
+        BroadcastAuditSink, CollectingAuditSink, TracingAuditSink,
+    };
+    use golem_worker_service_base::service::gateway::rate_limit::{
+        InMemoryRateLimiter, UnlimitedRateLimiter,
+    };
+    use golem_worker_service_base::service::gateway::http_api_definition_validator::HttpApiDefinitionValidator;
+    use golem_worker_service_base::service::gateway::security_scheme::DefaultSecuritySchemeService;
+    use http::StatusCode;
+    use poem::test::{TestClient, TestResponse};
+    use rib::RibResult;
+    use std::marker::PhantomData;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct SqliteDb<'c> {
+        db_path: String,
+        lifetime: PhantomData<&'c ()>,
+    }
+
+    impl Default for SqliteDb<'_> {
+        fn default() -> Self {
+            Self {
+                db_path: format!("/tmp/golem-worker-{}.db", uuid::Uuid::new_v4()),
+                lifetime: PhantomData,
+            }
+        }
+    }
+
+    impl Drop for SqliteDb<'_> {
+        fn drop(&mut self) {
+            std::fs::remove_file(&self.db_path).unwrap();
+        }
+    }
+
+    // The only component known by TestComponentService, which has no exports and only version 0
+    const KNOWN_COMPONENT_ID: &str = "8d3c5a0e-5f8e-4b8a-9f0c-6a1f2c7d9e41";
+
+    struct TestComponentService;
+
+    #[async_trait]
+    impl golem_worker_service_base::service::component::ComponentService<EmptyAuthCtx>
+        for TestComponentService
+    {
+        async fn get_by_version(
+            &self,
+            component_id: &ComponentId,
+            version: u64,
+            _auth_ctx: &EmptyAuthCtx,
+        ) -> ComponentResult<Component> {
+            if component_id.0.to_string() != KNOWN_COMPONENT_ID || version != 0 {
+                return Err(ComponentServiceError::NotFound(component_id.to_string()));
+            }
+
+            Ok(Component {
+                versioned_component_id: VersionedComponentId {
+                    component_id: component_id.clone(),
+                    version,
+                },
+                component_name: ComponentName("known".to_string()),
+                component_size: 0,
+                metadata: ComponentMetadata {
+                    exports: vec![],
+                    producers: vec![],
+                    memories: vec![],
+                    dynamic_linking: Default::default(),
+                },
+                created_at: None,
+                component_type: None,
+                files: vec![],
+                installed_plugins: vec![],
+            })
+        }
+
+        async fn get_latest(
+            &self,
+            _component_id: &ComponentId,
+            _auth_ctx: &EmptyAuthCtx,
+        ) -> ComponentResult<Component> {
+            unimplemented!()
+        }
+
+        async fn create_or_update_constraints(
+            &self,
+            _component_id: &ComponentId,
+            _constraints: FunctionConstraintCollection,
+            _auth_ctx: &EmptyAuthCtx,
+        ) -> ComponentResult<FunctionConstraintCollection> {
+            unimplemented!()
+        }
+    }
+
+    struct TestWorkerRequestExecutor;
+
+    #[async_trait]
+    impl GatewayWorkerRequestExecutor<DefaultNamespace> for TestWorkerRequestExecutor {
+        async fn execute(
+            &self,
+            _resolved_worker_request: GatewayResolvedWorkerRequest<DefaultNamespace>,
+        ) -> Result<WorkerResponse, WorkerRequestExecutorError> {
+            Err("Workers are not available in tests".into())
+        }
+    }
+
+    struct TestFileServerBindingHandler;
+
+    #[async_trait]
+    impl FileServerBindingHandler<DefaultNamespace> for TestFileServerBindingHandler {
+        async fn handle_file_server_binding_result(
+            &self,
+            _namespace: &DefaultNamespace,
+            _worker_detail: &WorkerDetail,
+            _original_result: RibResult,
+        ) -> FileServerBindingResult {
+            unimplemented!()
+        }
+    }
+
+    struct TestSessionStore;
+
+    #[async_trait]
+    impl GatewaySession for TestSessionStore {
+        async fn insert(
+            &self,
+            _session_id: SessionId,
+            _data_key: DataKey,
+            _data_value: DataValue,
+        ) -> Result<(), GatewaySessionError> {
+            Ok(())
+        }
+
+        async fn get(
+            &self,
+            session_id: &SessionId,
+            data_key: &DataKey,
+        ) -> Result<DataValue, GatewaySessionError> {
+            Err(GatewaySessionError::MissingValue {
+                session_id: session_id.clone(),
+                data_key: data_key.clone(),
+            })
+        }
+    }
+
+    async fn make_route<'c>() -> (poem::Route, SqliteDb<'c>) {
+        make_route_with(Arc::new(TracingAuditSink), Arc::new(UnlimitedRateLimiter), None).await
+    }
+
+    async fn make_route_with<'c>(
+        audit_sink: Arc<dyn AuditSink + Sync + Send>,
+        registration_rate_limiter: Arc<dyn RateLimiter + Sync + Send>,
+        max_definitions: Option<u64>,
+    ) -> (poem::Route, SqliteDb<'c>) {
+        let db = SqliteDb::default();
+        let db_config = DbSqliteConfig {
+            database: db.db_path.to_string(),
+            max_connections: 10,
+        };
+
+        db::sqlite_migrate(
+            &db_config,
+            MigrationsDir::new("./db/migration".into()).sqlite_migrations(),
+        )
+        .await
+        .unwrap();
+
+        let db_pool = db::create_sqlite_pool(&db_config).await.unwrap();
+
+        let api_definition_repo: Arc<dyn ApiDefinitionRepo + Sync + Send> = Arc::new(
+            LoggedApiDefinitionRepo::new(DbApiDefinitionRepo::new(db_pool.clone().into())),
+        );
+        let api_deployment_repo: Arc<dyn api_deployment::ApiDeploymentRepo + Sync + Send> =
+            Arc::new(api_deployment::LoggedDeploymentRepo::new(
+                api_deployment::DbApiDeploymentRepo::new(db_pool.clone().into()),
+            ));
+
+        let security_scheme_repo: Arc<dyn SecuritySchemeRepo + Sync + Send> = Arc::new(
+            LoggedSecuritySchemeRepo::new(DbSecuritySchemeRepo::new(db_pool.clone().into())),
+        );
+
+        let identity_provider = Arc::new(DefaultIdentityProvider);
+
+        let security_scheme_service = Arc::new(DefaultSecuritySchemeService::new(
+            security_scheme_repo,
+            identity_provider,
+        ));
+
+        let component_service: ComponentService = Arc::new(TestComponentService);
+        let definition_service = ApiDefinitionServiceDefault::new(
+            component_service,
+            api_definition_repo,
+            api_deployment_repo,
+            security_scheme_service,
+            Arc::new(HttpApiDefinitionValidator {}),
+            max_definitions,
+        );
+
+        let endpoint = RegisterApiDefinitionApi::new(
+            Arc::new(definition_service),
+            audit_sink,
+            registration_rate_limiter,
+            None,
+            Arc::new(TestWorkerRequestExecutor),
+            Arc::new(TestFileServerBindingHandler),
+            Arc::new(TestSessionStore),
+        );
+
+        (
+            poem::Route::new().nest("", OpenApiService::new(endpoint, "test", "1.0")),
+            db,
+        )
+    }
+
+    fn definition(id: &str, version: &str) -> HttpApiDefinitionRequest {
+        HttpApiDefinitionRequest {
+            id: ApiDefinitionId(id.to_string()),
+            version: ApiVersion(version.to_string()),
+            routes: vec![],
+            draft: true,
+            labels: BTreeMap::new(),
+            security: None,
+        }
+    }
+
+    // Creates the API definition, which is expected to succeed
+    async fn register(
+        client: &TestClient<poem::Route>,
+        definition: &impl serde::Serialize,
+    ) -> TestResponse {
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(definition)
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        response
+    }
+
+    #[test]
+    async fn conflict_error_returned() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let definition = definition("test", "1.0.0");
+        register(&client, &definition).await;
+
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&definition)
+            .send()
+            .await;
+
+        response.assert_status(http::StatusCode::CONFLICT);
+        let body = response.json().await;
+        body.value()
+            .object()
+            .get("code")
+            .assert_string("api_definition.already_exists");
+    }
+
+    #[test]
+    async fn create_if_version_absent() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client
+            .post("/v1/api/definitions")
+            .query("if_version_absent", &true)
+            .body_json(&definition("test", "1.0.0"))
+            .send()
+            .await;
+        response.assert_status_is_ok();
+
+        let response = client
+            .post("/v1/api/definitions")
+            .query("if_version_absent", &true)
+            .body_json(&definition("test", "2.0.0"))
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::CONFLICT);
+        let body = response.json().await;
+        body.value()
+            .object()
+            .get("code")
+            .assert_string("api_definition.already_exists");
+
+        register(&client, &definition("test", "2.0.0")).await;
+    }
+
+    #[test]
+    async fn create_next_assigns_the_next_minor_version() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        for expected in ["1.0.0", "1.1.0"] {
+            let response = client
+                .post("/v1/api/definitions/next")
+                .body_json(&definition("test", "ignored"))
+                .send()
+                .await;
+            response.assert_status_is_ok();
+            let body = response.json().await;
+            body.value().object().get("version").assert_string(expected);
+        }
+
+        register(&client, &definition("test", "2.3.4-beta.1")).await;
+
+        let response = client
+            .post("/v1/api/definitions/next")
+            .body_json(&definition("test", "ignored"))
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        body.value().object().get("version").assert_string("2.4.0");
+    }
+
+    #[test]
+    async fn reserved_version_is_only_created_by_its_commit() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client
+            .post("/v1/api/definitions/reserve")
+            .body_json(&serde_json::json!({ "id": "test", "version": "1.0.0" }))
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        let token = body.value().object().get("token").string().to_string();
+
+        let response = client
+            .post("/v1/api/definitions/reserve")
+            .body_json(&serde_json::json!({ "id": "test", "version": "1.0.0" }))
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::CONFLICT);
+
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&definition("test", "1.0.0"))
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::CONFLICT);
+
+        let response = client
+            .put("/v1/api/definitions/commit")
+            .query("token", &token)
+            .body_json(&definition("test", "2.0.0"))
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::BAD_REQUEST);
+
+        let response = client
+            .put("/v1/api/definitions/commit")
+            .query("token", &uuid::Uuid::new_v4())
+            .body_json(&definition("test", "1.0.0"))
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
+
+        let response = client
+            .put("/v1/api/definitions/commit")
+            .query("token", &token)
+            .body_json(&definition("test", "1.0.0"))
+            .send()
+            .await;
+        response.assert_status_is_ok();
+
+        let response = client
+            .put("/v1/api/definitions/commit")
+            .query("token", &token)
+            .body_json(&definition("test", "1.0.0"))
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    async fn schema_describes_api_definition_requests() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client.get("/v1/api/definitions/schema").send().await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        body.value()
+            .object()
+            .get("$ref")
+            .assert_string("#/$defs/HttpApiDefinitionRequest");
+    }
+
+    #[test]
+    async fn definitions_are_filtered_by_labels() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let labelled = |id: &str, labels: &[(&str, &str)]| HttpApiDefinitionRequest {
+            labels: labels
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            ..definition(id, "1.0.0")
+        };
+
+        for definition in [
+            labelled("cart", &[("env", "prod"), ("team", "shop")]),
+            labelled("orders", &[("env", "staging"), ("team", "shop")]),
+            labelled("unlabelled", &[]),
+        ] {
+            register(&client, &definition).await;
+        }
+
+        let response = client
+            .get("/v1/api/definitions")
+            .query("label", &"team=shop")
+            .query("label", &"env=prod")
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        let definitions = body.value().array();
+        definitions.assert_len(1);
+        let cart = definitions.get(0).object();
+        cart.get("id").assert_string("cart");
+        cart.get("labels")
+            .object()
+            .get("env")
+            .assert_string("prod");
+
+        let response = client
+            .get("/v1/api/definitions")
+            .query("label", &"team")
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::BAD_REQUEST);
+
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&labelled("invalid", &[("owner", "a b")]))
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    async fn not_found_error_has_code() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client.get("/v1/api/definitions/missing/1.0.0").send().await;
+
+        response.assert_status(http::StatusCode::NOT_FOUND);
+        let body = response.json().await;
+        body.value()
+            .object()
+            .get("code")
+            .assert_string("api_definition.not_found");
+    }
+
+    #[test]
+    async fn create_api_definition_yaml() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client
+            .post("/v1/api/definitions")
+            .body_yaml(&definition("sample", "42.0.0"))
+            .send()
+            .await;
+
+        response.assert_status(http::StatusCode::OK);
+    }
+
+    #[test]
+    async fn create_api_definition_json() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = register(&client, &definition("sample", "42.0.0")).await;
+        response.assert_header_exist("X-Processing-Time-Ms");
+    }
+
+    #[test]
+    async fn non_semver_version_rejected_unless_relaxed() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let definition = definition("sample", "v1");
+
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::BAD_REQUEST);
+
+        let response = client
+            .post("/v1/api/definitions")
+            .query("relaxed_version", &true)
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status_is_ok();
+    }
+
+    #[test]
+    async fn empty_version_does_not_block_its_id() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        // The lock of the empty version used to be the lock of the whole id, which the creation
+        // had already taken, so the request never completed
+        let create = client
+            .post("/v1/api/definitions")
+            .query("relaxed_version", &true)
+            .body_json(&definition("test", ""))
+            .send();
+        tokio::time::timeout(Duration::from_secs(10), create)
+            .await
+            .expect("Creating a definition with an empty version did not complete");
+
+        let create = register(&client, &definition("test", "1.0.0"));
+        tokio::time::timeout(Duration::from_secs(10), create)
+            .await
+            .expect("Creating another version of the definition did not complete");
+    }
+
+    #[test]
+    async fn get_and_delete_latest_version() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client.get("/v1/api/definitions/test/latest").send().await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
+
+        for version in ["1.2.0", "1.10.0", "1.9.0"] {
+            register(&client, &definition("test", version)).await;
+        }
+
+        let response = client.get("/v1/api/definitions/test/latest").send().await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        body.value()
+            .object()
+            .get("version")
+            .assert_string("1.10.0");
+
+        let response = client.delete("/v1/api/definitions/test/latest").send().await;
+        response.assert_status_is_ok();
+
+        let response = client.get("/v1/api/definitions/test/latest").send().await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        body.value()
+            .object()
+            .get("version")
+            .assert_string("1.9.0");
+    }
+
+    #[test]
+    async fn health_check_succeeds() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client.get("/v1/api/definitions/health").send().await;
+        response.assert_status_is_ok();
+    }
+
+    #[test]
+    async fn update_with_stale_etag_rejected() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let mut definition = definition("test", "1.0.0");
+        register(&client, &definition).await;
+
+        let response = client.get("/v1/api/definitions/test/1.0.0").send().await;
+        response.assert_status_is_ok();
+        let etag = response
+            .0
+            .headers()
+            .get("ETag")
+            .expect("ETag header is missing")
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        definition.draft = false;
+        let response = client
+            .put("/v1/api/definitions/test/1.0.0")
+            .header("If-Match", "\"stale\"")
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::PRECONDITION_FAILED);
+
+        let response = client
+            .put("/v1/api/definitions/test/1.0.0")
+            .header("If-Match", &etag)
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status_is_ok();
+
+        let response = client.get("/v1/api/definitions/test/1.0.0").send().await;
+        response.assert_status_is_ok();
+        response.assert_header_exist("ETag");
+        assert_ne!(response.0.headers().get("ETag").unwrap(), etag.as_str());
+    }
+
+    #[test]
+    async fn get_honors_if_none_match() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        register(&client, &definition("test", "1.0.0")).await;
+
+        let response = client.get("/v1/api/definitions/test/1.0.0").send().await;
+        response.assert_status_is_ok();
+        let etag = response
+            .0
+            .headers()
+            .get("ETag")
+            .expect("ETag header is missing")
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let response = client
+            .get("/v1/api/definitions/test/1.0.0")
+            .header("If-None-Match", &etag)
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::NOT_MODIFIED);
+        response.assert_header("ETag", etag.as_str());
+
+        let response = client
+            .get("/v1/api/definitions/test/1.0.0")
+            .header("If-None-Match", "\"stale\"")
+            .send()
+            .await;
+        response.assert_status_is_ok();
+
+        let response = client
+            .get("/v1/api/definitions/test/2.0.0")
+            .header("If-None-Match", "*")
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    async fn definition_changes_are_audited() {
+        let audit_sink = Arc::new(CollectingAuditSink::new());
+        let (api, _db) =
+            make_route_with(audit_sink.clone(), Arc::new(UnlimitedRateLimiter), None).await;
+        let client = TestClient::new(api);
+
+        let mut definition = definition("test", "1.0.0");
+        register(&client, &definition).await;
+
+        // Failed changes are not audited
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::CONFLICT);
+
+        definition.draft = false;
+        let response = client
+            .put("/v1/api/definitions/test/1.0.0")
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status_is_ok();
+
+        let response = client.delete("/v1/api/definitions/test/1.0.0").send().await;
+        response.assert_status_is_ok();
+
+        let events = audit_sink.events();
+        let actions = events.iter().map(|event| event.action).collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![AuditAction::Create, AuditAction::Update, AuditAction::Delete]
+        );
+        for event in events {
+            assert_eq!(event.namespace, DefaultNamespace::default().to_string());
+            assert_eq!(event.id, definition.id);
+            assert_eq!(event.version, definition.version);
+            assert_eq!(event.subject, EmptyAuthCtx::default().to_string());
+        }
+    }
+
+    #[test]
+    async fn watch_streams_snapshot_and_changes() {
+        let audit_sink = Arc::new(BroadcastAuditSink::new(TracingAuditSink));
+        let (api, _db) = make_route_with(audit_sink, Arc::new(UnlimitedRateLimiter), None).await;
+        let client = TestClient::new(api);
+
+        register(&client, &definition("test", "1.0.0")).await;
+
+        let response = client.get("/v1/api/definitions/watch").send().await;
+        response.assert_status_is_ok();
+        response.assert_content_type("text/event-stream");
+        let mut events = response.0.into_body().into_bytes_stream();
+
+        let snapshot = read_event(&mut events).await;
+        assert!(snapshot.contains(r#""type":"Snapshot""#));
+        assert!(snapshot.contains(r#"{"id":"test","version":"1.0.0"}"#));
+
+        let response = client.delete("/v1/api/definitions/test/1.0.0").send().await;
+        response.assert_status_is_ok();
+
+        let change = read_event(&mut events).await;
+        assert!(change.contains(r#""type":"Change""#));
+        assert!(change.contains(r#""action":"Delete""#));
+    }
+
+    async fn read_event(
+        events: &mut (impl futures::Stream<Item = io::Result<bytes::Bytes>> + Unpin),
+    ) -> String {
+        let mut event = String::new();
+        while !event.ends_with("\n\n") {
+            let chunk = events.next().await.expect("event stream ended").unwrap();
+            event.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+        event
+    }
+
+    #[test]
+    async fn registration_is_rate_limited() {
+        let rate_limiter = Arc::new(InMemoryRateLimiter::new(RateLimitConfig {
+            rate_per_second: 0.1,
+            burst: 1,
+        }));
+        let (api, _db) = make_route_with(Arc::new(TracingAuditSink), rate_limiter, None).await;
+        let client = TestClient::new(api);
+
+        let mut definition = definition("test", "1.0.0");
+        register(&client, &definition).await;
+
+        definition.version = ApiVersion("2.0.0".to_string());
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::TOO_MANY_REQUESTS);
+        response.assert_header("Retry-After", "10");
+    }
+
+    #[test]
+    async fn updates_are_rate_limited() {
+        let rate_limiter = Arc::new(InMemoryRateLimiter::new(RateLimitConfig {
+            rate_per_second: 0.1,
+            burst: 1,
+        }));
+        let (api, _db) = make_route_with(Arc::new(TracingAuditSink), rate_limiter, None).await;
+        let client = TestClient::new(api);
+
+        let definition = definition("test", "1.0.0");
+        register(&client, &definition).await;
+
+        let response = client
+            .put("/v1/api/definitions/test/1.0.0")
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::TOO_MANY_REQUESTS);
+        response.assert_header("Retry-After", "10");
+    }
+
+    #[test]
+    async fn zero_rate_does_not_overflow_retry_after() {
+        let rate_limiter = Arc::new(InMemoryRateLimiter::new(RateLimitConfig {
+            rate_per_second: 0.0,
+            burst: 1,
+        }));
+        let (api, _db) = make_route_with(Arc::new(TracingAuditSink), rate_limiter, None).await;
+        let client = TestClient::new(api);
+
+        let mut definition = definition("test", "1.0.0");
+        register(&client, &definition).await;
+
+        definition.version = ApiVersion("2.0.0".to_string());
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::TOO_MANY_REQUESTS);
+        response.assert_header("Retry-After", u64::MAX.to_string());
+    }
+
+    #[test]
+    async fn definitions_are_limited() {
+        let (api, _db) =
+            make_route_with(Arc::new(TracingAuditSink), Arc::new(UnlimitedRateLimiter), Some(1))
+                .await;
+        let client = TestClient::new(api);
+
+        let mut definition = definition("test", "1.0.0");
+        register(&client, &definition).await;
+
+        definition.version = ApiVersion("2.0.0".to_string());
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&definition)
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::CONFLICT);
+        response
+            .json()
+            .await
+            .value()
+            .object()
+            .get("code")
+            .assert_string("api_definition.limit_exceeded");
+    }
+
+    #[test]
+    async fn empty_ids_are_rejected() {
+        let (api, _db) =
+            make_route_with(Arc::new(TracingAuditSink), Arc::new(UnlimitedRateLimiter), Some(1))
+                .await;
+        let client = TestClient::new(api);
+
+        // With a limit, the creation also takes the namespace lock, which must not be the lock
+        // of the empty id
+        let create = client
+            .post("/v1/api/definitions")
+            .body_json(&definition("", "1.0.0"))
+            .send();
+        let response = tokio::time::timeout(Duration::from_secs(10), create)
+            .await
+            .expect("Creating a definition with an empty id did not complete");
+        response.assert_status(http::StatusCode::BAD_REQUEST);
+        response
+            .json()
+            .await
+            .value()
+            .object()
+            .get("code")
+            .assert_string("api_definition.validation_failed");
+
+        register(&client, &definition("test", "1.0.0")).await;
+    }
+
+    #[test]
+    async fn unknown_components_are_rejected() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let component_id = ComponentId::new_v4();
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&serde_json::json!({
+                "id": "test",
+                "version": "1.0.0",
+                "draft": true,
+                "routes": [{
+                    "method": "Get",
+                    "path": "/test",
+                    "binding": {
+                        "componentId": { "componentId": component_id, "version": 0 },
+                        "response": "${1}"
+                    }
+                }]
+            }))
+            .send()
+            .await;
+
+        response.assert_status(http::StatusCode::BAD_REQUEST);
+        let body = response.json().await;
+        let body = body.value().object();
+        body.get("code")
+            .assert_string("api_definition.component_not_found");
+        let error = body.get("errors").array().get(0).string();
+        assert!(error.contains(&component_id.to_string()));
+    }
+
+    #[test]
+    async fn unknown_pinned_component_versions_are_rejected() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&serde_json::json!({
+                "id": "test",
+                "version": "1.0.0",
+                "draft": true,
+                "routes": [{
+                    "method": "Get",
+                    "path": "/test",
+                    "binding": {
+                        "componentId": { "componentId": KNOWN_COMPONENT_ID, "version": 0 },
+                        "componentVersion": 1,
+                        "workerName": "\"worker\"",
+                        "response": "${1}"
+                    }
+                }]
+            }))
+            .send()
+            .await;
+
+        response.assert_status(http::StatusCode::BAD_REQUEST);
+        let body = response.json().await;
+        let body = body.value().object();
+        body.get("code")
+            .assert_string("api_definition.component_not_found");
+        let error = body.get("errors").array().get(0).string();
+        assert!(error.contains(&format!("{KNOWN_COMPONENT_ID}#1")));
+        assert!(!error.contains(&format!("{KNOWN_COMPONENT_ID}#0")));
+    }
+
+    #[test]
+    async fn malformed_definitions_report_the_failing_field() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let definition = |method: &str, binding: serde_json::Value| {
+            serde_json::json!({
+                "id": "test",
+                "version": "1.0.0",
+                "draft": true,
+                "routes": [{
+                    "method": "Get",
+                    "path": "/a",
+                    "binding": {
+                        "componentId": { "componentId": KNOWN_COMPONENT_ID, "version": 0 },
+                        "response": "${1}"
+                    }
+                }, {
+                    "method": method,
+                    "path": "/b",
+                    "binding": binding
+                }]
+            })
+        };
+        let component_id = serde_json::json!({ "componentId": KNOWN_COMPONENT_ID, "version": 0 });
+
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&definition(
+                "Fetch",
+                serde_json::json!({ "componentId": component_id, "response": "${1}" }),
+            ))
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::BAD_REQUEST);
+        let body = response.0.into_body().into_string().await.unwrap();
+        assert!(body.contains("routes[1].method"), "{body}");
 
-        let identity_provider = Arc::new(DefaultIdentityProvider);
+        let response = client
+            .post("/v1/api/definitions")
+            .body_json(&definition("Get", serde_json::json!({ "componentId": component_id })))
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::BAD_REQUEST);
+        let body = response.json().await;
+        let body = body.value().object();
+        body.get("code").assert_string("api_definition.invalid");
+        let error = body.get("errors").array().get(0).string();
+        assert!(error.contains("routes[1]"), "{error}");
+    }
 
-        let security_scheme_service = Arc::new(DefaultSecuritySchemeService::new(
-            security_scheme_repo,
-            identity_provider,
-        ));
+    #[test]
+    async fn head_checks_existence() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
 
-        let component_service: ComponentService = Arc::new(TestComponentService);
-        let definition_service = ApiDefinitionServiceDefault::new(
-            component_service,
-            api_definition_repo,
-            api_deployment_repo,
-            security_scheme_service,
-            Arc::new(HttpApiDefinitionValidator {}),
-        );
+        register(&client, &definition("test", "1.0.0")).await;
 
-        let endpoint = RegisterApiDefinitionApi::new(Arc::new(definition_service));
+        let response = client
+            .head("/v1/api/definitions")
+            .query("api-definition-id", &"test")
+            .query("version", &"1.0.0")
+            .send()
+            .await;
+        response.assert_status_is_ok();
 
-        (
-            poem::Route::new().nest("", OpenApiService::new(endpoint, "test", "1.0")),
-            db,
-        )
+        let response = client
+            .head("/v1/api/definitions")
+            .query("api-definition-id", &"test")
+            .query("version", &"2.0.0")
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
     }
 
     #[test]
-    async fn conflict_error_returned() {
+    async fn duplicate_routes_are_rejected() {
         let (api, _db) = make_route().await;
         let client = TestClient::new(api);
 
-        let definition = golem_worker_service_base::api::HttpApiDefinitionRequest {
-            id: ApiDefinitionId("test".to_string()),
-            version: ApiVersion("1.0".to_string()),
-            routes: vec![],
-            draft: false,
-            security: None,
-        };
+        let route = serde_json::json!({
+            "method": "Options",
+            "path": "/cart",
+            "binding": { "bindingType": "cors-preflight" }
+        });
+        let definition = serde_json::json!({
+            "id": "test",
+            "version": "1.0.0",
+            "routes": [route, route],
+            "draft": true
+        });
 
         let response = client
             .post("/v1/api/definitions")
             .body_json(&definition)
             .send()
             .await;
-
-        response.assert_status_is_ok();
+        response.assert_status(http::StatusCode::BAD_REQUEST);
 
         let response = client
             .post("/v1/api/definitions")
+            .query("allow_duplicate_routes", &true)
             .body_json(&definition)
             .send()
             .await;
-
-        response.assert_status(http::StatusCode::CONFLICT);
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        body.value().object().get("routes").array().assert_len(1);
     }
 
     #[test]
-    async fn create_api_definition_yaml() {
+    async fn all_versions_are_deleted() {
         let (api, _db) = make_route().await;
         let client = TestClient::new(api);
 
-        let definition = HttpApiDefinitionRequest {
-            id: ApiDefinitionId("sample".to_string()),
-            version: ApiVersion("42.0".to_string()),
-            routes: vec![],
-            draft: false,
-            security: None,
-        };
+        for version in ["1.0.0", "2.0.0"] {
+            register(&client, &definition("test", version)).await;
+        }
 
         let response = client
-            .post("/v1/api/definitions")
-            .body_yaml(&definition)
+            .delete("/v1/api/definitions/by-id")
+            .query("api-definition-id", &"test")
             .send()
             .await;
+        response.assert_status_is_ok();
+        response.assert_json(2).await;
 
-        response.assert_status(http::StatusCode::OK);
+        let response = client.get("/v1/api/definitions/test/1.0.0").send().await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
+
+        let response = client
+            .delete("/v1/api/definitions/by-id")
+            .query("api-definition-id", &"test")
+            .send()
+            .await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
     }
 
     #[test]
-    async fn create_api_definition_json() {
+    async fn single_route_is_deleted() {
         let (api, _db) = make_route().await;
         let client = TestClient::new(api);
 
-        let definition = HttpApiDefinitionRequest {
-            id: ApiDefinitionId("sample".to_string()),
-            version: ApiVersion("42.0".to_string()),
-            routes: vec![],
-            draft: false,
-            security: None,
+        let definition = serde_json::json!({
+            "id": "test",
+            "version": "1.0.0",
+            "routes": [
+                {
+                    "method": "Options",
+                    "path": "/cart",
+                    "binding": { "bindingType": "cors-preflight" }
+                },
+                {
+                    "method": "Options",
+                    "path": "/orders",
+                    "binding": { "bindingType": "cors-preflight" }
+                }
+            ],
+            "draft": true
+        });
+        register(&client, &definition).await;
+
+        let delete_route = |version: &'static str, path: &'static str| {
+            client
+                .delete("/v1/api/definitions/route")
+                .query("api-definition-id", &"test")
+                .query("version", &version)
+                .query("method", &"Options")
+                .query("path", &path)
+                .send()
         };
 
+        let response = delete_route("1.0.0", "/cart").await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        let routes = body.value().object().get("routes").array();
+        routes.assert_len(1);
+        routes.get(0).object().get("path").assert_string("/orders");
+
+        let response = delete_route("1.0.0", "/cart").await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
+
+        let response = delete_route("2.0.0", "/orders").await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    async fn routing_table_flags_conflicting_routes() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let with_routes = |id: &str, version: &str, paths: &[&str]| {
+            let routes = paths
+                .iter()
+                .map(|path| {
+                    serde_json::json!({
+                        "method": "Options",
+                        "path": path,
+                        "binding": { "bindingType": "cors-preflight" }
+                    })
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({ "id": id, "version": version, "routes": routes, "draft": true })
+        };
+
+        for definition in [
+            with_routes("shop", "1.0.0", &["/legacy"]),
+            with_routes("shop", "2.0.0", &["/orders", "/cart"]),
+            with_routes("cart", "1.0.0", &["/cart"]),
+        ] {
+            register(&client, &definition).await;
+        }
+
+        let response = client.get("/v1/api/definitions/routing-table").send().await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        let entries = body.value().array();
+        entries.assert_len(3);
+
+        let entry = entries.get(0).object();
+        entry.get("path").assert_string("/cart");
+        entry.get("definitionId").assert_string("cart");
+        entry.get("conflicting").assert_bool(true);
+        let entry = entries.get(1).object();
+        entry.get("path").assert_string("/cart");
+        entry.get("definitionId").assert_string("shop");
+        entry.get("definitionVersion").assert_string("2.0.0");
+        entry.get("conflicting").assert_bool(true);
+        let entry = entries.get(2).object();
+        entry.get("path").assert_string("/orders");
+        entry.get("conflicting").assert_bool(false);
+    }
+
+    #[test]
+    async fn routes_are_tested_without_deployment() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let definition = serde_json::json!({
+            "id": "test",
+            "version": "1.0.0",
+            "routes": [
+                {
+                    "method": "Options",
+                    "path": "/cart",
+                    "binding": { "bindingType": "cors-preflight" }
+                }
+            ],
+            "draft": true
+        });
+        register(&client, &definition).await;
+
+        let test_route = |version: &'static str, path: &'static str| {
+            client
+                .post("/v1/api/definitions/routes/test")
+                .query("api-definition-id", &"test")
+                .query("version", &version)
+                .body_json(&serde_json::json!({ "method": "Options", "path": path }))
+                .send()
+        };
+
+        let response = test_route("1.0.0", "/cart").await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        body.value().object().get("status").assert_i64(200);
+
+        let response = test_route("1.0.0", "/orders").await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        body.value().object().get("status").assert_i64(404);
+
+        let response = test_route("2.0.0", "/cart").await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    async fn open_api_client_is_exported() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        register(&client, &definition("test", "1.0.0")).await;
+
         let response = client
-            .post("/v1/api/definitions")
-            .body_json(&definition)
+            .get("/v1/api/definitions/oas/client")
+            .query("api-definition-id", &"test")
+            .query("version", &"1.0.0")
             .send()
             .await;
+        response.assert_status_is_ok();
+        response.assert_header(
+            "Content-Disposition",
+            "attachment; filename=\"test-1.0.0.openapi.json\"",
+        );
+        let body = response.json().await;
+        body.value()
+            .object()
+            .get("openapi")
+            .assert_string("3.0.0");
+        body.value()
+            .object()
+            .get("x-golem-api-definition-id")
+            .assert_string("test");
+    }
 
-        response.assert_status(http::StatusCode::OK);
+    #[test]
+    async fn count_definitions() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        for (id, version) in [("first", "1.0.0"), ("first", "2.0.0"), ("second", "1.0.0")] {
+            register(&client, &definition(id, version)).await;
+        }
+
+        let response = client.get("/v1/api/definitions/count").send().await;
+        response.assert_status_is_ok();
+        response.assert_json(3).await;
+
+        let response = client
+            .get("/v1/api/definitions/count")
+            .query("api-definition-id", &"first")
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        response.assert_json(2).await;
     }
 
     #[test]
@@ -531,13 +2945,7 @@ mod test {
         let (api, _db) = make_route().await;
         let client = TestClient::new(api);
 
-        let definition = HttpApiDefinitionRequest {
-            id: ApiDefinitionId("test".to_string()),
-            version: ApiVersion("42.0".to_string()),
-            routes: vec![],
-            draft: false,
-            security: None,
-        };
+        let definition = definition("test", "42.0.0");
 
         let response = client
             .put(format!(
@@ -556,38 +2964,153 @@ mod test {
         let (api, _db) = make_route().await;
         let client = TestClient::new(api);
 
-        let definition = HttpApiDefinitionRequest {
-            id: ApiDefinitionId("test".to_string()),
-            version: ApiVersion("1.0".to_string()),
-            routes: vec![],
-            draft: false,
-            security: None,
-        };
+        register(&client, &definition("test", "1.0.0")).await;
+
+        register(&client, &definition("test", "2.0.0")).await;
+
+        let response = client.get("/v1/api/definitions").send().await;
+        response.assert_status_is_ok();
+        let body = response.json().await;
+        body.value().array().assert_len(2)
+    }
+
+    #[test]
+    async fn get_all_with_id_prefix() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        for id in ["billing-invoices", "shop", "billing-payments"] {
+            register(&client, &definition(id, "1.0.0")).await;
+        }
+
         let response = client
-            .post("/v1/api/definitions")
-            .body_json(&definition)
+            .get("/v1/api/definitions")
+            .query("id-prefix", &"billing-")
             .send()
             .await;
         response.assert_status_is_ok();
+        let body = response.json().await;
+        let ids = body
+            .value()
+            .array()
+            .iter()
+            .map(|definition| definition.object().get("id").string().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["billing-invoices", "billing-payments"]);
 
-        let definition = HttpApiDefinitionRequest {
-            id: ApiDefinitionId("test".to_string()),
-            version: ApiVersion("2.0".to_string()),
-            routes: vec![],
-            draft: false,
-            security: None,
-        };
         let response = client
-            .post("/v1/api/definitions")
-            .body_json(&definition)
+            .get("/v1/api/definitions")
+            .query("id-prefix", &"billing-")
+            .header("Accept", "application/x-ndjson")
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        let body = response.0.into_body().into_string().await.unwrap();
+        assert_eq!(body.lines().count(), 2);
+    }
+
+    #[test]
+    async fn get_all_summaries() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        register(&client, &definition("test", "1.0.0")).await;
+
+        let response = client
+            .get("/v1/api/definitions")
+            .query("summary", &true)
             .send()
             .await;
         response.assert_status_is_ok();
+        response.assert_content_type("application/vnd.golem.summary+json");
+        let body: serde_json::Value = response.0.into_body().into_json().await.unwrap();
+        let summaries = body.as_array().unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0]["id"], "test");
+        assert_eq!(summaries[0]["version"], "1.0.0");
+        assert_eq!(summaries[0]["routeCount"], 0);
+        assert_eq!(summaries[0]["draft"], true);
+        assert!(summaries[0].get("routes").is_none());
+    }
+
+    #[test]
+    async fn get_all_is_ordered_by_id_and_version() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        for (id, version) in [("b", "1.0.0"), ("a", "10.0.0"), ("c", "1.0.0"), ("a", "2.0.0")] {
+            register(&client, &definition(id, version)).await;
+        }
 
         let response = client.get("/v1/api/definitions").send().await;
         response.assert_status_is_ok();
         let body = response.json().await;
-        body.value().array().assert_len(2)
+        let definitions: Vec<(String, String)> = body
+            .value()
+            .array()
+            .iter()
+            .map(|definition| {
+                let definition = definition.object();
+                (
+                    definition.get("id").string().to_string(),
+                    definition.get("version").string().to_string(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            definitions,
+            vec![
+                ("a".to_string(), "2.0.0".to_string()),
+                ("a".to_string(), "10.0.0".to_string()),
+                ("b".to_string(), "1.0.0".to_string()),
+                ("c".to_string(), "1.0.0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    async fn listing_by_unused_component_is_empty() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        register(&client, &definition("no-routes", "1.0.0")).await;
+
+        let response = client
+            .get("/v1/api/definitions/by-component")
+            .query("component-id", &ComponentId::new_v4().to_string())
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        response.assert_json(serde_json::json!([])).await;
+    }
+
+    #[test]
+    async fn get_all_as_ndjson() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        for id in ["second", "first"] {
+            register(&client, &definition(id, "1.0.0")).await;
+        }
+
+        let response = client
+            .get("/v1/api/definitions")
+            .header("Accept", "application/x-ndjson")
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        response.assert_content_type("application/x-ndjson");
+
+        let body = response.0.into_body().into_string().await.unwrap();
+        let ids = body
+            .lines()
+            .map(|line| {
+                let definition: serde_json::Value = serde_json::from_str(line).unwrap();
+                definition["id"].as_str().unwrap().to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["first", "second"]);
     }
 
     #[ignore] // There is already sql tests that does this
@@ -694,4 +3217,155 @@ mod test {
 
         response.assert_status_is_ok();
     }
+
+    #[test]
+    async fn import_rejects_unsupported_content_types() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client
+            .put("/v1/api/definitions/import")
+            .content_type("application/x-www-form-urlencoded")
+            .body("openapi=3.0.0")
+            .send()
+            .await;
+
+        response.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    // The check is part of the JSON or YAML payload, so it applies to every endpoint taking one
+    #[test]
+    async fn create_and_update_reject_unsupported_content_types() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        register(&client, &definition("test", "1.0.0")).await;
+
+        let response = client
+            .post("/v1/api/definitions")
+            .content_type("text/plain")
+            .body("id: test")
+            .send()
+            .await;
+        response.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let response = client
+            .put("/v1/api/definitions/test/1.0.0")
+            .content_type("text/plain")
+            .body("id: test")
+            .send()
+            .await;
+        response.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    fn retry_config(max_attempts: u32) -> Option<RetryConfig> {
+        Some(RetryConfig {
+            max_attempts,
+            min_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            multiplier: 2.0,
+            max_jitter_factor: None,
+        })
+    }
+
+    #[test]
+    async fn internal_errors_are_retried() {
+        let attempts = &AtomicU32::new(0);
+        let result = retry_internal_errors(&retry_config(3), || async move {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(ApiDefinitionError::Internal("contention".to_string()))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    async fn retries_give_up_after_max_attempts() {
+        let attempts = &AtomicU32::new(0);
+        let result: Result<(), _> = retry_internal_errors(&retry_config(3), || async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(ApiDefinitionError::Internal("contention".to_string()))
+        })
+        .await;
+
+        assert!(matches!(result, Err(ApiDefinitionError::Internal(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    async fn other_errors_and_disabled_retries_are_not_retried() {
+        let attempts = &AtomicU32::new(0);
+        let result: Result<(), _> = retry_internal_errors(&retry_config(3), || async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(ApiDefinitionError::ApiDefinitionNotFound(ApiDefinitionId("test".to_string())))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        let result: Result<(), _> = retry_internal_errors(&None, || async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(ApiDefinitionError::Internal("contention".to_string()))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    async fn deleting_missing_definition_if_exists_succeeds() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        let response = client.delete("/v1/api/definitions/missing/1.0.0").send().await;
+        response.assert_status(http::StatusCode::NOT_FOUND);
+
+        for version in ["1.0.0", "latest"] {
+            let response = client
+                .delete(format!("/v1/api/definitions/missing/{version}"))
+                .query("if_exists", &true)
+                .send()
+                .await;
+            response.assert_status_is_ok();
+            response
+                .assert_json("API definition not found, nothing to delete")
+                .await;
+        }
+    }
+
+    #[test]
+    async fn open_api_import_rejects_insecure_urls() {
+        let (api, _db) = make_route().await;
+        let client = TestClient::new(api);
+
+        for url in ["http://localhost/openapi.yaml", "file:///openapi.yaml", "not a url"] {
+            let response = client
+                .post("/v1/api/definitions/oas/from-url")
+                .body_json(&serde_json::json!({ "url": url }))
+                .send()
+                .await;
+
+            response.assert_status(http::StatusCode::BAD_REQUEST);
+            let body = response.json().await;
+            body.value()
+                .object()
+                .get("code")
+                .assert_string("api_definition.invalid_open_api_url");
+        }
+    }
+
+    #[test]
+    fn plain_http_open_api_urls_can_be_allowed() {
+        let url = Url::parse("http://localhost/openapi.yaml").unwrap();
+        assert!(!is_allowed_open_api_url(&url, false));
+        assert!(is_allowed_open_api_url(&url, true));
+
+        let url = Url::parse("https://localhost/openapi.yaml").unwrap();
+        assert!(is_allowed_open_api_url(&url, false));
+    }
 }
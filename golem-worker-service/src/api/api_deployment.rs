@@ -65,9 +65,10 @@ impl ApiDeploymentApi {
                 .instrument(record.span.clone())
                 .await?;
 
-            let deployment = data.ok_or(ApiEndpointError::internal(safe(
-                "Failed to verify the deployment".to_string(),
-            )))?;
+            let deployment = data.ok_or(ApiEndpointError::internal(
+                "api_deployment.internal",
+                safe("Failed to verify the deployment".to_string()),
+            ))?;
 
             Ok(Json(deployment.into()))
         };
@@ -115,9 +116,10 @@ impl ApiDeploymentApi {
                 .deployment_service
                 .get_by_site(&ApiSiteString(site))
                 .await?
-                .ok_or(ApiEndpointError::not_found(safe(
-                    "Api deployment not found".to_string(),
-                )))?;
+                .ok_or(ApiEndpointError::not_found(
+                    "api_deployment.not_found",
+                    safe("Api deployment not found".to_string()),
+                ))?;
 
             Ok(Json(value.into()))
         };
@@ -0,0 +1,154 @@
+use futures::StreamExt;
+use http::{header, Method, StatusCode};
+use poem::{Body, BoxEndpoint, Endpoint, EndpointExt, IntoEndpoint, Request, Response};
+
+// Only the API definition uploads are limited, they are buffered and parsed as a whole
+const LIMITED_PATH_PREFIX: &str = "/v1/api/definitions";
+
+const LIMITED_METHODS: [Method; 3] = [Method::POST, Method::PUT, Method::PATCH];
+
+/// Rejects the API definition write requests with a body larger than `max_size` bytes with
+/// `413 Payload Too Large`. Bodies declaring their size are rejected before being read, chunked
+/// ones as soon as they grow over the limit.
+pub fn with_body_limit(endpoint: impl IntoEndpoint, max_size: u64) -> BoxEndpoint<'static> {
+    endpoint
+        .into_endpoint()
+        .map_to_response()
+        .around(move |endpoint, mut request: Request| async move {
+            let is_limited = request.uri().path().starts_with(LIMITED_PATH_PREFIX)
+                && LIMITED_METHODS.contains(request.method());
+
+            if is_limited {
+                let declared_size = request
+                    .header(header::CONTENT_LENGTH)
+                    .and_then(|length| length.parse::<u64>().ok());
+
+                match declared_size {
+                    Some(size) if size > max_size => return Ok(payload_too_large(max_size)),
+                    Some(_) => {}
+                    None => match read_limited(request.take_body(), max_size).await? {
+                        Some(data) => request.set_body(data),
+                        None => return Ok(payload_too_large(max_size)),
+                    },
+                }
+            }
+
+            endpoint.call(request).await
+        })
+        .boxed()
+}
+
+// Returns None if the body is larger than `max_size`, without reading the rest of it
+async fn read_limited(body: Body, max_size: u64) -> poem::Result<Option<Vec<u8>>> {
+    let mut stream = std::pin::pin!(body.into_bytes_stream());
+    let mut data = Vec::new();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(poem::error::BadRequest)?;
+        if (data.len() + chunk.len()) as u64 > max_size {
+            return Ok(None);
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    Ok(Some(data))
+}
+
+fn payload_too_large(max_size: u64) -> Response {
+    Response::builder()
+        .status(StatusCode::PAYLOAD_TOO_LARGE)
+        .body(format!("Request body is over the limit of {max_size} bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use bytes::Bytes;
+    use futures::stream;
+    use poem::test::TestClient;
+    use poem::{handler, post, Route};
+
+    const MAX_SIZE: u64 = 16;
+
+    #[handler]
+    fn size(body: Vec<u8>) -> String {
+        body.len().to_string()
+    }
+
+    fn client() -> TestClient<BoxEndpoint<'static>> {
+        TestClient::new(with_body_limit(
+            Route::new()
+                .at("/v1/api/definitions", post(size).put(size).get(size))
+                .at("/v1/components", post(size)),
+            MAX_SIZE,
+        ))
+    }
+
+    fn chunked(chunks: Vec<&'static str>) -> Body {
+        Body::from_bytes_stream(stream::iter(
+            chunks
+                .into_iter()
+                .map(|chunk| Ok::<_, std::io::Error>(Bytes::from_static(chunk.as_bytes()))),
+        ))
+    }
+
+    #[test]
+    async fn bodies_within_the_limit_are_accepted() {
+        let response = client()
+            .post("/v1/api/definitions")
+            .header(header::CONTENT_LENGTH, "16")
+            .body("a".repeat(16))
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        response.assert_text("16").await;
+
+        let response = client()
+            .put("/v1/api/definitions")
+            .body(chunked(vec!["aaaaaaaa", "aaaaaaaa"]))
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        response.assert_text("16").await;
+    }
+
+    #[test]
+    async fn declared_size_over_the_limit_is_rejected() {
+        let response = client()
+            .post("/v1/api/definitions")
+            .header(header::CONTENT_LENGTH, "17")
+            .body("a".repeat(17))
+            .send()
+            .await;
+        response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    async fn chunked_body_over_the_limit_is_rejected() {
+        let response = client()
+            .put("/v1/api/definitions")
+            .body(chunked(vec!["aaaaaaaa", "aaaaaaaa", "a"]))
+            .send()
+            .await;
+        response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    async fn other_requests_are_not_limited() {
+        let response = client()
+            .post("/v1/components")
+            .body("a".repeat(32))
+            .send()
+            .await;
+        response.assert_status_is_ok();
+
+        let response = client()
+            .get("/v1/api/definitions")
+            .body("a".repeat(32))
+            .send()
+            .await;
+        response.assert_status_is_ok();
+    }
+}
@@ -0,0 +1,205 @@
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use http::{header, HeaderValue};
+use poem::{Body, BoxEndpoint, Endpoint, EndpointExt, IntoEndpoint, Request, Response};
+use std::io::Write;
+
+// Only the API definition responses are compressed, as some other endpoints stream their bodies
+const COMPRESSED_PATH_PREFIX: &str = "/v1/api/definitions";
+
+// Compressing smaller bodies saves next to nothing
+const MIN_COMPRESSED_SIZE: usize = 1024;
+
+// Streamed bodies never end on their own, so they can't be buffered for compression
+const STREAMED_CONTENT_TYPES: [&str; 2] = ["application/x-ndjson", "text/event-stream"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentEncoding {
+    Gzip,
+    Deflate,
+}
+
+impl ContentEncoding {
+    fn name(&self) -> &'static str {
+        match self {
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Deflate => "deflate",
+        }
+    }
+
+    fn encode(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            ContentEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            ContentEncoding::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+
+    // Prefers gzip when both are accepted, and ignores the encodings accepted with `q=0`
+    fn from_accept_encoding(accept_encoding: &str) -> Option<Self> {
+        let accepted: Vec<&str> = accept_encoding
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';').map(str::trim);
+                let name = parts.next()?;
+                let rejected = parts.any(|param| {
+                    param
+                        .strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .is_some_and(|q| q == 0.0)
+                });
+                (!rejected).then_some(name)
+            })
+            .collect();
+
+        [ContentEncoding::Gzip, ContentEncoding::Deflate]
+            .into_iter()
+            .find(|encoding| {
+                accepted
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(encoding.name()))
+            })
+    }
+}
+
+/// Compresses the API definition responses with gzip or deflate, if the client accepts any of
+/// them. Small and already encoded bodies, and streamed NDJSON and event stream responses are sent
+/// as they are.
+pub fn with_compression(endpoint: impl IntoEndpoint) -> BoxEndpoint<'static> {
+    endpoint
+        .into_endpoint()
+        .map_to_response()
+        .around(|endpoint, request: Request| async move {
+            let encoding = if request.uri().path().starts_with(COMPRESSED_PATH_PREFIX) {
+                request
+                    .header(header::ACCEPT_ENCODING)
+                    .and_then(ContentEncoding::from_accept_encoding)
+            } else {
+                None
+            };
+
+            let response = endpoint.call(request).await?;
+            match encoding {
+                Some(encoding) => compress(response, encoding).await,
+                None => Ok(response),
+            }
+        })
+        .boxed()
+}
+
+async fn compress(response: Response, encoding: ContentEncoding) -> poem::Result<Response> {
+    let is_streamed = response.content_type().is_some_and(|content_type| {
+        STREAMED_CONTENT_TYPES
+            .iter()
+            .any(|streamed| content_type.starts_with(streamed))
+    });
+    if is_streamed || response.headers().contains_key(header::CONTENT_ENCODING) {
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let data = body.into_bytes().await?;
+    if data.len() < MIN_COMPRESSED_SIZE {
+        return Ok(Response::from_parts(parts, Body::from(data)));
+    }
+
+    let compressed = encoding
+        .encode(&data)
+        .map_err(poem::error::InternalServerError)?;
+
+    let mut response = Response::from_parts(parts, Body::from(compressed));
+    let headers = response.headers_mut();
+    headers.remove(header::CONTENT_LENGTH);
+    headers.insert(
+        header::CONTENT_ENCODING,
+        HeaderValue::from_static(encoding.name()),
+    );
+    headers.append(header::VARY, HeaderValue::from_static("accept-encoding"));
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use flate2::read::GzDecoder;
+    use poem::test::TestClient;
+    use poem::{handler, Route};
+    use std::io::Read;
+
+    #[handler]
+    fn large() -> String {
+        "definition".repeat(200)
+    }
+
+    #[handler]
+    fn small() -> &'static str {
+        "definition"
+    }
+
+    fn client() -> TestClient<BoxEndpoint<'static>> {
+        TestClient::new(with_compression(
+            Route::new()
+                .at("/v1/api/definitions/large", large)
+                .at("/v1/api/definitions/small", small)
+                .at("/v1/components/large", large),
+        ))
+    }
+
+    #[test]
+    async fn large_responses_are_compressed() {
+        let response = client()
+            .get("/v1/api/definitions/large")
+            .header(header::ACCEPT_ENCODING, "deflate, gzip;q=0.8")
+            .send()
+            .await;
+
+        response.assert_header(header::CONTENT_ENCODING, "gzip");
+        let compressed = response.0.into_body().into_vec().await.unwrap();
+        let mut decompressed = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, "definition".repeat(200));
+    }
+
+    #[test]
+    async fn responses_are_not_compressed_unless_accepted() {
+        let response = client().get("/v1/api/definitions/large").send().await;
+        response.assert_header_is_not_exist(header::CONTENT_ENCODING);
+        response.assert_text("definition".repeat(200)).await;
+
+        let response = client()
+            .get("/v1/api/definitions/large")
+            .header(header::ACCEPT_ENCODING, "gzip;q=0, br")
+            .send()
+            .await;
+        response.assert_header_is_not_exist(header::CONTENT_ENCODING);
+    }
+
+    #[test]
+    async fn small_and_other_responses_are_not_compressed() {
+        let response = client()
+            .get("/v1/api/definitions/small")
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .send()
+            .await;
+        response.assert_header_is_not_exist(header::CONTENT_ENCODING);
+        response.assert_text("definition").await;
+
+        let response = client()
+            .get("/v1/components/large")
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .send()
+            .await;
+        response.assert_header_is_not_exist(header::CONTENT_ENCODING);
+    }
+}
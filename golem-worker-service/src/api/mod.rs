@@ -1,15 +1,21 @@
 pub mod api_definition;
 pub mod api_deployment;
+mod body_limit;
+mod compression;
 mod security_scheme;
 pub mod worker;
 pub mod worker_connect;
 
+use crate::api::body_limit::with_body_limit;
+use crate::api::compression::with_compression;
 use crate::api::worker::WorkerApi;
 use crate::service::Services;
 use golem_worker_service_base::api::CustomHttpRequestApi;
 use golem_worker_service_base::api::HealthcheckApi;
+use golem_worker_service_base::app_config::ApiCorsConfig;
 use poem::endpoint::PrometheusExporter;
-use poem::{get, EndpointExt, Route};
+use poem::middleware::Cors;
+use poem::{get, BoxEndpoint, EndpointExt, IntoEndpoint, Route};
 use poem_openapi::OpenApiService;
 use prometheus::Registry;
 
@@ -21,11 +27,19 @@ pub type ApiServices = (
     HealthcheckApi,
 );
 
-pub fn combined_routes(prometheus_registry: Registry, services: &Services) -> Route {
+pub fn combined_routes(
+    prometheus_registry: Registry,
+    services: &Services,
+    cors: &Option<ApiCorsConfig>,
+    max_api_definition_body_size: u64,
+) -> Route {
     let api_service = make_open_api_service(services);
 
     let ui = api_service.swagger_ui();
     let spec = api_service.spec_endpoint_yaml();
+    let api_service = with_compression(api_service);
+    let api_service = with_body_limit(api_service, max_api_definition_body_size);
+    let api_service = with_cors(api_service, cors);
     let metrics = PrometheusExporter::new(prometheus_registry.clone());
 
     let connect_services = worker_connect::ConnectService::new(services.worker_service.clone());
@@ -41,6 +55,22 @@ pub fn combined_routes(prometheus_registry: Registry, services: &Services) -> Ro
         )
 }
 
+// CORS is disabled unless configured, so browsers can only call the API from the same origin
+fn with_cors(endpoint: impl IntoEndpoint, cors: &Option<ApiCorsConfig>) -> BoxEndpoint<'static> {
+    let endpoint = endpoint.into_endpoint().map_to_response();
+    match cors {
+        Some(cors) => endpoint
+            .with(
+                Cors::new()
+                    .allow_origins(cors.allowed_origins.iter().map(String::as_str))
+                    .allow_methods(cors.allowed_methods.iter().map(String::as_str))
+                    .allow_headers(cors.allowed_headers.iter().map(String::as_str)),
+            )
+            .boxed(),
+        None => endpoint.boxed(),
+    }
+}
+
 pub fn custom_request_route(services: &Services) -> Route {
     let custom_request_executor = CustomHttpRequestApi::new(
         services.worker_to_http_service.clone(),
@@ -59,7 +89,16 @@ pub fn make_open_api_service(services: &Services) -> OpenApiService<ApiServices,
                 component_service: services.component_service.clone(),
                 worker_service: services.worker_service.clone(),
             },
-            api_definition::RegisterApiDefinitionApi::new(services.definition_service.clone()),
+            api_definition::RegisterApiDefinitionApi::new(
+                services.definition_service.clone(),
+                services.audit_sink.clone(),
+                services.registration_rate_limiter.clone(),
+                services.registration_retries.clone(),
+                services.worker_to_http_service.clone(),
+                services.fileserver_binding_handler.clone(),
+                services.gateway_session_store.clone(),
+            )
+            .with_insecure_open_api_urls(services.allow_insecure_open_api_urls),
             api_deployment::ApiDeploymentApi::new(services.deployment_service.clone()),
             security_scheme::SecuritySchemeApi::new(services.security_scheme_service.clone()),
             HealthcheckApi,
@@ -68,3 +107,64 @@ pub fn make_open_api_service(services: &Services) -> OpenApiService<ApiServices,
         "1.0",
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use http::{header, Method, StatusCode};
+    use poem::handler;
+    use poem::test::TestClient;
+
+    #[handler]
+    fn index() -> &'static str {
+        "ok"
+    }
+
+    #[test]
+    async fn cors_is_disabled_by_default() {
+        let client = TestClient::new(with_cors(Route::new().at("/", index), &None));
+
+        let response = client
+            .options("/")
+            .header(header::ORIGIN, "https://admin.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+            .send()
+            .await;
+
+        response.assert_header_is_not_exist(header::ACCESS_CONTROL_ALLOW_ORIGIN);
+    }
+
+    #[test]
+    async fn cors_preflight_allowed_when_configured() {
+        let cors = ApiCorsConfig {
+            allowed_origins: vec!["https://admin.example.com".to_string()],
+            allowed_methods: vec![Method::PUT.to_string()],
+            allowed_headers: vec![],
+        };
+        let client = TestClient::new(with_cors(Route::new().at("/", index), &Some(cors)));
+
+        let response = client
+            .options("/")
+            .header(header::ORIGIN, "https://admin.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+            .send()
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        response.assert_header(
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            "https://admin.example.com",
+        );
+
+        let response = client
+            .options("/")
+            .header(header::ORIGIN, "https://other.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+            .send()
+            .await;
+
+        response.assert_header_is_not_exist(header::ACCESS_CONTROL_ALLOW_ORIGIN);
+    }
+}
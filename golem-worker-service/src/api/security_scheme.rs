@@ -66,7 +66,10 @@ impl SecuritySchemeApi {
             security_scheme_identifier = payload.0.scheme_identifier
         );
         let security_scheme = SecurityScheme::try_from(payload.0).map_err(|err| {
-            ApiEndpointError::bad_request(safe(format!("Invalid security scheme {}", err)))
+            ApiEndpointError::bad_request(
+                "security_scheme.invalid",
+                safe(format!("Invalid security scheme {}", err)),
+            )
         })?;
 
         let security_scheme_with_metadata = self
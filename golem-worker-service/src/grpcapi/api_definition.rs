@@ -263,9 +263,10 @@ impl GrpcApiDefinitionService {
                 definition.clone().try_into().map_err(bad_request)?
             }
             create_api_definition_request::ApiDefinition::Openapi(definition) => {
-                let value = OpenApiHttpApiDefinitionRequest(
+                let value = OpenApiHttpApiDefinitionRequest::from_json_value(
                     serde_json::from_str(&definition).map_err(|_| bad_request("Invalid JSON"))?,
-                );
+                )
+                .map_err(bad_request)?;
 
                 value
                     .to_http_api_definition_request()
@@ -305,9 +306,10 @@ impl GrpcApiDefinitionService {
                 definition.clone().try_into().map_err(bad_request)?
             }
             update_api_definition_request::ApiDefinition::Openapi(definition) => {
-                let value = OpenApiHttpApiDefinitionRequest(
+                let value = OpenApiHttpApiDefinitionRequest::from_json_value(
                     serde_json::from_str(&definition).map_err(|_| bad_request("Invalid JSON"))?,
-                );
+                )
+                .map_err(bad_request)?;
 
                 value
                     .to_http_api_definition_request()
@@ -319,6 +321,7 @@ impl GrpcApiDefinitionService {
             .definition_service
             .update(
                 &internal_definition,
+                None,
                 &DefaultNamespace::default(),
                 &EmptyAuthCtx::default(),
             )
@@ -417,7 +420,8 @@ impl GrpcApiDefinitionService {
         let api_definition_id = get_api_definition_id(request.api_definition_id)?;
         let version = ApiVersion(request.version);
 
-        self.definition_service
+        let deleted = self
+            .definition_service
             .delete(
                 &api_definition_id,
                 &version,
@@ -426,7 +430,14 @@ impl GrpcApiDefinitionService {
             )
             .await?;
 
-        Ok(())
+        if deleted {
+            Ok(())
+        } else {
+            Err(not_found(format!(
+                "Api Definition with id: {} and version: {} not found",
+                api_definition_id.0, version.0
+            )))
+        }
     }
 }
 
@@ -19,12 +19,13 @@ use futures_util::TryFutureExt;
 use golem_api_grpc::proto;
 use golem_api_grpc::proto::golem::apidefinition::v1::api_definition_service_server::ApiDefinitionServiceServer;
 use golem_api_grpc::proto::golem::worker::v1::worker_service_server::WorkerServiceServer;
+use golem_worker_service_base::app_config::GrpcTlsConfig;
 use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tokio::task::JoinSet;
 use tokio_stream::wrappers::TcpListenerStream;
 use tonic::codec::CompressionEncoding;
-use tonic::transport::Server;
+use tonic::transport::{Identity, Server, ServerTlsConfig};
 use tracing::Instrument;
 
 mod api_definition;
@@ -32,6 +33,7 @@ mod worker;
 
 pub async fn start_grpc_server(
     addr: SocketAddr,
+    tls: Option<GrpcTlsConfig>,
     services: Services,
     join_set: &mut JoinSet<Result<(), anyhow::Error>>,
 ) -> anyhow::Result<u16> {
@@ -53,9 +55,16 @@ pub async fn start_grpc_server(
         .build_v1()
         .unwrap();
 
+    let mut server = Server::builder();
+    if let Some(tls) = tls {
+        let cert = std::fs::read(&tls.cert_path)?;
+        let key = std::fs::read(&tls.key_path)?;
+        server = server.tls_config(ServerTlsConfig::new().identity(Identity::from_pem(cert, key)))?;
+    }
+
     join_set.spawn(
         async move {
-            Server::builder()
+            server
                 .add_service(reflection_service)
                 .add_service(health_service)
                 .add_service(
@@ -17,7 +17,7 @@ use anyhow::{anyhow, Context};
 use golem_common::config::DbConfig;
 use golem_service_base::db;
 use golem_service_base::migration::Migrations;
-use golem_worker_service_base::app_config::WorkerServiceBaseConfig;
+use golem_worker_service_base::app_config::{ApiCorsConfig, WorkerServiceBaseConfig};
 use poem::listener::Acceptor;
 use poem::listener::Listener;
 use poem::middleware::{OpenTelemetryMetrics, Tracing};
@@ -79,6 +79,12 @@ impl WorkerService {
         })
     }
 
+    /// Enables CORS on the management HTTP API, overriding the configured settings
+    pub fn with_cors(mut self, cors: ApiCorsConfig) -> Self {
+        self.config.api_cors = Some(cors);
+        self
+    }
+
     pub async fn run(
         &self,
         join_set: &mut JoinSet<anyhow::Result<()>>,
@@ -104,6 +110,7 @@ impl WorkerService {
     ) -> Result<u16, anyhow::Error> {
         grpcapi::start_grpc_server(
             SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), self.config.worker_grpc_port).into(),
+            self.config.worker_grpc_tls.clone(),
             self.services.clone(),
             join_set,
         )
@@ -116,9 +123,14 @@ impl WorkerService {
         join_set: &mut JoinSet<anyhow::Result<()>>,
     ) -> Result<u16, anyhow::Error> {
         let prometheus_registry = self.prometheus_registry.clone();
-        let app = api::combined_routes(prometheus_registry, &self.services)
-            .with(OpenTelemetryMetrics::new())
-            .with(Tracing);
+        let app = api::combined_routes(
+            prometheus_registry,
+            &self.services,
+            &self.config.api_cors,
+            self.config.max_api_definition_body_size,
+        )
+        .with(OpenTelemetryMetrics::new())
+        .with(Tracing);
 
         let poem_listener =
             poem::listener::TcpListener::bind(format!("0.0.0.0:{}", self.config.port));
@@ -42,6 +42,12 @@ use golem_worker_service_base::service::gateway::api_definition::{
     ApiDefinitionService, ApiDefinitionServiceDefault,
 };
 use golem_worker_service_base::service::gateway::api_definition_validator::ApiDefinitionValidatorService;
+use golem_worker_service_base::service::gateway::audit::{
+    AuditSink, BroadcastAuditSink, TracingAuditSink,
+};
+use golem_worker_service_base::service::gateway::rate_limit::{
+    InMemoryRateLimiter, RateLimiter, UnlimitedRateLimiter,
+};
 use golem_worker_service_base::service::gateway::http_api_definition_validator::HttpApiDefinitionValidator;
 use golem_worker_service_base::service::worker::WorkerServiceDefault;
 
@@ -92,6 +98,10 @@ pub struct Services {
         Arc<dyn ApiDefinitionValidatorService<HttpApiDefinition> + Sync + Send>,
     pub fileserver_binding_handler:
         Arc<dyn FileServerBindingHandler<DefaultNamespace> + Sync + Send>,
+    pub audit_sink: Arc<dyn AuditSink + Sync + Send>,
+    pub registration_rate_limiter: Arc<dyn RateLimiter + Sync + Send>,
+    pub registration_retries: Option<RetryConfig>,
+    pub allow_insecure_open_api_urls: bool,
 }
 
 impl Services {
@@ -271,7 +281,9 @@ impl Services {
             api_deployment_repo.clone(),
             security_scheme_service.clone(),
             api_definition_validator_service.clone(),
-        ));
+            config.max_api_definitions,
+        )
+        .with_reservation_ttl(config.api_definition_reservation_ttl));
 
         let deployment_service: Arc<
             dyn ApiDeploymentService<EmptyAuthCtx, DefaultNamespace> + Sync + Send,
@@ -284,6 +296,14 @@ impl Services {
         let http_definition_lookup_service =
             Arc::new(HttpApiDefinitionLookup::new(deployment_service.clone()));
 
+        let audit_sink = Arc::new(BroadcastAuditSink::new(TracingAuditSink));
+
+        let registration_rate_limiter: Arc<dyn RateLimiter + Sync + Send> =
+            match &config.registration_rate_limit {
+                Some(rate_limit) => Arc::new(InMemoryRateLimiter::new(rate_limit.clone())),
+                None => Arc::new(UnlimitedRateLimiter),
+            };
+
         Ok(Services {
             worker_service,
             definition_service,
@@ -295,6 +315,10 @@ impl Services {
             api_definition_validator_service,
             fileserver_binding_handler,
             gateway_session_store,
+            audit_sink,
+            registration_rate_limiter,
+            registration_retries: config.registration_retries.clone(),
+            allow_insecure_open_api_urls: config.allow_insecure_open_api_urls,
         })
     }
 }
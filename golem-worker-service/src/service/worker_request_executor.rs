@@ -12,18 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use golem_common::model::TargetWorkerId;
+use golem_common::model::{ComponentVersion, TargetWorkerId, WorkerId};
 use golem_service_base::auth::DefaultNamespace;
-use golem_service_base::model::validate_worker_name;
+use golem_service_base::model::{validate_worker_name, GolemError};
 use golem_worker_service_base::empty_worker_metadata;
 use golem_worker_service_base::gateway_execution::{
     GatewayResolvedWorkerRequest, GatewayWorkerRequestExecutor, WorkerRequestExecutorError,
     WorkerResponse,
 };
-use golem_worker_service_base::service::worker::WorkerService;
+use golem_worker_service_base::service::worker::{WorkerService, WorkerServiceError};
 use tracing::{debug, info};
 
 // The open source deviates from the proprietary codebase here, only in terms of authorisation
@@ -35,6 +36,42 @@ impl UnauthorisedWorkerRequestExecutor {
     pub fn new(worker_service: Arc<dyn WorkerService + Sync + Send>) -> Self {
         Self { worker_service }
     }
+
+    // Creates the worker at the pinned component version if it doesn't exist yet, and refuses
+    // to invoke it if it already runs another version
+    async fn ensure_component_version(
+        &self,
+        worker_id: &WorkerId,
+        component_version: ComponentVersion,
+    ) -> Result<(), WorkerRequestExecutorError> {
+        match self
+            .worker_service
+            .get_metadata(worker_id, empty_worker_metadata())
+            .await
+        {
+            Ok(metadata) if metadata.component_version == component_version => Ok(()),
+            Ok(metadata) => Err(format!(
+                "Worker {} runs component version {}, but the binding is pinned to version {}",
+                worker_id, metadata.component_version, component_version
+            )
+            .into()),
+            Err(WorkerServiceError::WorkerNotFound(_))
+            | Err(WorkerServiceError::Golem(GolemError::WorkerNotFound(_))) => {
+                self.worker_service
+                    .create(
+                        worker_id,
+                        component_version,
+                        vec![],
+                        HashMap::new(),
+                        empty_worker_metadata(),
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(())
+            }
+            Err(err) => Err(err.to_string().into()),
+        }
+    }
 }
 
 #[async_trait]
@@ -55,6 +92,19 @@ impl GatewayWorkerRequestExecutor<DefaultNamespace> for UnauthorisedWorkerReques
             worker_name: worker_name_opt_validated.clone(),
         };
 
+        // Bindings are only pinned if they have a worker name
+        if let (Some(component_version), Some(worker_name)) = (
+            worker_request_params.component_version,
+            &worker_name_opt_validated,
+        ) {
+            let worker_id = WorkerId {
+                component_id: component_id.clone(),
+                worker_name: worker_name.clone(),
+            };
+            self.ensure_component_version(&worker_id, component_version)
+                .await?;
+        }
+
         info!(
             "Executing request for component: {}, worker: {}, function: {:?}",
             component_id,
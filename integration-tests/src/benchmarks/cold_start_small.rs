@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use async_trait::async_trait;
-use golem_test_framework::config::{CliParams, TestDependencies};
+use golem_test_framework::config::{CliParams, Teardown};
 use golem_test_framework::dsl::benchmark::{Benchmark, BenchmarkRecorder, RunConfig};
 use golem_wasm_rpc::Value;
 use integration_tests::benchmarks::{
@@ -43,7 +43,7 @@ impl Benchmark for ColdStartEchoSmall {
     }
 
     async fn cleanup(benchmark_context: Self::BenchmarkContext) {
-        benchmark_context.deps.kill_all().await
+        benchmark_context.deps.shutdown().await
     }
 
     async fn create(params: CliParams, config: RunConfig) -> Self {
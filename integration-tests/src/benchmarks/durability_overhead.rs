@@ -16,7 +16,7 @@ use async_trait::async_trait;
 use golem_wasm_rpc::Value;
 
 use golem_common::model::WorkerId;
-use golem_test_framework::config::{CliParams, TestDependencies};
+use golem_test_framework::config::{CliParams, Teardown};
 use golem_test_framework::dsl::benchmark::{Benchmark, BenchmarkRecorder, RunConfig};
 use golem_test_framework::dsl::TestDsl;
 use integration_tests::benchmarks::{
@@ -54,7 +54,7 @@ impl Benchmark for DurabilityOverhead {
     }
 
     async fn cleanup(benchmark_context: Self::BenchmarkContext) {
-        benchmark_context.deps.kill_all().await
+        benchmark_context.deps.shutdown().await
     }
 
     async fn create(_params: CliParams, config: RunConfig) -> Self {
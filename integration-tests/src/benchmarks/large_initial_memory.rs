@@ -14,7 +14,7 @@
 
 use async_trait::async_trait;
 
-use golem_test_framework::config::{CliParams, TestDependencies};
+use golem_test_framework::config::{CliParams, Teardown};
 use golem_test_framework::dsl::benchmark::{Benchmark, BenchmarkRecorder, RunConfig};
 use integration_tests::benchmarks::{
     benchmark_invocations, delete_workers, invoke_and_await, run_benchmark, setup_benchmark,
@@ -42,7 +42,7 @@ impl Benchmark for LargeInitialMemory {
     }
 
     async fn cleanup(benchmark_context: Self::BenchmarkContext) {
-        benchmark_context.deps.kill_all().await
+        benchmark_context.deps.shutdown().await
     }
 
     async fn create(_params: CliParams, config: RunConfig) -> Self {
@@ -21,7 +21,7 @@ use tokio::task::JoinSet;
 
 use golem_common::model::WorkerId;
 use golem_test_framework::config::{
-    CliParams, CliTestDependencies, CliTestService, TestDependencies, TestService,
+    CliParams, CliTestDependencies, CliTestService, Teardown, TestService,
 };
 use golem_test_framework::dsl::benchmark::{Benchmark, BenchmarkRecorder, RunConfig};
 use integration_tests::benchmarks::{
@@ -76,7 +76,7 @@ impl Benchmark for ThroughputCpuIntensive {
     }
 
     async fn cleanup(benchmark_context: Self::BenchmarkContext) {
-        benchmark_context.deps.kill_all().await;
+        benchmark_context.deps.shutdown().await;
         benchmark_context.rust_service.kill_all();
     }
 
@@ -439,6 +439,12 @@ mod tests {
             while let Some(result) = tasks.join_next().await {
                 result.unwrap()
             }
+
+            let health = self.worker_executor_cluster().health().await;
+            assert!(
+                health.all_started_healthy(),
+                "Worker executors are not healthy after starting them: {health:?}"
+            );
         }
 
         async fn stop_random_worker_executor(&self) {